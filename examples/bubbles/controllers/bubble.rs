@@ -1,20 +1,21 @@
 use crate::{
-    qp_assets::RCamera2D,
+    qp_assets::{Handle, RCamera2D},
     qp_core::{now_secs, random::Random},
     qp_ecs::components::{CQuad, CTag, CTransform2D, CVelocity2D},
+    qp_gfx::Viewport,
     qp_schemas::SchemaSprite,
-    Controller, FrameResult, QPError, Schema, VersionedIndex, World,
+    Controller, Delta, Events, FrameResult, QPError, Schema, VersionedIndex, World,
 };
 use sdl2::{event::Event, keyboard::Keycode};
 
 pub struct BubbleController {
     bubbles: Vec<VersionedIndex>,
-    camera: u64,
+    camera: Handle<RCamera2D>,
     rand: Random,
 }
 
 impl BubbleController {
-    pub fn new(world: &mut World, camera: u64) -> Result<Self, QPError> {
+    pub fn new(world: &mut World, camera: Handle<RCamera2D>) -> Result<Self, QPError> {
         let mut bubbles = world.registry.entity_manager.query(CTag {
             tag: "sprite".to_string(),
         });
@@ -35,35 +36,31 @@ impl BubbleController {
 
 impl Controller for BubbleController {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        if world.debug_mode {
-            return FrameResult::None;
-        }
-
         // handle input
-        for event in world.events.iter() {
-            match event {
+        let spawn_requested = world.resource::<Events>().iter().any(|event| {
+            matches!(
+                event,
                 Event::KeyDown {
                     keycode: Some(Keycode::Space),
                     ..
-                } => {
-                    match spawn(&mut self.rand, world) {
-                        Ok(index) => self.bubbles.push(index),
-                        Err(_e) => {
-                            #[cfg(debug_assertions)]
-                            println!(
-                                "[bubble controller] there was a problem spawning a bubble: {}",
-                                _e.to_string()
-                            );
-                        }
-                    }
-
-                    break;
                 }
-                _ => (),
-            };
+            )
+        });
+
+        if spawn_requested {
+            match spawn(&mut self.rand, world) {
+                Ok(index) => self.bubbles.push(index),
+                Err(_e) => {
+                    #[cfg(debug_assertions)]
+                    println!(
+                        "[bubble controller] there was a problem spawning a bubble: {}",
+                        _e.to_string()
+                    );
+                }
+            }
         }
 
-        update(&self.bubbles, world, self.camera);
+        update(world, &self.camera);
 
         FrameResult::None
     }
@@ -72,7 +69,7 @@ impl Controller for BubbleController {
 fn spawn(rand: &mut Random, world: &mut World) -> Result<VersionedIndex, QPError> {
     let mut this_schema = SchemaSprite::default();
 
-    let (_x, _y, width, height) = world.viewport.get_dimensions();
+    let (_x, _y, width, height) = world.resource::<Viewport>().get_dimensions();
 
     let vel = (rand.range(-200, 200) as f32, rand.range(-200, 200) as f32);
     let color = glm::vec4(rand.random(), rand.random(), rand.random(), 1.0);
@@ -105,41 +102,28 @@ fn spawn(rand: &mut Random, world: &mut World) -> Result<VersionedIndex, QPError
     Ok(id)
 }
 
-pub fn update(bubbles: &[VersionedIndex], world: &mut World, camera: u64) {
-    let Some(camera) = world.registry.asset_manager.get::<RCamera2D>(camera) else {
+pub fn update(world: &mut World, camera: &Handle<RCamera2D>) {
+    let (_x, _y, width, height) = world.resource::<Viewport>().get_dimensions();
+    let delta = world.resource::<Delta>().0;
+
+    let Some(camera) = world.registry.asset_manager.get(camera) else {
         println!("couldn't find camera");
 
         return;
     };
 
-    let (_x, _y, width, height) = world.viewport.get_dimensions();
-
-    for bubble in bubbles {
-        let Some(vel) = world.registry.entity_manager.get::<CVelocity2D>(&bubble) else {
-            continue;
-        };
-        let Some(transform) = world.registry.entity_manager.get::<CTransform2D>(&bubble) else {
-            continue;
-        };
-        let Some(quad) = world.registry.entity_manager.get::<CQuad>(&bubble) else {
-            continue;
-        };
-
+    for (_, vel, transform, quad) in world
+        .registry
+        .entity_manager
+        .query_iter::<(&mut CVelocity2D, &mut CTransform2D, &CQuad)>()
+    {
         let scale = transform.scale;
 
-        let vel = glm::vec2(vel.x, vel.y);
-        let translate = transform.translate + (vel * world.delta);
+        let translate = transform.translate + (glm::vec2(vel.x, vel.y) * delta);
         let w = quad.width * scale.x;
         let h = quad.height * scale.y;
         let (colided_x, colided_y) = check_screen_collision(&camera, translate, w, h);
 
-        let Some(transform) = world
-            .registry
-            .entity_manager
-            .get_mut::<CTransform2D>(&bubble)
-        else {
-            continue;
-        };
         match colided_x {
             -1 => transform.translate.x = 0.0 + w * 0.5,
             1 => transform.translate.x = width as f32 - w * 0.5,
@@ -151,13 +135,6 @@ pub fn update(bubbles: &[VersionedIndex], world: &mut World, camera: u64) {
             _ => transform.translate.y = translate.y,
         }
 
-        let Some(vel) = world
-            .registry
-            .entity_manager
-            .get_mut::<CVelocity2D>(&bubble)
-        else {
-            continue;
-        };
         if colided_x != 0 {
             vel.x *= -1.0
         }
@@ -1,7 +1,11 @@
-use crate::{qp_assets::RCamera2D, qp_schemas::SchemaCamera2D, GlobalRegistry};
+use crate::{
+    qp_assets::{Handle, RCamera2D},
+    qp_schemas::SchemaCamera2D,
+    GlobalRegistry,
+};
 use quipi::{
     app::{Controller, FrameResult},
-    prelude::QPError,
+    prelude::{qp_gfx::Viewport, Delta, Events, QPError},
     world::World,
 };
 use sdl2::event::{Event, WindowEvent};
@@ -9,12 +13,12 @@ use sdl2::event::{Event, WindowEvent};
 pub const MAIN_CAMERA: &str = "main_camera";
 
 pub struct CameraController {
-    pub camera: u64,
+    pub camera: Handle<RCamera2D>,
 }
 
 impl CameraController {
     pub fn new(registry: &mut GlobalRegistry) -> Result<Self, QPError> {
-        let Some(camera) = registry.asset_manager.get_asset_id(MAIN_CAMERA) else {
+        let Some(camera) = registry.asset_manager.handle(MAIN_CAMERA) else {
             return Err(QPError::Generic(
                 "[camera controller] camera resource has not been loaded".to_string(),
             ));
@@ -26,18 +30,21 @@ impl CameraController {
 
 impl Controller for CameraController {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        for event in world.events.iter() {
+        let events = world.resource::<Events>().clone();
+        let delta = world.resource::<Delta>().0;
+
+        for event in events.iter() {
             match event {
                 Event::Window {
                     win_event: WindowEvent::Resized(w, h),
                     ..
                 } => {
-                    world.viewport.set_dimensions(0, 0, *w, *h);
+                    world.resource_mut::<Viewport>().set_dimensions(0, 0, *w, *h);
 
                     if let Some(camera) = world
                         .registry
                         .asset_manager
-                        .get_mut::<RCamera2D>(self.camera)
+                        .get_mut(&self.camera)
                     {
                         camera.params.right = *w as f32;
                         camera.params.top = *h as f32;
@@ -50,9 +57,9 @@ impl Controller for CameraController {
                     if let Some(camera) = world
                         .registry
                         .asset_manager
-                        .get_mut::<RCamera2D>(self.camera)
+                        .get_mut(&self.camera)
                     {
-                        camera.set_zoom(camera.zoom + (*precise_y * world.delta * 7.0));
+                        camera.set_zoom(camera.zoom + (*precise_y * delta * 7.0));
                     }
                 }
                 _ => (),
@@ -2,14 +2,14 @@ use crate::{
     qp_gfx::ShaderUniforms,
     qp_gfx::SpriteRenderer,
     qp_schemas::{load_scene_2d, SchemaScene2D, SchemaShader, SchemaTexture},
-    App, GlobalRegistry, Schema,
+    App, GlobalRegistry, Schema, Stage,
 };
 use quipi::{
     app::{Controller, FrameResult},
     prelude::{
-        qp_assets::RFont,
-        qp_gfx::{QPText, QPTextStyle},
-        QPError,
+        qp_assets::{Handle, RFont},
+        qp_gfx::{QPText, QPTextStyle, Viewport},
+        DebugInfo, Events, QPError, TextBuffer,
     },
     world::World,
 };
@@ -29,14 +29,17 @@ impl SceneController {
         scene.build_entity(&mut app.world.registry)?;
 
         let camera_controller = CameraController::new(&mut app.world.registry)?;
-        let bubble_controller = BubbleController::new(&mut app.world, camera_controller.camera)?;
+        let bubble_controller = BubbleController::new(&mut app.world, camera_controller.camera.clone())?;
         let text_controller = DebugInfoText::new(&mut app.world.registry)?;
 
         let renderer = SpriteRenderer::new(&mut app.world.registry, "main_camera", "sprite")?;
 
-        app.register_controller(bubble_controller);
-        app.register_controller(camera_controller);
-        app.register_controller(text_controller);
+        app.register_controller(camera_controller).label("camera");
+        app.register_controller(bubble_controller)
+            .label("bubbles")
+            .after("camera")
+            .run_if(|world| !world.debug_mode);
+        app.add_controller(Stage::PostUpdate, text_controller);
 
         app.register_renderer(renderer);
 
@@ -46,7 +49,9 @@ impl SceneController {
 
 impl Controller for SceneController {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        for event in world.events.iter() {
+        let events = world.resource::<Events>().clone();
+
+        for event in events.iter() {
             match event {
                 Event::Quit { .. } => {
                     return FrameResult::Quit;
@@ -68,11 +73,13 @@ impl Controller for SceneController {
 }
 
 fn scene_schema(world: &World) -> SchemaScene2D {
-    let (_x, _y, width, height) = world.viewport.get_dimensions();
+    let (_x, _y, width, height) = world.resource::<Viewport>().get_dimensions();
     SchemaScene2D {
         name: "bubbles".to_string(),
         cameras: vec![camera_schema(width as f32, height as f32)],
         sprites: vec![],
+        entities: vec![],
+        prefabs: vec![],
         shaders: vec![SchemaShader {
             name: "sprite".to_string(),
             uniforms: vec![
@@ -88,14 +95,12 @@ fn scene_schema(world: &World) -> SchemaScene2D {
 }
 
 struct DebugInfoText {
-    font: u64,
+    font: Handle<RFont>,
 }
 
 impl DebugInfoText {
     pub fn new(registry: &mut GlobalRegistry) -> Result<Self, QPError> {
-        let font = registry
-            .asset_manager
-            .load_asset("Poppins-Regular", RFont::new("Poppins-Regular")?)?;
+        let font = RFont::load(&mut registry.asset_manager, "Poppins-Regular")?;
 
         Ok(Self { font })
     }
@@ -105,40 +110,28 @@ impl Controller for DebugInfoText {
     fn update(&mut self, world: &mut World) -> FrameResult {
         let entity_count = world.registry.entity_manager.count();
         let style = QPTextStyle {
-            font: self.font,
+            font: self.font.clone(),
             color: glm::vec4(0.1, 0.1, 0.1, 1.0),
             scale: 0.4,
         };
-        world.text_buffer.push(QPText {
-            text: format!("entities: {}", entity_count),
-            pos: glm::vec2(20.0, 20.0),
-            style: style.clone(),
-        });
-        world.text_buffer.push(QPText {
-            text: format!("draw calls: {}", world.debug_info.draw_calls),
-            pos: glm::vec2(20.0, 40.0),
-            style: style.clone(),
-        });
-        world.text_buffer.push(QPText {
-            text: format!("render ms: {}", world.debug_info.render_ms),
-            pos: glm::vec2(20.0, 60.0),
-            style: style.clone(),
-        });
-        world.text_buffer.push(QPText {
-            text: format!("controller ms: {}", world.debug_info.controller_ms),
-            pos: glm::vec2(20.0, 80.0),
-            style: style.clone(),
-        });
-        world.text_buffer.push(QPText {
-            text: format!("fps: {}", world.debug_info.fps as u32),
-            pos: glm::vec2(20.0, 100.0),
-            style: style.clone(),
-        });
-        world.text_buffer.push(QPText {
-            text: format!("ms: {}", world.debug_info.frame_ms as u32),
-            pos: glm::vec2(20.0, 120.0),
-            style: style.clone(),
-        });
+        let debug_info = world.resource::<DebugInfo>();
+        let lines = [
+            format!("entities: {}", entity_count),
+            format!("draw calls: {}", debug_info.draw_calls),
+            format!("render ms: {}", debug_info.render_ms),
+            format!("controller ms: {}", debug_info.controller_ms),
+            format!("fps: {}", debug_info.fps),
+            format!("ms: {}", debug_info.frame_ms),
+        ];
+
+        let text_buffer = world.resource_mut::<TextBuffer>();
+        for (i, text) in lines.into_iter().enumerate() {
+            text_buffer.push(QPText {
+                text,
+                pos: glm::vec2(20.0, 20.0 + i as f32 * 20.0),
+                style: style.clone(),
+            });
+        }
 
         FrameResult::None
     }
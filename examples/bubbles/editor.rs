@@ -3,7 +3,7 @@ mod debug;
 mod scene;
 
 use quipi::app::Renderer;
-use quipi::world::World;
+use quipi::{prelude::DebugInfo, world::World};
 use scene::SceneEditor;
 
 use crate::editor::debug::DebugUi;
@@ -40,7 +40,7 @@ impl Renderer for AppEditor {
 
         self.gui.update(world);
 
-        world.debug_info.editor_ms = (self.timer.delta() * 1000.0) as u32;
+        world.resource_mut::<DebugInfo>().editor_ms = (self.timer.delta() * 1000.0) as u32;
 
         None
     }
@@ -2,45 +2,43 @@ use egui::{Context, Ui};
 use quipi::world::World;
 
 use crate::{
-    qp_ecs::components::{CQuad, CTag, CTransform2D, CVelocity2D},
+    qp_ecs::Value,
     qp_editor::IGuiController,
     GlobalRegistry, VersionedIndex,
 };
 
 pub struct EntityEditor {
     pub active_entity: Option<VersionedIndex>,
-
-    to_remove: Vec<Box<dyn FnMut(&mut GlobalRegistry, VersionedIndex)>>,
 }
 
 impl EntityEditor {
     pub fn new() -> Self {
         Self {
             active_entity: None,
-            to_remove: vec![],
         }
     }
 
     fn add_component(&self, ui: &mut Ui, entity: VersionedIndex, registry: &mut GlobalRegistry) {
-        ui.menu_button("Add component", |ui| {
-            if registry
-                .entity_manager
-                .get::<CVelocity2D>(&entity)
-                .is_none()
-            {
-                if ui.button("CVelocity").clicked() {
-                    registry.entity_manager.add(&entity, CVelocity2D::default());
-                }
-            } else {
-                ui.label("CVelocity");
-            }
+        let entity_manager = &mut registry.entity_manager;
+        let present: Vec<&'static str> = entity_manager
+            .reflect_components(&entity)
+            .iter()
+            .map(|component| component.type_name())
+            .collect();
+        let addable: Vec<&'static str> = entity_manager
+            .types()
+            .iter()
+            .filter(|r| r.is_constructible() && !present.contains(&r.name))
+            .map(|r| r.name)
+            .collect();
 
-            if registry.entity_manager.get::<CQuad>(&entity).is_none() {
-                if ui.button("CQuad").clicked() {
-                    registry.entity_manager.add(&entity, CQuad::default());
+        ui.menu_button("Add component", |ui| {
+            for name in addable {
+                if ui.button(name).clicked() {
+                    if let Err(e) = entity_manager.add_by_name(&entity, name) {
+                        println!("[editor] couldn't add {}: {}", name, e);
+                    }
                 }
-            } else {
-                ui.label("CQuad");
             }
         });
     }
@@ -48,93 +46,126 @@ impl EntityEditor {
 
 impl IGuiController for EntityEditor {
     fn update(&mut self, ctx: &Context, world: &mut World) {
-        if let Some(entity) = self.active_entity {
-            while !self.to_remove.is_empty() {
-                self.to_remove.pop().unwrap()(&mut world.registry, entity);
-            }
+        let Some(entity) = self.active_entity else {
+            return;
+        };
 
-            egui::Window::new("Entity").show(ctx, |ui| {
-                ui.add_space(10.0);
-                self.add_component(ui, entity, &mut world.registry);
-                ui.add_space(10.0);
+        let components: Vec<(&'static str, Value)> = world
+            .registry
+            .entity_manager
+            .reflect_components(&entity)
+            .iter()
+            .map(|component| (component.type_name(), component.to_value()))
+            .collect();
 
-                if let Some(tag) = world.registry.entity_manager.get_mut::<CTag>(&entity) {
-                    ui.collapsing("Tag", |ui| {
-                        ui.add(egui::TextEdit::singleline(&mut tag.tag));
-                    });
-                }
-                if let Some(transform) = world
-                    .registry
-                    .entity_manager
-                    .get_mut::<CTransform2D>(&entity)
-                {
-                    ui.collapsing("Transforms", |ui| {
-                        ui.label("translate");
-                        ui.horizontal(|ui| {
-                            ui.label("x");
-                            ui.add(egui::DragValue::new(&mut transform.translate.x).speed(1.0));
-                            ui.label("y");
-                            ui.add(egui::DragValue::new(&mut transform.translate.y).speed(1.0));
-                        });
-                        ui.label("scale");
-                        ui.horizontal(|ui| {
-                            ui.label("x");
-                            ui.add(egui::DragValue::new(&mut transform.scale.x).speed(0.05));
-                            ui.label("y");
-                            ui.add(egui::DragValue::new(&mut transform.scale.y).speed(0.05));
-                        });
-                        ui.label("rotation");
-                        ui.horizontal(|ui| {
-                            ui.label("angle");
-                            ui.add(egui::DragValue::new(&mut transform.rotate).speed(0.1));
-                        });
-                    });
-                }
-                if let Some(velocity) = world
-                    .registry
-                    .entity_manager
-                    .get_mut::<CVelocity2D>(&entity)
-                {
-                    ui.collapsing("Velocity", |ui| {
-                        if ui.button("del").clicked() {
-                            self.to_remove
-                                .push(Box::new(|registry, entity: VersionedIndex| {
-                                    registry.entity_manager.remove::<CVelocity2D>(&entity);
-                                }))
-                        }
-
-                        ui.horizontal(|ui| {
-                            ui.label("x");
-                            ui.add(egui::DragValue::new(&mut velocity.x).speed(1.0));
-                            ui.label("y");
-                            ui.add(egui::DragValue::new(&mut velocity.y).speed(1.0));
-                        });
-                    });
-                }
-                if let Some(rect) = world.registry.entity_manager.get_mut::<CQuad>(&entity) {
-                    ui.collapsing("Quad", |ui| {
-                        if ui.button("del").clicked() {
-                            self.to_remove
-                                .push(Box::new(|registry, entity: VersionedIndex| {
-                                    registry.entity_manager.remove::<CQuad>(&entity);
-                                }))
-                        }
-
-                        ui.horizontal(|ui| {
-                            ui.label("center x");
-                            ui.add(egui::DragValue::new(&mut rect.center_x).speed(1.0));
-                            ui.label("center y");
-                            ui.add(egui::DragValue::new(&mut rect.center_y).speed(1.0));
-                        });
-                        ui.horizontal(|ui| {
-                            ui.label("width");
-                            ui.add(egui::DragValue::new(&mut rect.width).speed(1.0));
-                            ui.label("height");
-                            ui.add(egui::DragValue::new(&mut rect.height).speed(1.0));
-                        });
-                    });
+        let mut edited = vec![];
+        let mut to_remove = vec![];
+
+        egui::Window::new("Entity").show(ctx, |ui| {
+            ui.add_space(10.0);
+            self.add_component(ui, entity, &mut world.registry);
+            ui.add_space(10.0);
+
+            for (name, mut value) in components {
+                ui.collapsing(name, |ui| {
+                    if ui.button("del").clicked() {
+                        to_remove.push(name);
+                    }
+
+                    if value_ui(ui, &mut value) {
+                        edited.push((name, value));
+                    }
+                });
+            }
+        });
+
+        let entity_manager = &mut world.registry.entity_manager;
+        for (name, value) in edited {
+            if let Some(component) = entity_manager.reflect_component_mut(&entity, name) {
+                if let Err(e) = component.set_value(value) {
+                    println!("[editor] couldn't update {}: {}", name, e);
                 }
-            });
+            }
+        }
+
+        for name in to_remove {
+            entity_manager.remove_by_name(&entity, name);
         }
     }
 }
+
+/// draws a widget for the value and returns true if it was edited
+fn value_ui(ui: &mut Ui, value: &mut Value) -> bool {
+    match value {
+        Value::Bool(v) => ui.checkbox(v, "").changed(),
+        Value::Int(v) => ui.add(egui::DragValue::new(v)).changed(),
+        Value::UInt(v) => ui.add(egui::DragValue::new(v)).changed(),
+        Value::Float(v) => ui.add(egui::DragValue::new(v).speed(0.1)).changed(),
+        Value::String(v) => ui.text_edit_singleline(v).changed(),
+        Value::Vec2(v) => ui
+            .horizontal(|ui| drag(ui, "x", &mut v.x) | drag(ui, "y", &mut v.y))
+            .inner,
+        Value::Vec3(v) => ui
+            .horizontal(|ui| {
+                drag(ui, "x", &mut v.x) | drag(ui, "y", &mut v.y) | drag(ui, "z", &mut v.z)
+            })
+            .inner,
+        Value::Vec4(v) => ui
+            .horizontal(|ui| {
+                drag(ui, "x", &mut v.x)
+                    | drag(ui, "y", &mut v.y)
+                    | drag(ui, "z", &mut v.z)
+                    | drag(ui, "w", &mut v.w)
+            })
+            .inner,
+        Value::Struct(fields) => {
+            let mut changed = false;
+            for (name, field) in fields.iter_mut() {
+                changed |= field_ui(ui, name, field);
+            }
+
+            changed
+        }
+        Value::List(items) => {
+            let mut changed = false;
+            for (i, item) in items.iter_mut().enumerate() {
+                changed |= field_ui(ui, &i.to_string(), item);
+            }
+
+            changed
+        }
+        Value::Entity(entity) => {
+            ui.label(entity.to_string());
+            false
+        }
+        Value::Mat4(_) => {
+            ui.label("matrix");
+            false
+        }
+        Value::None => {
+            ui.label("none");
+            false
+        }
+    }
+}
+
+fn field_ui(ui: &mut Ui, name: &str, value: &mut Value) -> bool {
+    match value {
+        Value::Struct(_) | Value::List(_) => ui
+            .collapsing(name, |ui| value_ui(ui, value))
+            .body_returned
+            .unwrap_or(false),
+        _ => {
+            ui.horizontal(|ui| {
+                ui.label(name);
+                value_ui(ui, value)
+            })
+            .inner
+        }
+    }
+}
+
+fn drag(ui: &mut Ui, label: &str, value: &mut f32) -> bool {
+    ui.label(label);
+    ui.add(egui::DragValue::new(value).speed(0.1)).changed()
+}
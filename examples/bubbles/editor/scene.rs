@@ -2,7 +2,10 @@ use egui::{Context, Vec2};
 use quipi::{prelude::qp_editor::IGuiController, world::World};
 
 use crate::{
-    qp_ecs::components::{CScene, CSprite, CTag},
+    qp_ecs::{
+        components::{CScene, CSprite, CTag},
+        IncludeDisabled,
+    },
     qp_schemas::{save_scene_2d, SchemaSprite},
     Schema,
 };
@@ -24,7 +27,13 @@ impl SceneEditor {
 impl IGuiController for SceneEditor {
     fn update(&mut self, ctx: &Context, world: &mut World) {
         self.entity_editor.update(ctx, world);
-        let entities = world.registry.entity_manager.query_all::<CSprite>();
+        // disabled sprites are still part of the scene, list them too
+        let entities: Vec<_> = world
+            .registry
+            .entity_manager
+            .query_iter_filtered::<(&CSprite,), IncludeDisabled>()
+            .map(|(entity, _)| entity)
+            .collect();
 
         egui::Window::new("Scene").show(ctx, |ui| {
             ui.horizontal(|ui| {
@@ -74,7 +83,7 @@ impl IGuiController for SceneEditor {
                             if self.entity_editor.active_entity == Some(*entity) {
                                 self.entity_editor.active_entity = None;
                             }
-                            world.registry.entity_manager.set_to_delete(*entity);
+                            world.commands.despawn(*entity);
                         }
                     });
                     ui.allocate_space(Vec2::new(0.0, 5.0));
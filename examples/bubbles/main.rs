@@ -14,10 +14,16 @@ mod controllers;
 mod editor;
 
 pub fn run() -> Result<(), QPError> {
+    qp_core::vfs_mut().mount_source(env!("CARGO_MANIFEST_DIR"));
+
     let mut app = App::init("Bouncing Shapes", WIDTH, HEIGHT, 8576394876)?;
 
+    // pick up texture and font changes without restarting
+    #[cfg(debug_assertions)]
+    app.world.registry.asset_manager.set_hot_reload(true);
+
     let scene = SceneController::load(&mut app)?;
-    app.register_controller(scene);
+    app.add_controller(Stage::Input, scene);
 
     #[cfg(debug_assertions)]
     #[cfg(feature = "qp_editor")]
@@ -3,11 +3,14 @@ extern crate quipi;
 
 pub use quipi::prelude::*;
 use quipi::{
-    asset_manager::assets::{camera::OrthographicCameraParams, RCamera2D, RShader},
+    asset_manager::assets::{
+        camera::OrthographicCameraParams, Handle, PrefabEntity, RCamera2D, RFont, RPrefab,
+        RShader, RTexture,
+    },
     core::prelude::{random::Random, trig::magnitude2d_squared, Interval, Timer},
     ecs::prelude::components::CTransform2D,
-    gfx::prelude::{ShaderUniforms, SpriteRenderer, SPRITE_FRAG, SPRITE_VERT},
-    schemas::sprite::TextureAtlas,
+    gfx::prelude::{ShaderUniforms, SpriteRenderer, Viewport, SPRITE_FRAG, SPRITE_VERT},
+    schemas::{prelude::instantiate, sprite::TextureAtlas},
 };
 
 use qp_ecs::components::*;
@@ -17,9 +20,12 @@ pub static WIDTH: u32 = 1600;
 pub static HEIGHT: u32 = 900;
 
 pub fn run() -> Result<(), QPError> {
+    qp_core::vfs_mut().mount_source(env!("CARGO_MANIFEST_DIR"));
+
     let mut app = App::init("Space Shooter", WIDTH, HEIGHT, 348756)?;
 
-    app.world.registry.asset_manager.load_asset(
+    // the handles keep the assets loaded until the app stops running
+    let _shader = app.world.registry.asset_manager.load_asset(
         "shader",
         RShader::from_str(
             SPRITE_VERT,
@@ -31,14 +37,13 @@ pub fn run() -> Result<(), QPError> {
         )?,
     )?;
 
-    app.world.registry.asset_manager.load_asset(
+    let _tilesheet = app.world.registry.asset_manager.load_asset(
         "space_tilesheet",
-        qp_assets::RTexture {
-            texture: qp_gfx::texture::from_image("assets/textures/space.png")?,
+        RTexture {
+            texture: qp_gfx::texture::from_image("textures/space.png")?,
             texture_dims: glm::vec2(8.0, 6.0),
         },
     )?;
-
     let game = GameController::new(&mut app)?;
     app.register_controller(game);
 
@@ -48,6 +53,68 @@ pub fn run() -> Result<(), QPError> {
     app.run((0.1, 0.1, 0.1, 1.0))
 }
 
+/// the templates that bullets, asteroids and stars are spawned from
+fn load_prefabs(registry: &mut GlobalRegistry) -> Result<Vec<Handle<RPrefab>>, QPError> {
+    let texture = registry
+        .asset_manager
+        .handle::<RTexture>("space_tilesheet")
+        .ok_or(QPError::SpriteTextureDoesntExist)?;
+
+    let texture_dims = registry
+        .asset_manager
+        .get(&texture)
+        .ok_or(QPError::SpriteTextureDoesntExist)?
+        .texture_dims;
+
+    let quad = CQuad {
+        width: 32.0,
+        height: 32.0,
+        ..CQuad::default()
+    };
+    let sprite = |color: glm::Vec4, active_texture: glm::Vec2| {
+        CSprite::new(
+            &quad,
+            Some(color),
+            Some(TextureAtlas {
+                texture: texture.clone(),
+                texture_dims,
+                active_texture,
+            }),
+        )
+    };
+
+    let bullet = PrefabEntity::new()
+        .with(CTag {
+            tag: "bullet".to_string(),
+        })
+        .with(CTransform2D::default())
+        .with(CVelocity2D::default())
+        .with(sprite(glm::vec4(1.0, 1.0, 1.0, 1.0), glm::vec2(1.0, 5.0)));
+
+    let asteroid = PrefabEntity::new()
+        .with(CTag {
+            tag: "asteroid".to_string(),
+        })
+        .with(CTransform2D::default())
+        .with(CVelocity2D::default())
+        .with(sprite(glm::vec4(0.9, 0.9, 0.9, 1.0), glm::vec2(0.0, 1.0)));
+
+    let star = PrefabEntity::new()
+        .with(CTag {
+            tag: "asteroid".to_string(),
+        })
+        .with(CTransform2D::default())
+        .with(sprite(glm::vec4(1.0, 1.0, 0.8, 1.0), glm::vec2(6.0, 2.0)));
+
+    let assets = &mut registry.asset_manager;
+
+    Ok(vec![
+        assets.load_asset("bullet", RPrefab::new(bullet))?,
+        assets.load_asset("asteroid", RPrefab::new(asteroid))?,
+        assets.load_asset("star", RPrefab::new(star))?,
+    ])
+}
+
 fn main() {
     if let Err(e) = run() {
         eprintln!("Space Shooter ended unexpectedly: {}", e);
@@ -56,6 +123,8 @@ fn main() {
 
 pub struct GameController {
     rand: Random,
+    // keeps the prefabs loaded
+    _prefabs: Vec<Handle<RPrefab>>,
 
     score: Score,
     score_interval: Interval,
@@ -75,21 +144,18 @@ pub struct GameController {
 
 impl GameController {
     pub fn new(app: &mut App) -> Result<Self, QPError> {
-        let font = app
-            .world
-            .registry
-            .asset_manager
-            .load_asset("Poppins-Regular", qp_assets::RFont::new("Poppins-Regular")?)?;
+        let font = RFont::load(&mut app.world.registry.asset_manager, "Poppins-Regular")?;
+        let prefabs = load_prefabs(&mut app.world.registry)?;
 
         let ship = Ship::new(&mut app.world)?;
         let camera = Camera::new(&mut app.world, ship.index)?;
-        let score = Score::new(font)?;
-        let game_over_text = GameOver::new(font)?;
+        let score = Score::new(font.clone())?;
+        let game_over_text = GameOver::new(font.clone())?;
 
         app.register_controller(camera);
 
         if cfg!(debug_assertions) {
-            let text = DebugInfoText::new(font)?;
+            let text = DebugInfoText::new(font.clone())?;
             app.register_controller(text);
         }
 
@@ -112,6 +178,7 @@ impl GameController {
 
         Ok(Self {
             rand,
+            _prefabs: prefabs,
             score,
             game_over_text,
             ship,
@@ -170,7 +237,7 @@ impl GameController {
             return Ok(());
         }
 
-        let (_x, _y, width, height) = world.viewport.get_dimensions();
+        let (_x, _y, width, height) = world.resource::<Viewport>().get_dimensions();
 
         let ship = world
             .registry
@@ -196,7 +263,10 @@ impl GameController {
             glm::vec2(x_pos, y_pos),
             (self.rand.random() + 1.0) * 2.0,
             self.rand.random() * 2.0 * glm::pi::<f32>(),
-            &mut world.rand,
+            world
+                .resources
+                .get_mut::<Random>()
+                .expect("[space shooter] the world should have a random generator"),
         )?;
         self.asteroids.push(asteroid);
 
@@ -225,7 +295,9 @@ impl GameController {
 
 impl Controller for GameController {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        for event in world.events.iter() {
+        let events = world.resource::<Events>().clone();
+
+        for event in events.iter() {
             match event {
                 Event::Quit { .. } => {
                     return FrameResult::Quit;
@@ -333,7 +405,7 @@ impl Controller for GameController {
 
 pub struct Camera {
     ship: VersionedIndex,
-    id: u64,
+    camera: Handle<RCamera2D>,
 }
 
 impl Camera {
@@ -355,24 +427,25 @@ impl Camera {
 
         camera.follow(transform, 10.0, 1.0);
 
-        let id = world.registry.asset_manager.load_asset("camera", camera)?;
+        let camera = world.registry.asset_manager.load_asset("camera", camera)?;
 
-        Ok(Self { ship, id })
+        Ok(Self { ship, camera })
     }
 }
 
 impl Controller for Camera {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        for event in world.events.iter() {
+        let events = world.resource::<Events>().clone();
+
+        for event in events.iter() {
             match event {
                 Event::Window {
                     win_event: WindowEvent::Resized(w, h),
                     ..
                 } => {
-                    world.viewport.set_dimensions(0, 0, *w, *h);
+                    world.resource_mut::<Viewport>().set_dimensions(0, 0, *w, *h);
 
-                    if let Some(camera) = world.registry.asset_manager.get_mut::<RCamera2D>(self.id)
-                    {
+                    if let Some(camera) = world.registry.asset_manager.get_mut(&self.camera) {
                         camera.params.right = *w as f32;
                         camera.params.top = *h as f32;
 
@@ -391,7 +464,7 @@ impl Controller for Camera {
         else {
             return FrameResult::None;
         };
-        let Some(camera) = world.registry.asset_manager.get_mut::<RCamera2D>(self.id) else {
+        let Some(camera) = world.registry.asset_manager.get_mut(&self.camera) else {
             return FrameResult::None;
         };
 
@@ -408,22 +481,22 @@ pub struct Ship {
     acceleration: f32,
     max_velocity: f32,
     thrust: bool,
-    thruster_offset: glm::Vec2,
 }
 
 impl Ship {
     pub fn new(world: &mut World) -> Result<Self, QPError> {
-        let texture_id = world
+        let texture = world
             .registry
             .asset_manager
-            .get_asset_id("space_tilesheet")
+            .handle::<RTexture>("space_tilesheet")
             .ok_or(QPError::SpriteTextureDoesntExist)?;
 
-        let texture = world
+        let texture_dims = world
             .registry
             .asset_manager
-            .get::<qp_assets::RTexture>(texture_id)
-            .ok_or(QPError::SpriteTextureDoesntExist)?;
+            .get(&texture)
+            .ok_or(QPError::SpriteTextureDoesntExist)?
+            .texture_dims;
 
         let quad = CQuad {
             width: 64.0,
@@ -436,13 +509,14 @@ impl Ship {
             translate: glm::vec2(0.0, 0.0),
             ..CTransform2D::default()
         };
-        let thruster_offset = glm::vec2(0.0, 26.0);
+        // relative to the ship, which carries it around
+        let thruster_offset = glm::vec2(0.0, -26.0);
         let mut sprite = CSprite::new(
             &quad,
             Some(glm::vec4(1.0, 1.0, 1.0, 1.0)),
             Some(TextureAtlas {
-                texture: texture_id,
-                texture_dims: texture.texture_dims,
+                texture: texture.clone(),
+                texture_dims,
                 active_texture: glm::vec2(7.0, 0.0),
             }),
         );
@@ -454,8 +528,7 @@ impl Ship {
             })
             .with(CVelocity2D { x: 0.0, y: 0.0 })
             .with(CTransform2D {
-                translate: ship_transform.translate - thruster_offset,
-                rotate: ship_transform.rotate,
+                translate: thruster_offset,
                 scale: glm::vec2(0.5, 0.5),
                 ..CTransform2D::default()
             })
@@ -472,29 +545,35 @@ impl Ship {
                 &quad,
                 Some(glm::vec4(0.8, 0.2, 0.0, 1.0)),
                 Some(TextureAtlas {
-                    texture: texture_id,
-                    texture_dims: texture.texture_dims,
+                    texture,
+                    texture_dims,
                     active_texture: glm::vec2(6.0, 5.0),
                 }),
             ))
             .build();
 
+        world
+            .registry
+            .entity_manager
+            .set_parent(&thruster, &index);
+
         Ok(Self {
             index,
             thruster,
             acceleration: 20.0,
             max_velocity: 200.0,
             thrust: false,
-            thruster_offset,
         })
     }
 }
 
 impl Controller for Ship {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        let (_x, _y, width, height) = world.viewport.get_dimensions();
+        let (_x, _y, width, height) = world.resource::<Viewport>().get_dimensions();
 
-        for event in world.events.iter() {
+        let events = world.resource::<Events>().clone();
+
+        for event in events.iter() {
             match event {
                 Event::MouseMotion { x, y, .. } => {
                     let x = x - width / 2;
@@ -552,31 +631,6 @@ impl Controller for Ship {
             };
         }
 
-        // match thruster transform to ship
-        let transform = world
-            .registry
-            .entity_manager
-            .get::<CTransform2D>(&self.index)
-            .unwrap();
-
-        let translate = transform.translate.clone();
-        let rotate = transform.rotate.clone();
-        let offset = glm::vec2(
-            self.thruster_offset.y * transform.direction().x,
-            self.thruster_offset.y * transform.direction().y,
-        );
-
-        let Some(thruster) = world
-            .registry
-            .entity_manager
-            .get_mut::<CTransform2D>(&self.thruster)
-        else {
-            return FrameResult::None;
-        };
-
-        thruster.translate = translate - offset;
-        thruster.rotate = rotate;
-
         if !self.thrust {
             return FrameResult::None;
         }
@@ -603,6 +657,7 @@ impl Controller for Ship {
         };
 
         let velocity = velocity.clone();
+        let delta = world.resource::<Delta>().0;
 
         if let Some(transform) = world
             .registry
@@ -610,8 +665,8 @@ impl Controller for Ship {
             .get_mut::<CTransform2D>(&self.index)
         {
             let direction = transform.direction();
-            transform.translate.x += velocity.x * world.delta * direction.x;
-            transform.translate.y += velocity.y * world.delta * direction.y;
+            transform.translate.x += velocity.x * delta * direction.x;
+            transform.translate.y += velocity.y * delta * direction.y;
         }
 
         FrameResult::None
@@ -633,48 +688,19 @@ impl Bullet {
         direction: glm::Vec2,
         angle: f32,
     ) -> Result<Self, QPError> {
-        let texture_id = registry
-            .asset_manager
-            .get_asset_id("space_tilesheet")
-            .ok_or(QPError::SpriteTextureDoesntExist)?;
-
-        let texture = registry
-            .asset_manager
-            .get::<qp_assets::RTexture>(texture_id)
-            .ok_or(QPError::SpriteTextureDoesntExist)?;
-
-        let quad = CQuad {
-            width: 32.0,
-            height: 32.0,
-            center_x: 0.0,
-            center_y: 0.0,
-        };
-
         let speed = 250.0;
-
-        let index = EntityBuilder::create(&mut registry.entity_manager)
-            .with(CTag {
-                tag: "bullet".to_string(),
-            })
-            .with(CTransform2D {
-                translate: position,
-                rotate: angle,
-                ..CTransform2D::default()
-            })
-            .with(CVelocity2D {
-                x: speed * direction.x,
-                y: speed * direction.y,
-            })
-            .with(CSprite::new(
-                &quad,
-                Some(glm::vec4(1.0, 1.0, 1.0, 1.0)),
-                Some(TextureAtlas {
-                    texture: texture_id,
-                    texture_dims: texture.texture_dims,
-                    active_texture: glm::vec2(1.0, 5.0),
-                }),
-            ))
-            .build();
+        let velocity = direction * speed;
+
+        let index = instantiate(
+            registry,
+            "bullet",
+            &[
+                ("CTransform2D.translate", qp_ecs::Value::Vec2(position)),
+                ("CTransform2D.rotate", qp_ecs::Value::Float(angle as f64)),
+                ("CVelocity2D.x", qp_ecs::Value::Float(velocity.x as f64)),
+                ("CVelocity2D.y", qp_ecs::Value::Float(velocity.y as f64)),
+            ],
+        )?;
 
         let timer = Timer::new();
 
@@ -687,6 +713,7 @@ impl Bullet {
     }
 
     pub fn update(&mut self, world: &mut World) -> bool {
+        let delta = world.resource::<Delta>().0;
         let Some(velocity) = world
             .registry
             .entity_manager
@@ -695,7 +722,7 @@ impl Bullet {
             return false;
         };
 
-        let velocity = glm::vec2(velocity.x * world.delta, velocity.y * world.delta);
+        let velocity = glm::vec2(velocity.x * delta, velocity.y * delta);
 
         if let Some(transform) = world
             .registry
@@ -744,48 +771,23 @@ impl Asteroid {
         rotate: f32,
         rand: &mut Random,
     ) -> Result<Self, QPError> {
-        let texture_id = registry
-            .asset_manager
-            .get_asset_id("space_tilesheet")
-            .ok_or(QPError::SpriteTextureDoesntExist)?;
-
-        let texture = registry
-            .asset_manager
-            .get::<qp_assets::RTexture>(texture_id)
-            .ok_or(QPError::SpriteTextureDoesntExist)?;
-
-        let quad = CQuad {
-            width: 32.0,
-            height: 32.0,
-            ..CQuad::default()
-        };
-
         let direction = (ship_pos - position).normalize();
+        let velocity = glm::vec2(
+            direction.x * (rand.random() + 1.0),
+            direction.y * (rand.random() + 1.0),
+        );
 
-        let index = EntityBuilder::create(&mut registry.entity_manager)
-            .with(CTag {
-                tag: "asteroid".to_string(),
-            })
-            .with(CTransform2D {
-                translate: position,
-                rotate,
-                scale: glm::vec2(scale, scale),
-                ..CTransform2D::default()
-            })
-            .with(CVelocity2D {
-                x: direction.x * (rand.random() + 1.0),
-                y: direction.y * (rand.random() + 1.0),
-            })
-            .with(CSprite::new(
-                &quad,
-                Some(glm::vec4(0.9, 0.9, 0.9, 1.0)),
-                Some(TextureAtlas {
-                    texture: texture_id,
-                    texture_dims: texture.texture_dims,
-                    active_texture: glm::vec2(0.0, 1.0),
-                }),
-            ))
-            .build();
+        let index = instantiate(
+            registry,
+            "asteroid",
+            &[
+                ("CTransform2D.translate", qp_ecs::Value::Vec2(position)),
+                ("CTransform2D.rotate", qp_ecs::Value::Float(rotate as f64)),
+                ("CTransform2D.scale", qp_ecs::Value::Vec2(glm::vec2(scale, scale))),
+                ("CVelocity2D.x", qp_ecs::Value::Float(velocity.x as f64)),
+                ("CVelocity2D.y", qp_ecs::Value::Float(velocity.y as f64)),
+            ],
+        )?;
 
         let timer = Timer::new();
 
@@ -840,6 +842,7 @@ impl Controller for Asteroid {
             .get::<CVelocity2D>(&self.index)
             .unwrap()
             .clone();
+        let delta = world.resource::<Delta>().0;
 
         if let Some(transform) = world
             .registry
@@ -849,7 +852,7 @@ impl Controller for Asteroid {
             transform.translate.x += velocity.x;
             transform.translate.y += velocity.y;
 
-            transform.rotate += world.delta * self.rotation_step;
+            transform.rotate += delta * self.rotation_step;
         }
 
         let sprite = world
@@ -875,60 +878,36 @@ struct Star {
 
 impl Star {
     pub fn new(world: &mut World, ship_pos: glm::Vec2) -> Result<Self, QPError> {
-        let (_x, _y, width, height) = world.viewport.get_dimensions();
-        let x_pos = world.rand.range(
+        let (_x, _y, width, height) = world.resource::<Viewport>().get_dimensions();
+        let rand = world.resource_mut::<Random>();
+        let x_pos = rand.range(
             ship_pos.x as i32 - (width / 2),
             ship_pos.x as i32 + (width / 2),
         ) as f32;
 
-        let y_pos = world.rand.range(
+        let y_pos = rand.range(
             ship_pos.y as i32 - (height / 2),
             ship_pos.y as i32 + (height / 2),
         ) as f32;
-
-        let texture_id = world
-            .registry
-            .asset_manager
-            .get_asset_id("space_tilesheet")
-            .ok_or(QPError::SpriteTextureDoesntExist)?;
-
-        let texture = world
-            .registry
-            .asset_manager
-            .get::<qp_assets::RTexture>(texture_id)
-            .ok_or(QPError::SpriteTextureDoesntExist)?;
-
-        let quad = CQuad {
-            width: 32.0,
-            height: 32.0,
-            ..CQuad::default()
+        let active_x = match rand.binary(0.7) {
+            true => 7.0,
+            false => 6.0,
         };
 
-        let index = EntityBuilder::create(&mut world.registry.entity_manager)
-            .with(CTag {
-                tag: "asteroid".to_string(),
-            })
-            .with(CTransform2D {
-                translate: glm::vec2(x_pos, y_pos),
-                scale: glm::vec2(1.0, 1.0),
-                ..CTransform2D::default()
-            })
-            .with(CSprite::new(
-                &quad,
-                Some(glm::vec4(1.0, 1.0, 0.8, 1.0)),
-                Some(TextureAtlas {
-                    texture: texture_id,
-                    texture_dims: texture.texture_dims,
-                    active_texture: glm::vec2(
-                        match world.rand.binary(0.7) {
-                            true => 7.0,
-                            false => 6.0,
-                        },
-                        2.0,
-                    ),
-                }),
-            ))
-            .build();
+        let index = instantiate(
+            &mut world.registry,
+            "star",
+            &[
+                (
+                    "CTransform2D.translate",
+                    qp_ecs::Value::Vec2(glm::vec2(x_pos, y_pos)),
+                ),
+                (
+                    "CSprite.texture_atlas.active_texture.x",
+                    qp_ecs::Value::Float(active_x),
+                ),
+            ],
+        )?;
 
         let timer = Timer::new();
 
@@ -969,23 +948,23 @@ impl Controller for Star {
 
 struct Score {
     score: u32,
-    font: u64,
+    font: Handle<RFont>,
 }
 
 impl Score {
-    pub fn new(font: u64) -> Result<Self, QPError> {
+    pub fn new(font: Handle<RFont>) -> Result<Self, QPError> {
         Ok(Self { score: 0, font })
     }
 }
 
 impl Controller for Score {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        let (_x, _y, _width, height) = world.viewport.get_dimensions();
-        world.text_buffer.push(qp_gfx::QPText {
+        let (_x, _y, _width, height) = world.resource::<Viewport>().get_dimensions();
+        world.resource_mut::<TextBuffer>().push(qp_gfx::QPText {
             text: format!("score: {}", self.score),
             pos: glm::vec2(20.0, height as f32 - 40.0),
             style: qp_gfx::QPTextStyle {
-                font: self.font,
+                font: self.font.clone(),
                 color: glm::vec4(1.0, 1.0, 1.0, 1.0),
                 scale: 0.4,
             },
@@ -996,32 +975,32 @@ impl Controller for Score {
 }
 
 struct GameOver {
-    font: u64,
+    font: Handle<RFont>,
 }
 
 impl GameOver {
-    pub fn new(font: u64) -> Result<Self, QPError> {
+    pub fn new(font: Handle<RFont>) -> Result<Self, QPError> {
         Ok(Self { font })
     }
 }
 
 impl Controller for GameOver {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        let (_x, _y, width, height) = world.viewport.get_dimensions();
-        world.text_buffer.push(qp_gfx::QPText {
+        let (_x, _y, width, height) = world.resource::<Viewport>().get_dimensions();
+        world.resource_mut::<TextBuffer>().push(qp_gfx::QPText {
             text: "Game Over".into(),
             pos: glm::vec2((width as f32 / 2.0) - 300.0, height as f32 / 2.0),
             style: qp_gfx::QPTextStyle {
-                font: self.font,
+                font: self.font.clone(),
                 color: glm::vec4(1.0, 1.0, 1.0, 0.6),
                 scale: 2.0,
             },
         });
-        world.text_buffer.push(qp_gfx::QPText {
+        world.resource_mut::<TextBuffer>().push(qp_gfx::QPText {
             text: "Press Enter to start again".into(),
             pos: glm::vec2((width as f32 / 2.0) - 160.0, (height as f32 / 2.0) - 50.0),
             style: qp_gfx::QPTextStyle {
-                font: self.font,
+                font: self.font.clone(),
                 color: glm::vec4(0.8, 0.8, 0.8, 1.0),
                 scale: 0.5,
             },
@@ -1032,11 +1011,11 @@ impl Controller for GameOver {
 }
 
 struct DebugInfoText {
-    font: u64,
+    font: Handle<RFont>,
 }
 
 impl DebugInfoText {
-    pub fn new(font: u64) -> Result<Self, QPError> {
+    pub fn new(font: Handle<RFont>) -> Result<Self, QPError> {
         Ok(Self { font })
     }
 }
@@ -1044,11 +1023,11 @@ impl DebugInfoText {
 impl Controller for DebugInfoText {
     fn update(&mut self, world: &mut World) -> FrameResult {
         let entity_count = world.registry.entity_manager.count();
-        world.text_buffer.push(qp_gfx::QPText {
+        world.resource_mut::<TextBuffer>().push(qp_gfx::QPText {
             text: format!("entities: {}", entity_count),
             pos: glm::vec2(20.0, 20.0),
             style: qp_gfx::QPTextStyle {
-                font: self.font,
+                font: self.font.clone(),
                 color: glm::vec4(1.0, 1.0, 1.0, 1.0),
                 scale: 0.4,
             },
@@ -1,10 +1,10 @@
 use crate::{
-    qp_assets::RCamera2D, qp_ecs::components::CTransform2D, qp_schemas::SchemaCamera2D,
+    qp_assets::{Handle, RCamera2D}, qp_ecs::components::CTransform2D, qp_schemas::SchemaCamera2D,
     GlobalRegistry, VersionedIndex,
 };
 use quipi::{
     app::{Controller, FrameResult},
-    prelude::QPError,
+    prelude::{qp_gfx::Viewport, Events, QPError},
     world::World,
 };
 use sdl2::event::{Event, WindowEvent};
@@ -12,14 +12,14 @@ use sdl2::event::{Event, WindowEvent};
 pub const MAIN_CAMERA: &str = "main_camera";
 
 pub struct CameraController {
-    camera: u64,
+    camera: Handle<RCamera2D>,
 
     player: VersionedIndex, // camera will follow player
 }
 
 impl CameraController {
     pub fn new(player: VersionedIndex, registry: &mut GlobalRegistry) -> Result<Self, QPError> {
-        let Some(camera) = registry.asset_manager.get_asset_id(MAIN_CAMERA) else {
+        let Some(camera) = registry.asset_manager.handle(MAIN_CAMERA) else {
             return Err(QPError::Generic(
                 "[camera controller] camera resource has not been loaded".into(),
             ));
@@ -31,18 +31,20 @@ impl CameraController {
 
 impl Controller for CameraController {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        for event in world.events.iter() {
+        let events = world.resource::<Events>().clone();
+
+        for event in events.iter() {
             match event {
                 Event::Window {
                     win_event: WindowEvent::Resized(w, h),
                     ..
                 } => {
-                    world.viewport.set_dimensions(0, 0, *w, *h);
+                    world.resource_mut::<Viewport>().set_dimensions(0, 0, *w, *h);
 
                     if let Some(camera) = world
                         .registry
                         .asset_manager
-                        .get_mut::<RCamera2D>(self.camera)
+                        .get_mut(&self.camera)
                     {
                         camera.params.right = *w as f32;
                         camera.params.top = *h as f32;
@@ -66,7 +68,7 @@ impl Controller for CameraController {
         if let Some(camera) = world
             .registry
             .asset_manager
-            .get_mut::<RCamera2D>(self.camera)
+            .get_mut(&self.camera)
         {
             camera.transform.translate.x = x - (camera.params.right / 2.0);
             camera.transform.translate.y = y - (camera.params.top / 2.0);
@@ -1,12 +1,12 @@
 use crate::{
-    qp_assets::{tilemap::ValidTile, RTileMap},
+    qp_assets::{tilemap::ValidTile, Handle, RTileMap},
     qp_ecs::components::{CQuad, CTransform2D},
     qp_schemas::SchemaSprite,
     GlobalRegistry, Schema, VersionedIndex,
 };
 use quipi::{
     app::{Controller, FrameResult},
-    prelude::QPError,
+    prelude::{Events, QPError},
     world::World,
 };
 use sdl2::{event::Event, keyboard::Keycode};
@@ -15,13 +15,16 @@ const PLAYER_SIZE: f32 = 54.0;
 
 pub struct PlayerController {
     pub player: VersionedIndex,
-    tile_map: u64,
+    tile_map: Handle<RTileMap>,
     tile: glm::Vec2,
 }
 
 impl PlayerController {
-    pub fn new(registry: &mut GlobalRegistry, tile_map: u64) -> Result<Self, QPError> {
-        let r_tile_map = registry.asset_manager.get::<RTileMap>(tile_map).unwrap();
+    pub fn new(
+        registry: &mut GlobalRegistry,
+        tile_map: Handle<RTileMap>,
+    ) -> Result<Self, QPError> {
+        let r_tile_map = registry.asset_manager.get(&tile_map).unwrap();
         let mut this_schema = SchemaSprite::default();
         let start_tile = glm::vec2(1.0, 7.0);
 
@@ -59,7 +62,7 @@ impl PlayerController {
 impl Controller for PlayerController {
     fn update(&mut self, world: &mut World) -> FrameResult {
         let mut new_tile = self.tile;
-        for event in world.events.iter() {
+        for event in world.resource::<Events>().iter() {
             match event {
                 Event::KeyDown {
                     keycode,
@@ -76,7 +79,7 @@ impl Controller for PlayerController {
             };
         }
 
-        let Some(tile_map) = world.registry.asset_manager.get::<RTileMap>(self.tile_map) else {
+        let Some(tile_map) = world.registry.asset_manager.get(&self.tile_map) else {
             return FrameResult::None;
         };
         let ValidTile::Valid(tile_val) = tile_map.get_tile_value(new_tile) else {
@@ -6,9 +6,9 @@ use crate::{
 use quipi::{
     app::{Controller, FrameResult},
     prelude::{
-        qp_assets::RFont,
-        qp_gfx::{QPText, QPTextStyle},
-        QPError,
+        qp_assets::{Handle, RFont},
+        qp_gfx::{QPText, QPTextStyle, Viewport},
+        DebugInfo, Events, QPError, TextBuffer,
     },
     world::World,
 };
@@ -30,7 +30,7 @@ impl SceneController {
 
         let tile_controller = TileControler::new(&mut app.world.registry)?;
         let player_controller =
-            PlayerController::new(&mut app.world.registry, tile_controller.tile_map)?;
+            PlayerController::new(&mut app.world.registry, tile_controller.tile_map.clone())?;
         let camera_controller =
             CameraController::new(player_controller.player, &mut app.world.registry)?;
         let text_controller = DebugInfoText::new(&mut app.world.registry)?;
@@ -50,7 +50,9 @@ impl SceneController {
 
 impl Controller for SceneController {
     fn update(&mut self, world: &mut World) -> FrameResult {
-        for event in world.events.iter() {
+        let events = world.resource::<Events>().clone();
+
+        for event in events.iter() {
             match event {
                 Event::Quit { .. } => {
                     return FrameResult::Quit;
@@ -72,11 +74,13 @@ impl Controller for SceneController {
 }
 
 fn scene_schema(world: &World) -> SchemaScene2D {
-    let (_x, _y, width, height) = world.viewport.get_dimensions();
+    let (_x, _y, width, height) = world.resource::<Viewport>().get_dimensions();
     SchemaScene2D {
         name: "bouncing_shapes".to_string(),
         cameras: vec![camera_schema(width as f32, height as f32)],
         sprites: vec![],
+        entities: vec![],
+        prefabs: vec![],
         shaders: vec![SchemaShader {
             name: "sprite".to_string(),
             uniforms: vec![
@@ -102,14 +106,12 @@ fn scene_schema(world: &World) -> SchemaScene2D {
 }
 
 struct DebugInfoText {
-    font: u64,
+    font: Handle<RFont>,
 }
 
 impl DebugInfoText {
     pub fn new(registry: &mut GlobalRegistry) -> Result<Self, QPError> {
-        let font = registry
-            .asset_manager
-            .load_asset("Poppins-Regular", RFont::new("Poppins-Regular")?)?;
+        let font = RFont::load(&mut registry.asset_manager, "Poppins-Regular")?;
 
         Ok(Self { font })
     }
@@ -119,40 +121,28 @@ impl Controller for DebugInfoText {
     fn update(&mut self, world: &mut World) -> FrameResult {
         let entity_count = world.registry.entity_manager.count();
         let style = QPTextStyle {
-            font: self.font,
+            font: self.font.clone(),
             color: glm::vec4(1.0, 1.0, 1.0, 1.0),
             scale: 0.4,
         };
-        world.text_buffer.push(QPText {
-            text: format!("entities: {}", entity_count),
-            pos: glm::vec2(20.0, 20.0),
-            style: style.clone(),
-        });
-        world.text_buffer.push(QPText {
-            text: format!("draw calls: {}", world.debug_info.draw_calls),
-            pos: glm::vec2(20.0, 40.0),
-            style: style.clone(),
-        });
-        world.text_buffer.push(QPText {
-            text: format!("render ms: {}", world.debug_info.render_ms),
-            pos: glm::vec2(20.0, 60.0),
-            style: style.clone(),
-        });
-        world.text_buffer.push(QPText {
-            text: format!("controller ms: {}", world.debug_info.controller_ms),
-            pos: glm::vec2(20.0, 80.0),
-            style: style.clone(),
-        });
-        world.text_buffer.push(QPText {
-            text: format!("fps: {}", world.debug_info.fps as u32),
-            pos: glm::vec2(20.0, 100.0),
-            style: style.clone(),
-        });
-        world.text_buffer.push(QPText {
-            text: format!("ms: {}", world.debug_info.frame_ms as u32),
-            pos: glm::vec2(20.0, 120.0),
-            style: style.clone(),
-        });
+        let debug_info = world.resource::<DebugInfo>();
+        let lines = [
+            format!("entities: {}", entity_count),
+            format!("draw calls: {}", debug_info.draw_calls),
+            format!("render ms: {}", debug_info.render_ms),
+            format!("controller ms: {}", debug_info.controller_ms),
+            format!("fps: {}", debug_info.fps),
+            format!("ms: {}", debug_info.frame_ms),
+        ];
+
+        let text_buffer = world.resource_mut::<TextBuffer>();
+        for (i, text) in lines.into_iter().enumerate() {
+            text_buffer.push(QPText {
+                text,
+                pos: glm::vec2(20.0, 20.0 + i as f32 * 20.0),
+                style: style.clone(),
+            });
+        }
 
         FrameResult::None
     }
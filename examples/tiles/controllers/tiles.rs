@@ -6,7 +6,7 @@ use quipi::{
 };
 
 use crate::{
-    qp_assets::RTileMap,
+    qp_assets::{Handle, RTileMap},
     qp_core::{now_secs, random::Random},
     qp_ecs::components::{CQuad, CSprite, CTransform2D},
     GlobalRegistry, VersionedIndex,
@@ -29,7 +29,7 @@ const TILE_MAP: [[u16; 10]; 10] = [
 pub struct TileControler {
     _tiles: Vec<VersionedIndex>,
 
-    pub tile_map: u64,
+    pub tile_map: Handle<RTileMap>,
 }
 
 impl TileControler {
@@ -103,7 +103,7 @@ fn tile(x: u32, y: u32, tile_val: u16, registry: &mut GlobalRegistry) -> Version
             match tile_val {
                 9 => None,
                 _ => Some(TextureAtlas {
-                    texture: registry.strings_mut().intern("tiles.png".to_string()),
+                    texture: registry.asset_manager.handle("tiles.png").unwrap_or_default(),
                     active_texture: glm::vec2(tile_val as f32, 0.0),
                     texture_dims: glm::vec2(4.0, 1.0),
                 }),
@@ -5,7 +5,7 @@ use quipi::{
     world::World,
 };
 
-use crate::{qp_core::Timer, qp_editor::GuiManager, QPError};
+use crate::{qp_core::Timer, qp_editor::GuiManager, DebugInfo, QPError};
 
 pub struct AppEditor {
     gui: GuiManager,
@@ -36,7 +36,7 @@ impl Controller for AppEditor {
 
         self.gui.update(world);
 
-        world.debug_info.editor_ms = (self.timer.delta() * 1000.0) as u32;
+        world.resource_mut::<DebugInfo>().editor_ms = (self.timer.delta() * 1000.0) as u32;
 
         FrameResult::None
     }
@@ -14,8 +14,14 @@ mod controllers;
 mod editor;
 
 pub fn run() -> Result<(), QPError> {
+    qp_core::vfs_mut().mount_source(env!("CARGO_MANIFEST_DIR"));
+
     let mut app = App::init("Tiles", WIDTH, HEIGHT, 54875687)?;
 
+    // pick up texture and font changes without restarting
+    #[cfg(debug_assertions)]
+    app.world.registry.asset_manager.set_hot_reload(true);
+
     let scene = SceneController::load(&mut app)?;
 
     app.register_controller(scene);
@@ -1,21 +1,443 @@
 use proc_macro::TokenStream;
-use syn::DeriveInput;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
 
-#[proc_macro_derive(Component)]
+/**
+ * Implements `Component` and `Reflect`, and registers the component so
+ * it can be looked up by name, see `EntityManager::register_all_components`.
+ * The name is the module path and the type, eg `game::enemies::Health`, or
+ * the one given with `#[component(name = "Health")]` to keep scene files
+ * valid when the type moves.
+ * Fields that can't be reflected, like GPU handles, need `#[reflect(ignore)]`.
+ * Components listed in `#[require(CTransform2D, CQuad)]` are added with
+ * their defaults whenever this one is added to an entity that doesn't have them
+ */
+#[proc_macro_derive(Component, attributes(component, reflect, require))]
 pub fn component_derive_macro(item: TokenStream) -> TokenStream {
     // parse
     let ast: DeriveInput = syn::parse(item).unwrap();
 
     // generate
-    impl_component_trait(ast)
+    let type_name = quote!(<Self as ::quipi::ecs::prelude::Component>::NAME);
+    let reflect = match impl_reflect_trait(&ast, type_name) {
+        Ok(reflect) => reflect,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let component = match impl_component_trait(&ast) {
+        Ok(component) => component,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    (quote! {
+        #component
+        #reflect
+    })
+    .into()
+}
+
+/// reflection only, for plain structs used as component fields
+#[proc_macro_derive(Reflect, attributes(reflect))]
+pub fn reflect_derive_macro(item: TokenStream) -> TokenStream {
+    let ast: DeriveInput = syn::parse(item).unwrap();
+    let type_name = ast.ident.to_string();
+
+    match impl_reflect_trait(&ast, quote!(#type_name)) {
+        Ok(reflect) => reflect.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/**
+ * Implements `Bundle` for a struct whose fields are all components or
+ * bundles, so they can be added and removed together
+ */
+#[proc_macro_derive(Bundle)]
+pub fn bundle_derive_macro(item: TokenStream) -> TokenStream {
+    let ast: DeriveInput = syn::parse(item).unwrap();
+
+    match impl_bundle_trait(&ast) {
+        Ok(bundle) => bundle.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
 }
 
-fn impl_component_trait(ast: DeriveInput) -> TokenStream {
+fn impl_component_trait(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     // get the struct identifier
-    let ident = ast.ident;
+    let ident = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let name = component_name(ast)?;
+
+    let mut required = vec![];
+    for attr in ast.attrs.iter().filter(|a| a.path().is_ident("require")) {
+        let paths = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+        )?;
+        required.extend(paths);
+    }
+
+    let requirements = match required.is_empty() {
+        true => quote!(),
+        false => quote! {
+            const REQUIRED: &'static [&'static str] = &[
+                #(<#required as ::quipi::ecs::prelude::Component>::NAME),*
+            ];
+
+            fn add_required(
+                entity: &::quipi::ecs::prelude::VersionedIndex,
+                entity_manager: &mut ::quipi::ecs::prelude::EntityManager,
+            ) {
+                #(
+                    if entity_manager.get::<#required>(entity).is_none() {
+                        entity_manager.add(entity, <#required as ::std::default::Default>::default());
+                    }
+                )*
+            }
+        },
+    };
+
+    // generic components can't be registered without knowing their parameters
+    let registrar = match ast.generics.params.is_empty() {
+        true => quote! {
+            const _: () = {
+                use ::quipi::ecs::prelude::{
+                    RegisterDefault as _, RegisterNone as _, RegisterPlain as _,
+                };
+
+                fn register(entity_manager: &mut ::quipi::ecs::prelude::EntityManager) {
+                    (&&&::quipi::ecs::prelude::RegisterProbe::<#ident>::new())
+                        .register_with(entity_manager);
+                }
+
+                ::quipi::ecs::prelude::inventory::submit! {
+                    ::quipi::ecs::prelude::ComponentRegistrar {
+                        type_id: ::std::any::TypeId::of::<#ident>,
+                        register,
+                    }
+                }
+            };
+        },
+        false => quote!(),
+    };
 
     // generate impl
-    (quote::quote! {
-        impl Component for #ident {}
-    }).into()
+    Ok(quote! {
+        impl #impl_generics ::quipi::ecs::prelude::Component for #ident #ty_generics #where_clause {
+            const NAME: &'static str = #name;
+            #requirements
+        }
+
+        #registrar
+    })
+}
+
+// unique unless two types are given the same name
+fn component_name(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut name = None;
+    for attr in ast.attrs.iter().filter(|a| a.path().is_ident("component")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("name") {
+                return Err(meta.error("expected `name = \"..\"`"));
+            }
+
+            let value: syn::LitStr = meta.value()?.parse()?;
+            name = Some(quote!(#value));
+
+            Ok(())
+        })?;
+    }
+
+    let ident = ast.ident.to_string();
+
+    Ok(name.unwrap_or_else(|| quote!(concat!(module_path!(), "::", #ident))))
+}
+
+fn impl_reflect_trait(
+    ast: &DeriveInput,
+    type_name: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &ast.ident;
+    let name = ident.to_string();
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let body = match &ast.data {
+        Data::Struct(data) => reflect_struct(&data.fields, &type_name)?,
+        Data::Enum(data) => {
+            let mut variants = vec![];
+            for variant in data.variants.iter() {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "Reflect can only be derived for enums without fields",
+                    ));
+                }
+
+                variants.push(&variant.ident);
+            }
+
+            reflect_enum(&name, &type_name, &variants)
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(
+                data.union_token,
+                "Reflect can't be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::quipi::ecs::prelude::Reflect for #ident #ty_generics #where_clause {
+            fn type_name(&self) -> &'static str {
+                #type_name
+            }
+
+            #body
+        }
+    })
+}
+
+fn impl_bundle_trait(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let fields = match &ast.data {
+        Data::Struct(data) if !data.fields.is_empty() => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "Bundle can only be derived for structs with fields",
+            ))
+        }
+    };
+
+    let mut members = vec![];
+    let mut types = vec![];
+    let mut parts = vec![];
+    for (i, field) in fields.iter().enumerate() {
+        members.push(match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(i.into()),
+        });
+        types.push(&field.ty);
+        parts.push(quote::format_ident!("part_{}", i));
+    }
+
+    let bundle = quote!(::quipi::ecs::prelude::Bundle);
+    let entity_manager = quote!(::quipi::ecs::prelude::EntityManager);
+    let entity = quote!(::quipi::ecs::prelude::VersionedIndex);
+
+    Ok(quote! {
+        impl #impl_generics #bundle for #ident #ty_generics #where_clause {
+            fn add_to(self, entity: &#entity, entity_manager: &mut #entity_manager) {
+                #(<#types as #bundle>::add_to(self.#members, entity, entity_manager);)*
+            }
+
+            fn is_in(entity: &#entity, entity_manager: &#entity_manager) -> bool {
+                true #(&& <#types as #bundle>::is_in(entity, entity_manager))*
+            }
+
+            fn remove_from(entity: &#entity, entity_manager: &mut #entity_manager) {
+                #(<#types as #bundle>::remove_from(entity, entity_manager);)*
+            }
+
+            fn take_from(entity: &#entity, entity_manager: &mut #entity_manager) -> Option<Self> {
+                // checked up front, so a missing field doesn't lose the fields taken before it
+                if !<Self as #bundle>::is_in(entity, entity_manager) {
+                    return None;
+                }
+
+                Some(Self { #(#members: <#types as #bundle>::take_from(entity, entity_manager)?,)* })
+            }
+
+            fn add_batch(
+                bundles: Vec<Self>,
+                entities: &[#entity],
+                entity_manager: &mut #entity_manager,
+            ) {
+                // split the bundles into a column per field
+                #(let mut #parts: Vec<#types> = Vec::with_capacity(bundles.len());)*
+                for bundle in bundles {
+                    #(#parts.push(bundle.#members);)*
+                }
+
+                #(<#types as #bundle>::add_batch(#parts, entities, entity_manager);)*
+            }
+        }
+    })
+}
+
+fn reflect_struct(
+    fields: &Fields,
+    type_name: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut names = vec![];
+    let mut types = vec![];
+    let mut tys = vec![];
+    let mut members = vec![];
+    let mut ignored_tys = vec![];
+    let mut ignored_members = vec![];
+
+    for (i, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(i.into()),
+        };
+
+        if is_ignored(field)? {
+            ignored_tys.push(&field.ty);
+            ignored_members.push(member);
+            continue;
+        }
+
+        names.push(match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => i.to_string(),
+        });
+        let ty = &field.ty;
+        types.push(quote!(#ty).to_string().replace(' ', ""));
+        tys.push(ty);
+        members.push(member);
+    }
+
+    let reflect = quote!(::quipi::ecs::prelude::Reflect);
+    let value = quote!(::quipi::ecs::prelude::Value);
+    let error = quote!(::quipi::prelude::QPError);
+    let probe = quote!(::quipi::ecs::prelude::DefaultProbe);
+
+    // fields start from their default when they have one, like set_value on a default
+    let build = quote! {
+        fn from_value(value: #value) -> Result<Self, #error> {
+            use ::quipi::ecs::prelude::{ProbeDefault as _, ProbeNoDefault as _};
+
+            let mut fields = match value {
+                #value::Struct(fields) => fields,
+                value => {
+                    return Err(#error::ReflectTypeMismatch(
+                        #type_name.to_string(),
+                        value.kind().to_string(),
+                    ))
+                }
+            };
+            let mut take = |name: &str| {
+                let i = fields.iter().position(|(field, _)| field == name)?;
+                Some(fields.remove(i).1)
+            };
+
+            let built = Self {
+                #(#members: match ((&&#probe::<#tys>::new()).default_value(), take(#names)) {
+                    (Some(mut field), Some(value)) => {
+                        #reflect::set_value(&mut field, value)?;
+                        field
+                    }
+                    (None, Some(value)) => <#tys as #reflect>::from_value(value)?,
+                    (Some(field), None) => field,
+                    (None, None) => {
+                        return Err(#error::ReflectFieldNotFound(
+                            #type_name.to_string(),
+                            #names.to_string(),
+                        ))
+                    }
+                },)*
+                #(#ignored_members: (&&#probe::<#ignored_tys>::new())
+                    .default_value()
+                    .ok_or_else(|| #error::ComponentNotConstructible(#type_name.to_string()))?,)*
+            };
+
+            match fields.into_iter().next() {
+                Some((name, _)) => Err(#error::ReflectFieldNotFound(#type_name.to_string(), name)),
+                None => Ok(built),
+            }
+        }
+
+        fn can_build() -> bool {
+            use ::quipi::ecs::prelude::{ProbeDefault as _, ProbeNoDefault as _};
+
+            true
+                #(&& ((&&#probe::<#tys>::new()).default_value().is_some()
+                    || <#tys as #reflect>::can_build()))*
+                #(&& (&&#probe::<#ignored_tys>::new()).default_value().is_some())*
+        }
+    };
+
+    if names.is_empty() {
+        return Ok(build);
+    }
+
+    Ok(quote! {
+        #build
+
+        fn fields(&self) -> &'static [::quipi::ecs::prelude::FieldInfo] {
+            &[#(::quipi::ecs::prelude::FieldInfo { name: #names, type_name: #types }),*]
+        }
+
+        fn field(&self, name: &str) -> Option<&dyn ::quipi::ecs::prelude::Reflect> {
+            match name {
+                #(#names => Some(&self.#members as &dyn ::quipi::ecs::prelude::Reflect),)*
+                _ => None,
+            }
+        }
+
+        fn field_mut(&mut self, name: &str) -> Option<&mut dyn ::quipi::ecs::prelude::Reflect> {
+            match name {
+                #(#names => Some(&mut self.#members as &mut dyn ::quipi::ecs::prelude::Reflect),)*
+                _ => None,
+            }
+        }
+    })
+}
+
+/// fieldless enums reflect as the variant name
+fn reflect_enum(
+    name: &str,
+    type_name: &proc_macro2::TokenStream,
+    variants: &[&syn::Ident],
+) -> proc_macro2::TokenStream {
+    let variant_names: Vec<String> = variants.iter().map(|v| v.to_string()).collect();
+
+    quote! {
+        fn from_value(value: ::quipi::ecs::prelude::Value) -> Result<Self, ::quipi::prelude::QPError> {
+            match &value {
+                #(::quipi::ecs::prelude::Value::String(s) if s == #variant_names => Ok(Self::#variants),)*
+                _ => Err(::quipi::prelude::QPError::ReflectTypeMismatch(
+                    #type_name.to_string(),
+                    value.kind().to_string(),
+                )),
+            }
+        }
+
+        fn can_build() -> bool {
+            true
+        }
+
+        fn to_value(&self) -> ::quipi::ecs::prelude::Value {
+            ::quipi::ecs::prelude::Value::String(match self {
+                #(Self::#variants => #variant_names,)*
+            }.to_string())
+        }
+
+        fn set_value(&mut self, value: ::quipi::ecs::prelude::Value) -> Result<(), ::quipi::prelude::QPError> {
+            match &value {
+                #(::quipi::ecs::prelude::Value::String(s) if s == #variant_names => *self = Self::#variants,)*
+                _ => return Err(::quipi::prelude::QPError::ReflectTypeMismatch(
+                    #name.to_string(),
+                    value.kind().to_string(),
+                )),
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn is_ignored(field: &syn::Field) -> syn::Result<bool> {
+    let Some(attr) = field.attrs.iter().find(|a| a.path().is_ident("reflect")) else {
+        return Ok(false);
+    };
+
+    let arg: syn::Ident = attr.parse_args()?;
+    if arg != "ignore" {
+        return Err(syn::Error::new_spanned(arg, "expected #[reflect(ignore)]"));
+    }
+
+    Ok(true)
 }
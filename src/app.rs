@@ -1,9 +1,12 @@
 use crate::platform::opengl;
 use crate::platform::sdl2;
+use crate::prelude::qp_ecs::TransformPropagation;
 use crate::prelude::qp_gfx;
 use crate::prelude::qp_gfx::Viewport;
+use crate::prelude::DebugInfo;
 use crate::prelude::World;
 use crate::prelude::{qp_gfx::TextRenderer, QPError};
+use crate::schedule::{ParallelController, Schedule, Stage, SystemDescriptor};
 use crate::QPResult;
 
 #[cfg(feature = "qp_profiling")]
@@ -16,8 +19,7 @@ pub struct App {
     #[cfg(feature = "qp_profiling")]
     profiler: QPProfiler,
 
-    controllers: Vec<Box<dyn Controller>>,
-    renderers: Vec<Box<dyn Renderer>>,
+    schedule: Schedule,
 }
 
 impl App {
@@ -35,6 +37,11 @@ impl App {
 
         let world = World::new(viewport, seed)?;
 
+        let mut schedule = Schedule::new();
+        schedule
+            .add_controller(Stage::PostUpdate, TransformPropagation)
+            .label("transform_propagation");
+
         Ok(Self {
             winapi,
             world,
@@ -42,25 +49,69 @@ impl App {
             #[cfg(feature = "qp_profiling")]
             profiler: QPProfiler::new(),
 
-            controllers: vec![],
-            renderers: vec![],
+            schedule,
         })
     }
 
-    pub fn register_controller(&mut self, controller: impl Controller + 'static) {
-        self.controllers.push(Box::new(controller));
+    /// adds the controller to the update stage
+    pub fn register_controller(
+        &mut self,
+        controller: impl Controller + 'static,
+    ) -> &mut SystemDescriptor {
+        self.schedule.add_controller(Stage::Update, controller)
+    }
+
+    /// adds the renderer to the render stage
+    pub fn register_renderer(
+        &mut self,
+        renderer: impl Renderer + 'static,
+    ) -> &mut SystemDescriptor {
+        self.schedule.add_renderer(Stage::Render, renderer)
+    }
+
+    pub fn add_controller(
+        &mut self,
+        stage: Stage,
+        controller: impl Controller + 'static,
+    ) -> &mut SystemDescriptor {
+        self.schedule.add_controller(stage, controller)
+    }
+
+    pub fn add_renderer(
+        &mut self,
+        stage: Stage,
+        renderer: impl Renderer + 'static,
+    ) -> &mut SystemDescriptor {
+        self.schedule.add_renderer(stage, renderer)
+    }
+
+    pub fn add_parallel(
+        &mut self,
+        stage: Stage,
+        controller: impl ParallelController + 'static,
+    ) -> &mut SystemDescriptor {
+        self.schedule.add_parallel(stage, controller)
+    }
+
+    /// runs before the first frame and again after every restart
+    pub fn add_startup(&mut self, system: impl FnMut(&mut World) -> QPResult<()> + 'static) {
+        self.schedule.add_startup(system);
     }
 
-    pub fn register_renderer(&mut self, renderer: impl Renderer + 'static) {
-        self.renderers.push(Box::new(renderer));
+    pub fn schedule_mut(&mut self) -> &mut Schedule {
+        &mut self.schedule
     }
 
     pub fn run(&mut self, clear_color: (f32, f32, f32, f32)) -> QPResult<()> {
-        self.register_renderer(TextRenderer::new()?);
+        self.register_renderer(TextRenderer::new()?).label("text");
+
+        self.schedule.build()?;
+        self.schedule.run_startup(&mut self.world)?;
 
         'running: loop {
             self.world.flush();
             self.world.new_frame(&mut self.winapi)?;
+            self.world.update_assets();
 
             opengl::buffer::clear_buffers(clear_color);
 
@@ -68,11 +119,26 @@ impl App {
             #[cfg(feature = "qp_profiling")]
             self.profiler.begin();
 
-            for controller in self.controllers.iter_mut() {
-                match controller.update(&mut self.world) {
+            let mut draw_calls = 0;
+
+            for stage in [
+                Stage::Input,
+                Stage::PreUpdate,
+                Stage::FixedUpdate,
+                Stage::Update,
+                Stage::PostUpdate,
+            ] {
+                let result = match stage {
+                    Stage::FixedUpdate => self.schedule.run_fixed_update(&mut self.world)?,
+                    _ => self.schedule.run_stage(stage, &mut self.world)?,
+                };
+
+                draw_calls += result.draw_calls;
+                match result.frame_result {
                     FrameResult::Quit => break 'running,
                     FrameResult::Restart => {
-                        self.world.reset();
+                        self.schedule.restart(&mut self.world)?;
+                        continue 'running;
                     }
                     FrameResult::None => (),
                 }
@@ -80,19 +146,22 @@ impl App {
 
             #[cfg(feature = "qp_profiling")]
             {
-                self.world.debug_info.controller_ms = self.profiler.end() as u32;
+                self.world.resource_mut::<DebugInfo>().controller_ms = self.profiler.end() as u32;
             }
 
             // call renderers
-            let mut draw_calls = 0;
-
             #[cfg(feature = "qp_profiling")]
             self.profiler.begin();
 
-            for renderer in self.renderers.iter_mut() {
-                if let Some(m_draw_calls) = renderer.draw(&mut self.world) {
-                    draw_calls += m_draw_calls;
+            let result = self.schedule.run_stage(Stage::Render, &mut self.world)?;
+            draw_calls += result.draw_calls;
+            match result.frame_result {
+                FrameResult::Quit => break 'running,
+                FrameResult::Restart => {
+                    self.schedule.restart(&mut self.world)?;
+                    continue 'running;
                 }
+                FrameResult::None => (),
             }
 
             if let Some(window) = &self.winapi.window {
@@ -103,20 +172,31 @@ impl App {
 
             #[cfg(feature = "qp_profiling")]
             {
-                self.world.debug_info.render_ms = self.profiler.end() as u32;
+                self.world.resource_mut::<DebugInfo>().render_ms = self.profiler.end() as u32;
             }
 
-            self.world.debug_info.draw_calls = draw_calls;
+            self.world.resource_mut::<DebugInfo>().draw_calls = draw_calls;
+
+            let result = self.schedule.run_stage(Stage::Late, &mut self.world)?;
+            match result.frame_result {
+                FrameResult::Quit => break 'running,
+                FrameResult::Restart => {
+                    self.schedule.restart(&mut self.world)?;
+                    continue 'running;
+                }
+                FrameResult::None => (),
+            }
         }
 
         Ok(())
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FrameResult {
     Quit,
     None,
+    /// resets the world and runs the startup systems again at the end of the stage
     Restart,
 }
 
@@ -126,4 +206,9 @@ pub trait Renderer {
 
 pub trait Controller {
     fn update(&mut self, world: &mut World) -> FrameResult;
+
+    /// called after a restart, once the startup systems have run
+    fn reset(&mut self, _world: &mut World) -> QPResult<()> {
+        Ok(())
+    }
 }
@@ -3,12 +3,12 @@ use serde::{Deserialize, Serialize};
 use crate::{
     prelude::qp_ecs::{
         components::{CTransform, CTransform2D},
-        Component,
+        Component, Reflect,
     },
     QPResult,
 };
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[derive(Debug, Reflect, Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub struct OrthographicCameraParams {
     pub left: f32,
     pub right: f32,
@@ -133,7 +133,7 @@ impl RCamera2D {
 
 // 3D camera
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[derive(Debug, Reflect, Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub struct PerspectiveCameraParams {
     pub fov: f32,
     pub aspect: f32,
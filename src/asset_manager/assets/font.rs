@@ -1,4 +1,5 @@
-use crate::core::prelude::to_abs_path;
+use crate::asset_manager::{AssetManager, Handle};
+use crate::core::prelude::vfs;
 use crate::platform::opengl::textures::{ParameterName, ParameterValue};
 use crate::prelude::qp_ecs::Component;
 use crate::platform::opengl::{
@@ -10,10 +11,7 @@ use crate::platform::opengl::{
     }
 };
 use crate::QPResult;
-use ft::{
-    face::LoadFlag,
-    Face,
-};
+use ft::face::LoadFlag;
 
 use super::RTexture;
 
@@ -22,18 +20,41 @@ const CHARACTER_COUNT: usize = 128;
 #[derive(Debug, Component, PartialEq)]
 pub struct RFont {
     // pub texture: Texture,
+    #[reflect(ignore)]
     pub characters: Vec<Character>
 }
 
 impl RFont {
+    /// loads fonts/<font>.ttf and reloads it when it changes while hot reloading is on
+    pub fn load(assets: &mut AssetManager, font: &str) -> QPResult<Handle<RFont>> {
+        let path = format!("fonts/{font}.ttf");
+        let name = font.to_string();
+
+        assets.load_watched(font, vec![path.into()], move || RFont::new(&name))
+    }
+
+    /// rasterizes the font on a loader thread, see `AssetManager::load_async`. It isn't watched for changes
+    pub fn load_async(assets: &mut AssetManager, font: &str) -> QPResult<Handle<RFont>> {
+        let name = font.to_string();
+
+        assets.load_async(
+            font,
+            move || RFont::rasterize(&name),
+            |glyphs| Ok(RFont::from_glyphs(glyphs)),
+        )
+    }
+
     pub fn new(font: &str) -> QPResult<RFont> {
-        let font = to_abs_path(&format!("assets/fonts/{font}.ttf"))?;
-        let library = ft::Library::init()?;
-        let face = library.new_face(font, 0)?;
+        Ok(Self::from_glyphs(Self::rasterize(font)?))
+    }
 
-        pixel_store::set_unpack_alignment(1);
+    /// renders the characters with FreeType without touching GL, so it can run off the main thread
+    pub fn rasterize(font: &str) -> QPResult<Vec<Glyph>> {
+        let font = vfs().read(&format!("fonts/{font}.ttf"))?;
+        let library = ft::Library::init()?;
+        let face = library.new_memory_face(font, 0)?;
 
-        let mut characters = Vec::<Character>::with_capacity(CHARACTER_COUNT);
+        let mut glyphs = Vec::<Glyph>::with_capacity(CHARACTER_COUNT);
 
         for c in 0..CHARACTER_COUNT {
             face.set_char_size(40 * 64, 0, 96, 0)?;
@@ -45,39 +66,60 @@ impl RFont {
                 continue
             }
 
-            let width = face.glyph().bitmap().width();
-            let rows = face.glyph().bitmap().rows();
-            let left = face.glyph().bitmap_left();
-            let top = face.glyph().bitmap_top();
-
-            let texture = RTexture {
-                texture: texture_from_font(
-                    &face,
-                    width,
-                    rows
-                ),
-                texture_dims: glm::vec2(width as f32, rows as f32)
-            };
-    
-            let m_char = Character {
-                texture,
-                size: glm::vec2(width as f32, rows as f32),
-                bearing: glm::vec2(left as f32, top as f32),
+            let glyph = Glyph {
+                width: face.glyph().bitmap().width(),
+                rows: face.glyph().bitmap().rows(),
+                left: face.glyph().bitmap_left(),
+                top: face.glyph().bitmap_top(),
                 advance_x: face.glyph().advance().x,
                 advance_y: face.glyph().advance().y,
+                buffer: face.glyph().bitmap().buffer().to_vec(),
             };
-    
+
             if char::from_u32(c as u32).is_some() {
-                characters.push(m_char);
+                glyphs.push(glyph);
             }
         }
 
-        Ok(Self {
+        Ok(glyphs)
+    }
+
+    /// uploads the rasterized characters
+    pub fn from_glyphs(glyphs: Vec<Glyph>) -> RFont {
+        pixel_store::set_unpack_alignment(1);
+
+        let characters = glyphs
+            .into_iter()
+            .map(|glyph| Character {
+                texture: RTexture {
+                    texture: texture_from_glyph(&glyph),
+                    texture_dims: glm::vec2(glyph.width as f32, glyph.rows as f32)
+                },
+                size: glm::vec2(glyph.width as f32, glyph.rows as f32),
+                bearing: glm::vec2(glyph.left as f32, glyph.top as f32),
+                advance_x: glyph.advance_x,
+                advance_y: glyph.advance_y,
+            })
+            .collect();
+
+        Self {
             characters
-        })
+        }
     }
 }
 
+/// a character rendered by FreeType
+#[derive(Debug)]
+pub struct Glyph {
+    pub width: i32,
+    pub rows: i32,
+    pub left: i32,
+    pub top: i32,
+    pub advance_x: i32,
+    pub advance_y: i32,
+    pub buffer: Vec<u8>,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Character {
     pub texture: RTexture,
@@ -89,14 +131,10 @@ pub struct Character {
 
 // helpers
 
-fn texture_from_font(
-    face: &Face,
-    width: i32,
-    height: i32
-) -> Texture {
+fn texture_from_glyph(glyph: &Glyph) -> Texture {
     let texture = Texture::new(
-        width,
-        height,
+        glyph.width,
+        glyph.rows,
         Target::Texture2D
     );
 
@@ -111,10 +149,7 @@ fn texture_from_font(
         .add_image_data(
             Format::Red,
             Format::Red,
-            face
-                .glyph()
-                .bitmap()
-                .buffer(),
+            &glyph.buffer,
         );
 
     texture
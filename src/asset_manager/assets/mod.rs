@@ -1,11 +1,18 @@
 pub mod camera;
 pub mod font;
+pub mod prefab;
 pub mod shader;
 pub mod texture;
 pub mod tilemap;
 
+pub use super::AssetEvent;
+pub use super::Handle;
+pub use super::UntypedHandle;
+pub use super::WeakHandle;
 pub use camera::RCamera2D;
 pub use font::RFont;
+pub use prefab::PrefabEntity;
+pub use prefab::RPrefab;
 pub use shader::RShader;
 pub use texture::RTexture;
 pub use texture::RTextureAtlas;
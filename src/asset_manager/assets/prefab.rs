@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::qp_ecs::{Component, Value};
+
+/**
+ * One entity in a prefab. It either starts empty or as an instance of
+ * another prefab, gets `components` added, then has `overrides` applied.
+ * Override keys are a component name followed by a reflection path,
+ * eg "CTransform2D.translate"
+ */
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct PrefabEntity {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefab: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<PrefabEntity>,
+}
+
+impl PrefabEntity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// an instance of the prefab loaded under `name`
+    pub fn instance_of(name: &str) -> Self {
+        Self {
+            prefab: Some(name.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with<C: Component>(mut self, component: C) -> Self {
+        self.components
+            .insert(C::NAME.to_string(), component.to_value());
+
+        self
+    }
+
+    pub fn with_override(mut self, path: &str, value: Value) -> Self {
+        self.overrides.insert(path.to_string(), value);
+
+        self
+    }
+
+    pub fn with_child(mut self, child: PrefabEntity) -> Self {
+        self.children.push(child);
+
+        self
+    }
+}
+
+/**
+ * An entity template. Instantiate it with `qp_schemas::instantiate`
+ */
+#[derive(Debug, Component, Clone, PartialEq)]
+pub struct RPrefab {
+    #[reflect(ignore)]
+    pub root: PrefabEntity,
+}
+
+impl RPrefab {
+    pub fn new(root: PrefabEntity) -> Self {
+        Self { root }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::asset_manager::{AssetManager, Handle};
 use crate::platform::opengl::shader::ShaderProgram;
 use crate::prelude::qp_ecs::Component;
 use crate::prelude::qp_gfx::ShaderUniforms;
@@ -5,7 +6,9 @@ use crate::QPResult;
 
 #[derive(Debug, Component, PartialEq)]
 pub struct RShader {
+    #[reflect(ignore)]
     pub program: ShaderProgram,
+    #[reflect(ignore)]
     pub uniforms: Vec<ShaderUniforms>,
 }
 
@@ -17,6 +20,34 @@ impl RShader {
         })
     }
 
+    /**
+     * loads shaders/<file_name>.vert and .frag, and recompiles them
+     * when either changes while hot reloading is on. A shader that fails
+     * to compile keeps the previous program
+     */
+    pub fn load(
+        assets: &mut AssetManager,
+        file_name: &str,
+        uniforms: Vec<ShaderUniforms>,
+    ) -> QPResult<Handle<Self>> {
+        let name = file_name.to_string();
+
+        let paths = Self::source_paths(file_name)
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        assets.load_watched(file_name, paths, move || Self::new(&name, uniforms.clone()))
+    }
+
+    /// the vertex and fragment files `new` reads, as vfs paths
+    pub fn source_paths(file_name: &str) -> Vec<String> {
+        vec![
+            format!("shaders/{file_name}.vert"),
+            format!("shaders/{file_name}.frag"),
+        ]
+    }
+
     pub fn from_str(vert: &str, frag: &str, uniforms: Vec<ShaderUniforms>) -> QPResult<Self> {
         Ok(Self {
             program: ShaderProgram::from_str(vert, frag)?,
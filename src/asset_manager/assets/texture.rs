@@ -3,6 +3,7 @@ use crate::prelude::qp_ecs::Component;
 
 #[derive(Component, Debug, PartialEq)]
 pub struct RTexture {
+    #[reflect(ignore)]
     pub texture: Texture,
     pub texture_dims: glm::Vec2,
 }
@@ -10,6 +11,7 @@ pub struct RTexture {
 // TODO:
 #[derive(Component, Debug, PartialEq)]
 pub struct RTextureAtlas {
+    #[reflect(ignore)]
     pub texture: Texture,
     pub texture_dims: glm::Vec2,
 }
@@ -0,0 +1,276 @@
+use std::{
+    any::{Any, TypeId},
+    collections::{HashMap, VecDeque},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    prelude::{
+        qp_ecs::{Component, EntityManager},
+        QPError, VersionedIndex,
+    },
+    QPResult,
+};
+
+use super::{AssetEntry, AssetEvent, AssetManager, Handle};
+
+const WORKER_COUNT: usize = 2;
+const DEFAULT_UPLOAD_BUDGET: Duration = Duration::from_millis(4);
+
+/// where an asset is in its lifecycle
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadState {
+    /// nothing is loaded or loading under the id
+    NotLoaded,
+    /// decoding on a worker thread, or waiting for its upload
+    Loading,
+    Loaded,
+    /// the error the load stopped at. Loading the name again retries it
+    Failed(String),
+}
+
+type Decoded = Result<Box<dyn Any + Send>, String>;
+type Job = Box<dyn FnOnce() -> Decoded + Send>;
+type UploadFn =
+    Box<dyn FnOnce(Box<dyn Any + Send>, &mut EntityManager) -> QPResult<VersionedIndex>>;
+
+struct PendingLoad {
+    // tells a result apart from an older load of the same name
+    ticket: u64,
+    type_id: TypeId,
+    type_name: &'static str,
+    upload: UploadFn,
+}
+
+/**
+ * A few worker threads that run decode jobs. They stop when the
+ * asset manager, and with it the job sender, is dropped
+ */
+struct Workers {
+    jobs: Sender<(u64, u64, Job)>,
+    results: Receiver<(u64, u64, Decoded)>,
+}
+
+impl Workers {
+    fn spawn() -> Self {
+        let (jobs, job_queue) = channel::<(u64, u64, Job)>();
+        let (results_sender, results) = channel();
+        let job_queue = Arc::new(Mutex::new(job_queue));
+
+        for i in 0..WORKER_COUNT {
+            let job_queue = job_queue.clone();
+            let results = results_sender.clone();
+
+            let spawned = thread::Builder::new()
+                .name(format!("qp-asset-loader-{i}"))
+                .spawn(move || loop {
+                    let next = job_queue.lock().map(|queue| queue.recv());
+                    let Ok(Ok((id, ticket, job))) = next else {
+                        return;
+                    };
+
+                    if results.send((id, ticket, job())).is_err() {
+                        return;
+                    }
+                });
+
+            if let Err(_e) = spawned {
+                #[cfg(debug_assertions)]
+                println!(
+                    "[asset manager] couldn't start an asset loader thread: {}",
+                    _e
+                );
+            }
+        }
+
+        Self { jobs, results }
+    }
+}
+
+#[derive(Default)]
+pub(super) struct BackgroundLoads {
+    workers: Option<Workers>,
+    pending: HashMap<u64, PendingLoad>,
+    // decoded and waiting for an upload
+    ready: VecDeque<(u64, u64, Decoded)>,
+    failed: HashMap<u64, String>,
+    next_ticket: u64,
+    upload_budget: Option<Duration>,
+}
+
+impl BackgroundLoads {
+    /// forgets every pending load, results that are still on their way are dropped
+    pub(super) fn clear(&mut self) {
+        self.pending.clear();
+        self.ready.clear();
+        self.failed.clear();
+    }
+}
+
+impl AssetManager {
+    /**
+     * loads an asset without blocking. `decode` runs on a worker thread and
+     * should do the slow part, reading and parsing files. `upload` turns
+     * its output into the asset on the main thread, which is where GL
+     * objects have to be created. The handle resolves once the upload is done,
+     * see `load_state`
+     */
+    pub fn load_async<D, A>(
+        &mut self,
+        name: &str,
+        decode: impl FnOnce() -> QPResult<D> + Send + 'static,
+        upload: impl FnOnce(D) -> QPResult<A> + 'static,
+    ) -> QPResult<Handle<A>>
+    where
+        D: Send + 'static,
+        A: Component + std::fmt::Debug + PartialEq + 'static,
+    {
+        let id = self.intern(name)?;
+        let loaded = self
+            .asset_map
+            .get(&id)
+            .map(|entry| (entry.type_id, entry.type_name));
+        let pending = self
+            .loads
+            .pending
+            .get(&id)
+            .map(|load| (load.type_id, load.type_name));
+
+        if let Some((type_id, type_name)) = loaded.or(pending) {
+            if type_id != TypeId::of::<A>() {
+                return Err(QPError::AssetTypeMismatch(name.to_string(), type_name));
+            }
+
+            return Ok(self.refs.handle(id));
+        }
+
+        let ticket = self.loads.next_ticket;
+        self.loads.next_ticket += 1;
+        self.loads.failed.remove(&id);
+        self.loads.pending.insert(
+            id,
+            PendingLoad {
+                ticket,
+                type_id: TypeId::of::<A>(),
+                type_name: A::NAME,
+                upload: Box::new(move |decoded, asset_store| {
+                    // the job below always produces a D
+                    let decoded = *decoded.downcast::<D>().unwrap();
+                    let asset = upload(decoded)?;
+
+                    let index = asset_store.create();
+                    asset_store.add(&index, asset);
+
+                    Ok(index)
+                }),
+            },
+        );
+
+        let job: Job = Box::new(move || match decode() {
+            Ok(decoded) => Ok(Box::new(decoded) as Box<dyn Any + Send>),
+            Err(e) => Err(e.to_string()),
+        });
+
+        let workers = self.loads.workers.get_or_insert_with(Workers::spawn);
+        if let Err(returned) = workers.jobs.send((id, ticket, job)) {
+            // no worker is running, decode here instead of failing the load
+            let (id, ticket, job) = returned.0;
+            self.loads.ready.push_back((id, ticket, job()));
+        }
+
+        Ok(self.refs.handle(id))
+    }
+
+    pub fn load_state(&self, id: u64) -> LoadState {
+        if self.asset_map.contains_key(&id) {
+            return LoadState::Loaded;
+        }
+
+        if self.loads.pending.contains_key(&id) {
+            return LoadState::Loading;
+        }
+
+        match self.loads.failed.get(&id) {
+            Some(error) => LoadState::Failed(error.clone()),
+            None => LoadState::NotLoaded,
+        }
+    }
+
+    /// the number of assets still loading in the background, eg for a loading screen
+    pub fn loading(&self) -> usize {
+        self.loads.pending.len()
+    }
+
+    /// how long `finish_loads` may spend uploading each frame. At least one upload always happens
+    pub fn set_upload_budget(&mut self, budget: Duration) {
+        self.loads.upload_budget = Some(budget);
+    }
+
+    /**
+     * uploads the assets that finished decoding, until the upload budget
+     * runs out. Called once a frame by the app
+     */
+    pub fn finish_loads(&mut self) -> Vec<AssetEvent> {
+        if let Some(workers) = &self.loads.workers {
+            self.loads.ready.extend(workers.results.try_iter());
+        }
+
+        let budget = self.loads.upload_budget.unwrap_or(DEFAULT_UPLOAD_BUDGET);
+        let start = Instant::now();
+
+        let mut events = vec![];
+        while let Some((id, ticket, decoded)) = self.loads.ready.pop_front() {
+            // a result of a load that was reset or started over
+            let current = self.loads.pending.get(&id).map(|pending| pending.ticket);
+            if current != Some(ticket) {
+                continue;
+            }
+
+            let pending = self.loads.pending.remove(&id).unwrap();
+
+            // every handle was dropped while it loaded
+            if !self.refs.is_referenced(pending.type_id, id) {
+                self.refs.forget(pending.type_id, id);
+                continue;
+            }
+
+            let uploaded = decoded.and_then(|decoded| {
+                (pending.upload)(decoded, &mut self.asset_store).map_err(|e| e.to_string())
+            });
+
+            events.push(match uploaded {
+                Ok(index) => {
+                    self.asset_map.insert(
+                        id,
+                        AssetEntry {
+                            index,
+                            type_id: pending.type_id,
+                            type_name: pending.type_name,
+                        },
+                    );
+
+                    AssetEvent::Loaded { id }
+                }
+                Err(error) => {
+                    #[cfg(debug_assertions)]
+                    println!("[asset manager] failed to load asset {}: {}", id, error);
+
+                    self.loads.failed.insert(id, error.clone());
+                    self.refs.forget(pending.type_id, id);
+                    AssetEvent::LoadFailed { id, error }
+                }
+            });
+
+            if start.elapsed() >= budget {
+                break;
+            }
+        }
+
+        events
+    }
+}
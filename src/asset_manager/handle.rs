@@ -0,0 +1,360 @@
+use std::{
+    any::TypeId,
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Debug,
+    hash::Hash,
+    marker::PhantomData,
+    sync::{Arc, Mutex, Weak},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    prelude::{
+        qp_ecs::{Reflect, Value},
+        QPError,
+    },
+    QPResult,
+};
+
+/**
+ * The reference counts of one asset manager's assets, keyed by type and
+ * interned name. An asset has an entry while it's loaded or loading
+ */
+#[derive(Clone, Default)]
+pub(super) struct AssetRefs(Arc<Mutex<RefTable>>);
+
+type RefTable = HashMap<(TypeId, u64), Weak<()>>;
+
+impl AssetRefs {
+    /// a strong handle, the asset counts as loaded from now on
+    pub(super) fn handle<A: 'static>(&self, id: u64) -> Handle<A> {
+        let mut refs = self.0.lock().unwrap();
+        let weak = refs.entry((TypeId::of::<A>(), id)).or_default();
+
+        let strong = match weak.upgrade() {
+            Some(strong) => strong,
+            None => {
+                let strong = Arc::new(());
+                *weak = Arc::downgrade(&strong);
+
+                strong
+            }
+        };
+
+        Handle {
+            id,
+            refs: Some(strong),
+            _marker: PhantomData,
+        }
+    }
+
+    /// a strong handle if the asset is loaded or loading, an unbound one otherwise
+    fn bind<A: 'static>(&self, id: u64) -> Handle<A> {
+        let is_loaded = self
+            .0
+            .lock()
+            .unwrap()
+            .contains_key(&(TypeId::of::<A>(), id));
+
+        match is_loaded {
+            true => self.handle(id),
+            false => Handle::unbound(id),
+        }
+    }
+
+    /// true while a strong handle to the asset exists
+    pub(super) fn is_referenced(&self, type_id: TypeId, id: u64) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .get(&(type_id, id))
+            .is_some_and(|refs| refs.strong_count() > 0)
+    }
+
+    /// drops the entry of an asset that was unloaded
+    pub(super) fn forget(&self, type_id: TypeId, id: u64) {
+        self.0.lock().unwrap().remove(&(type_id, id));
+    }
+
+    pub(super) fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    pub(super) fn scope(&self) -> HandleScope {
+        BINDING.with(|binding| binding.borrow_mut().push(self.clone()));
+
+        HandleScope {
+            _not_send: PhantomData,
+        }
+    }
+}
+
+thread_local! {
+    static BINDING: RefCell<Vec<AssetRefs>> = const { RefCell::new(Vec::new()) };
+}
+
+/**
+ * While it lives, handles that are deserialized or set through
+ * reflection on this thread are bound to the asset manager that made it,
+ * see `AssetManager::bind_handles`
+ */
+pub struct HandleScope {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for HandleScope {
+    fn drop(&mut self) {
+        BINDING.with(|binding| binding.borrow_mut().pop());
+    }
+}
+
+// a handle read back from a file or a value, bound if a scope is active
+fn restore<A: 'static>(id: u64) -> Handle<A> {
+    BINDING.with(|binding| match binding.borrow().last() {
+        Some(refs) => refs.bind(id),
+        None => Handle::unbound(id),
+    })
+}
+
+/**
+ * A strong reference to an asset of type `A`. The asset stays loaded
+ * while any strong handle to it exists, and is unloaded on the asset
+ * manager's next flush after the last one is dropped.
+ *
+ * A handle read back from a file or a reflected value outside of
+ * `AssetManager::bind_handles` is unbound. It resolves while the asset
+ * is loaded but doesn't keep it loaded. The default handle refers to
+ * nothing and never resolves
+ */
+pub struct Handle<A> {
+    id: u64,
+    refs: Option<Arc<()>>,
+    _marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Handle<A> {
+    fn unbound(id: u64) -> Self {
+        Self {
+            id,
+            refs: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// the interned name of the asset
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.id == 0
+    }
+
+    /// false for handles that don't keep their asset loaded
+    pub fn is_bound(&self) -> bool {
+        self.refs.is_some()
+    }
+
+    pub fn downgrade(&self) -> WeakHandle<A> {
+        WeakHandle {
+            id: self.id,
+            refs: self.refs.as_ref().map(Arc::downgrade).unwrap_or_default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// the number of strong handles to the asset, this one included
+    pub fn strong_count(&self) -> usize {
+        self.refs.as_ref().map_or(0, Arc::strong_count)
+    }
+}
+
+impl<A> Clone for Handle<A> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            refs: self.refs.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A> Default for Handle<A> {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            refs: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A> Debug for Handle<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Handle<{}>({})", std::any::type_name::<A>(), self.id)
+    }
+}
+
+impl<A> PartialEq for Handle<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<A> Eq for Handle<A> {}
+
+impl<A> Hash for Handle<A> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+// saved as the asset id, the name it hashes is in the string interner
+impl<A> Serialize for Handle<A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.is_none() {
+            true => serializer.serialize_none(),
+            false => serializer.serialize_some(&self.id),
+        }
+    }
+}
+
+impl<'de, A: 'static> Deserialize<'de> for Handle<A> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Option::<u64>::deserialize(deserializer)? {
+            Some(id) => restore(id),
+            None => Self::default(),
+        })
+    }
+}
+
+impl<A: 'static> Reflect for Handle<A> {
+    fn type_name(&self) -> &'static str {
+        "Handle"
+    }
+
+    fn to_value(&self) -> Value {
+        match self.is_none() {
+            true => Value::None,
+            false => Value::UInt(self.id),
+        }
+    }
+
+    fn set_value(&mut self, value: Value) -> QPResult<()> {
+        *self = match value {
+            Value::None => Self::default(),
+            value => match value.as_u64() {
+                Some(id) if id == self.id && self.is_bound() => return Ok(()),
+                Some(id) => restore(id),
+                None => {
+                    return Err(QPError::ReflectTypeMismatch(
+                        "Handle".to_string(),
+                        value.kind().to_string(),
+                    ))
+                }
+            },
+        };
+
+        Ok(())
+    }
+
+    fn from_value(value: Value) -> QPResult<Self> {
+        let mut handle = Self::default();
+        handle.set_value(value)?;
+
+        Ok(handle)
+    }
+
+    fn can_build() -> bool {
+        true
+    }
+}
+
+/**
+ * A strong handle with its asset type erased, for code that loads assets
+ * of different types, eg `Schema::load_resource`. Keeps the asset loaded
+ * like the typed handle it was made from
+ */
+#[derive(Clone)]
+pub struct UntypedHandle {
+    id: u64,
+    type_id: TypeId,
+    refs: Option<Arc<()>>,
+}
+
+impl UntypedHandle {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// None if the handle refers to an asset of another type
+    pub fn typed<A: 'static>(self) -> Option<Handle<A>> {
+        (self.type_id == TypeId::of::<A>()).then_some(Handle {
+            id: self.id,
+            refs: self.refs,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<A: 'static> From<Handle<A>> for UntypedHandle {
+    fn from(handle: Handle<A>) -> Self {
+        Self {
+            id: handle.id,
+            type_id: TypeId::of::<A>(),
+            refs: handle.refs,
+        }
+    }
+}
+
+impl Debug for UntypedHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UntypedHandle({})", self.id)
+    }
+}
+
+/**
+ * Refers to an asset without keeping it loaded
+ */
+pub struct WeakHandle<A> {
+    id: u64,
+    refs: Weak<()>,
+    _marker: PhantomData<fn() -> A>,
+}
+
+impl<A> WeakHandle<A> {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// None once every strong handle is gone
+    pub fn upgrade(&self) -> Option<Handle<A>> {
+        Some(Handle {
+            id: self.id,
+            refs: Some(self.refs.upgrade()?),
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.refs.strong_count() > 0
+    }
+}
+
+impl<A> Clone for WeakHandle<A> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            refs: self.refs.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A> Debug for WeakHandle<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WeakHandle<{}>({})", std::any::type_name::<A>(), self.id)
+    }
+}
@@ -0,0 +1,186 @@
+use std::{collections::HashMap, fs, path::PathBuf, time::SystemTime};
+
+use crate::{
+    prelude::{
+        qp_core::vfs,
+        qp_ecs::{Component, EntityManager},
+        VersionedIndex,
+    },
+    QPResult,
+};
+
+use super::{AssetManager, Handle};
+
+/// reported by `AssetManager::poll_changes` and `AssetManager::finish_loads`
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetEvent {
+    /// a background load finished, its handles resolve from now on
+    Loaded { id: u64 },
+    /// the handles never resolve, loading the name again retries it
+    LoadFailed { id: u64, error: String },
+    /// the asset was rebuilt from its sources, its handles see the new version
+    Reloaded { id: u64 },
+    /// the asset keeps its previous version
+    ReloadFailed { id: u64, error: String },
+    /// a scene file changed, `World` rebuilds the scene from it
+    SceneChanged { name: String },
+}
+
+type ReloadFn = Box<dyn FnMut(&mut EntityManager, &VersionedIndex) -> QPResult<()>>;
+
+pub(super) struct WatchedAsset {
+    files: WatchedFiles,
+    reload: ReloadFn,
+}
+
+/// the modification times the watched files had when they were last read
+pub(super) struct WatchedFiles(Vec<(PathBuf, Option<SystemTime>)>);
+
+impl WatchedFiles {
+    // files that are only in an archive can't change, they aren't watched
+    fn new(paths: Vec<PathBuf>) -> Self {
+        Self(
+            paths
+                .into_iter()
+                .filter_map(|path| vfs().disk_path(&path.to_string_lossy()))
+                .map(|path| {
+                    let modified = modified(&path);
+                    (path, modified)
+                })
+                .collect(),
+        )
+    }
+
+    /// true if any file changed since the last call
+    fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, last) in self.0.iter_mut() {
+            let now = modified(path);
+            if now != *last {
+                *last = now;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+// None while the file is missing, eg halfway through an editor saving it
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+#[derive(Default)]
+pub(super) struct HotReload {
+    pub(super) enabled: bool,
+    pub(super) assets: HashMap<u64, WatchedAsset>,
+    pub(super) scenes: HashMap<String, WatchedFiles>,
+}
+
+impl AssetManager {
+    /// watching is off by default, `poll_changes` doesn't look at any file until it is turned on
+    pub fn set_hot_reload(&mut self, enabled: bool) {
+        self.hot_reload.enabled = enabled;
+    }
+
+    pub fn hot_reload(&self) -> bool {
+        self.hot_reload.enabled
+    }
+
+    /**
+     * reloads the asset with `load` whenever one of `paths` changes. The
+     * paths are read through the vfs, or are absolute.
+     * The new version replaces the old one in place, so the id and every
+     * handle stay valid. If `load` fails the old version is kept
+     */
+    pub fn watch<A: Component + std::fmt::Debug + PartialEq + 'static>(
+        &mut self,
+        handle: &Handle<A>,
+        paths: Vec<PathBuf>,
+        mut load: impl FnMut() -> QPResult<A> + 'static,
+    ) {
+        let reload: ReloadFn = Box::new(move |asset_store, index| {
+            let asset = load()?;
+            if let Some(current) = asset_store.get_mut::<A>(index) {
+                *current = asset;
+            }
+
+            Ok(())
+        });
+
+        self.hot_reload.assets.insert(
+            handle.id(),
+            WatchedAsset {
+                files: WatchedFiles::new(paths),
+                reload,
+            },
+        );
+    }
+
+    /// loads the asset with `load` and watches `paths` for changes. Already loaded assets aren't read again
+    pub fn load_watched<A: Component + std::fmt::Debug + PartialEq + 'static>(
+        &mut self,
+        name: &str,
+        paths: Vec<PathBuf>,
+        mut load: impl FnMut() -> QPResult<A> + 'static,
+    ) -> QPResult<Handle<A>> {
+        if let Some(handle) = self.handle::<A>(name) {
+            return Ok(handle);
+        }
+
+        let handle = self.load_asset(name, load()?)?;
+        self.watch(&handle, paths, load);
+
+        Ok(handle)
+    }
+
+    /// reports changes to scenes/<name>.yaml as `AssetEvent::SceneChanged`
+    pub fn watch_scene(&mut self, name: &str) {
+        let path = format!("scenes/{}.yaml", name);
+        self.hot_reload
+            .scenes
+            .insert(name.to_string(), WatchedFiles::new(vec![path.into()]));
+    }
+
+    /// reloads the watched assets whose files changed since the last poll
+    pub fn poll_changes(&mut self) -> Vec<AssetEvent> {
+        if !self.hot_reload.enabled {
+            return vec![];
+        }
+
+        let mut events = vec![];
+        for (id, watched) in self.hot_reload.assets.iter_mut() {
+            if !watched.files.changed() {
+                continue;
+            }
+
+            let Some(entry) = self.asset_map.get(id) else {
+                continue;
+            };
+
+            let event = match (watched.reload)(&mut self.asset_store, &entry.index) {
+                Ok(()) => AssetEvent::Reloaded { id: *id },
+                Err(e) => {
+                    #[cfg(debug_assertions)]
+                    println!("[asset manager] failed to reload asset {}: {}", id, e);
+
+                    AssetEvent::ReloadFailed {
+                        id: *id,
+                        error: e.to_string(),
+                    }
+                }
+            };
+
+            events.push(event);
+        }
+
+        for (name, files) in self.hot_reload.scenes.iter_mut() {
+            if files.changed() {
+                events.push(AssetEvent::SceneChanged { name: name.clone() });
+            }
+        }
+
+        events
+    }
+}
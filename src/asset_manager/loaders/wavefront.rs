@@ -1,15 +1,28 @@
+use std::path::Path;
+
 use crate::{
     QPResult,
-    prelude::qp_core::to_abs_path
+    prelude::qp_core::vfs
 };
 
+/// reads the obj file, and the material files it names next to it, through the vfs
 pub fn load_obj_file(
     rel_path: String
 ) -> QPResult<(Vec<tobj::Model>, Vec<tobj::Material>)> {
-    let full_path = to_abs_path(&rel_path)?;
-    let (models, materials) = tobj::load_obj(
-        full_path,
-        &tobj::GPU_LOAD_OPTIONS
+    let obj = vfs().read(&rel_path)?;
+    let dir = Path::new(&rel_path).parent().unwrap_or(Path::new(""));
+
+    let (models, materials) = tobj::load_obj_buf(
+        &mut &obj[..],
+        &tobj::GPU_LOAD_OPTIONS,
+        |mtl_path| {
+            let path = dir.join(mtl_path);
+            let Ok(mtl) = vfs().read(&path.to_string_lossy()) else {
+                return Err(tobj::LoadError::OpenFileFailed);
+            };
+
+            tobj::load_mtl_buf(&mut &mtl[..])
+        }
     )?;
 
     let materials = materials?;
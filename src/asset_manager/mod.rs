@@ -1,12 +1,27 @@
 pub mod assets;
+mod background;
+mod handle;
+mod hot_reload;
 mod loaders;
 
+pub use background::LoadState;
+pub use handle::Handle;
+pub use handle::HandleScope;
+pub use handle::UntypedHandle;
+pub use handle::WeakHandle;
+pub use hot_reload::AssetEvent;
+
 use std::{
+    any::TypeId,
     cell::RefCell,
     collections::HashMap,
     rc::{Rc, Weak},
 };
 
+use background::BackgroundLoads;
+use handle::AssetRefs;
+use hot_reload::HotReload;
+
 use crate::{
     prelude::{
         qp_core::StringInterner,
@@ -16,24 +31,46 @@ use crate::{
     QPResult,
 };
 
+/**
+ * Owns every loaded asset. Loading hands out a typed `Handle`, and an
+ * asset is unloaded on the first flush after its last strong handle is
+ * dropped
+ */
 pub struct AssetManager {
     asset_store: EntityManager,
-    asset_map: HashMap<u64, VersionedIndex>,
+    asset_map: HashMap<u64, AssetEntry>,
+    hot_reload: HotReload,
+    loads: BackgroundLoads,
+    refs: AssetRefs,
+    // prefabs by the name they were instantiated with
+    prefabs: HashMap<String, Handle<assets::RPrefab>>,
 
     strings: Weak<RefCell<StringInterner>>,
 }
 
+#[derive(Debug, Clone, Copy)]
+struct AssetEntry {
+    index: VersionedIndex,
+    type_id: TypeId,
+    type_name: &'static str,
+}
+
 impl AssetManager {
     pub fn init(strings: Weak<RefCell<StringInterner>>) -> QPResult<Self> {
         let mut manager = Self {
             asset_store: EntityManager::new()?,
             asset_map: HashMap::new(),
+            hot_reload: HotReload::default(),
+            loads: BackgroundLoads::default(),
+            refs: AssetRefs::default(),
+            prefabs: HashMap::new(),
             strings,
         };
 
         manager
             .asset_store
             .register_component::<assets::RFont>()
+            .register_component::<assets::RPrefab>()
             .register_component::<assets::RShader>()
             .register_component::<assets::RCamera2D>()
             .register_component::<assets::RTileMap>()
@@ -43,84 +80,201 @@ impl AssetManager {
         Ok(manager)
     }
 
+    /// loading a name that is already loaded keeps the loaded asset and returns a handle to it
     pub fn load_asset<A: Component + std::fmt::Debug + PartialEq + 'static>(
         &mut self,
         name: &str,
         asset: A,
-    ) -> QPResult<u64> {
-        let Some(interner) = self.string_interner() else {
-            return Err(QPError::SharedReferenceDropped);
-        };
+    ) -> QPResult<Handle<A>> {
+        let id = self.intern(name)?;
 
-        let id = interner.borrow_mut().intern(name.to_string());
-
-        if self.asset_map.get(&id).is_none() {
-            let index = self.asset_store.create();
-            self.asset_store.add(&index, asset);
+        match self.asset_map.get(&id) {
+            Some(entry) if entry.type_id != TypeId::of::<A>() => {
+                return Err(QPError::AssetTypeMismatch(
+                    name.to_string(),
+                    entry.type_name,
+                ));
+            }
+            Some(_) => {
+                #[cfg(debug_assertions)]
+                println!("tried to load an already loaded asset");
+            }
+            None => {
+                let index = self.asset_store.create();
+                self.asset_store.add(&index, asset);
 
-            self.asset_map.insert(id, index);
-        } else {
-            #[cfg(debug_assertions)]
-            println!("tried to load an already loaded asset");
+                self.asset_map.insert(
+                    id,
+                    AssetEntry {
+                        index,
+                        type_id: TypeId::of::<A>(),
+                        type_name: A::NAME,
+                    },
+                );
+            }
         }
 
-        Ok(id)
+        Ok(self.refs.handle(id))
     }
 
-    pub fn unload_asset<A: Component + std::fmt::Debug + PartialEq + 'static>(&mut self, id: u64) {
-        if let Some(index) = self.asset_map.get(&id) {
-            self.asset_store.remove::<A>(index);
+    /**
+     * drops the handle and unloads the asset right away, unless other
+     * strong handles to it are still alive
+     */
+    pub fn unload_asset<A: Component + std::fmt::Debug + PartialEq + 'static>(
+        &mut self,
+        handle: Handle<A>,
+    ) {
+        let id = handle.id();
+        drop(handle);
 
-            self.asset_map.remove(&id);
+        if self.refs.is_referenced(TypeId::of::<A>(), id) {
+            #[cfg(debug_assertions)]
+            println!("[asset manager] tried to unload an asset that is still in use");
+
+            return;
         }
+
+        self.remove(id);
     }
 
-    pub fn get<A: Component + std::fmt::Debug + PartialEq + 'static>(&self, id: u64) -> Option<&A> {
-        match self.asset_map.get(&id) {
-            Some(index) => self.asset_store.get::<A>(index),
-            None => None,
-        }
+    pub fn get<A: Component + std::fmt::Debug + PartialEq + 'static>(
+        &self,
+        handle: &Handle<A>,
+    ) -> Option<&A> {
+        self.get_by_id(handle.id())
     }
 
     pub fn get_mut<A: Component + std::fmt::Debug + PartialEq + 'static>(
         &mut self,
-        id: u64,
+        handle: &Handle<A>,
     ) -> Option<&mut A> {
-        match self.asset_map.get(&id) {
-            Some(index) => self.asset_store.get_mut::<A>(index),
-            None => None,
-        }
+        let index = self.index_of::<A>(handle.id())?;
+
+        self.asset_store.get_mut::<A>(&index)
     }
 
-    pub fn get_asset_id(&mut self, name: &str) -> Option<u64> {
-        let Some(interner) = self.string_interner() else {
-            return None;
-        };
+    /// looks an asset up by its interned name, for schemas that save assets by name
+    pub fn get_by_id<A: Component + std::fmt::Debug + PartialEq + 'static>(
+        &self,
+        id: u64,
+    ) -> Option<&A> {
+        let index = self.index_of::<A>(id)?;
+
+        self.asset_store.get::<A>(&index)
+    }
 
+    /// a new strong handle to a loaded asset, None if it isn't loaded or isn't an `A`
+    pub fn handle<A: Component + std::fmt::Debug + PartialEq + 'static>(
+        &mut self,
+        name: &str,
+    ) -> Option<Handle<A>> {
+        let interner = self.string_interner()?;
         let id = interner.borrow_mut().intern(name.to_string());
 
-        match self.asset_map.contains_key(&id) {
-            true => Some(id),
-            _ => None,
-        }
+        self.handle_by_id(id)
+    }
+
+    pub fn handle_by_id<A: Component + std::fmt::Debug + PartialEq + 'static>(
+        &self,
+        id: u64,
+    ) -> Option<Handle<A>> {
+        self.index_of::<A>(id)?;
+
+        Some(self.refs.handle(id))
     }
 
-    pub fn add_index(&mut self, id: u64, index: VersionedIndex) {
-        self.asset_map.insert(id, index);
+    /**
+     * binds the handles that are deserialized or set through reflection
+     * on this thread to this manager until the scope is dropped, so they
+     * keep their assets loaded. Used when loading scenes and snapshots
+     */
+    pub fn bind_handles(&self) -> HandleScope {
+        self.refs.scope()
     }
 
-    pub fn get_index(&self, id: u64) -> Option<VersionedIndex> {
-        self.asset_map.get(&id).cloned()
+    pub fn contains(&self, id: u64) -> bool {
+        self.asset_map.contains_key(&id)
     }
 
+    /// unloads the assets nothing holds a strong handle to
     pub fn flush(&mut self) {
+        let unused = self
+            .asset_map
+            .iter()
+            .filter(|(id, entry)| !self.refs.is_referenced(entry.type_id, **id))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        for id in unused {
+            self.remove(id);
+        }
+
         self.asset_store.flush();
     }
 
+    /// unloads every asset
+    pub fn reset(&mut self) -> QPResult<()> {
+        self.asset_store.reset()?;
+        self.asset_map.clear();
+        self.refs.clear();
+        self.prefabs.clear();
+        self.hot_reload.assets.clear();
+        self.loads.clear();
+
+        Ok(())
+    }
+
+    /**
+     * keeps a prefab loaded once it has been instantiated, so the next
+     * instance doesn't read its file again. Cleared on reset
+     */
+    pub fn keep_prefab(&mut self, name: &str, prefab: Handle<assets::RPrefab>) {
+        self.prefabs.entry(name.to_string()).or_insert(prefab);
+    }
+
+    /// lets the kept prefabs unload once nothing else holds on to them
+    pub fn release_prefabs(&mut self) {
+        self.prefabs.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.asset_map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.asset_map.is_empty()
+    }
+
     pub fn register_asset<A: Component + std::fmt::Debug + PartialEq + 'static>(&mut self) {
         self.asset_store.register_component::<A>();
     }
 
+    fn index_of<A: 'static>(&self, id: u64) -> Option<VersionedIndex> {
+        self.asset_map
+            .get(&id)
+            .filter(|entry| entry.type_id == TypeId::of::<A>())
+            .map(|entry| entry.index)
+    }
+
+    fn remove(&mut self, id: u64) {
+        if let Some(entry) = self.asset_map.remove(&id) {
+            self.asset_store.set_to_delete(entry.index);
+            self.hot_reload.assets.remove(&id);
+            self.refs.forget(entry.type_id, id);
+        }
+    }
+
+    fn intern(&mut self, name: &str) -> QPResult<u64> {
+        let Some(interner) = self.string_interner() else {
+            return Err(QPError::SharedReferenceDropped);
+        };
+
+        let id = interner.borrow_mut().intern(name.to_string());
+
+        Ok(id)
+    }
+
     fn string_interner(&mut self) -> Option<Rc<RefCell<StringInterner>>> {
         let Some(string_interner) = self.strings.upgrade() else {
             #[cfg(debug_assertions)]
@@ -132,3 +286,205 @@ impl AssetManager {
         Some(string_interner)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Mesh(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Sound;
+
+    fn asset_manager(strings: &Rc<RefCell<StringInterner>>) -> AssetManager {
+        let mut asset_manager = AssetManager::init(Rc::downgrade(strings)).unwrap();
+        asset_manager.register_asset::<Mesh>();
+        asset_manager.register_asset::<Sound>();
+
+        asset_manager
+    }
+
+    #[test]
+    fn asset_handles_unload_with_the_last_strong_handle() {
+        let strings = Rc::new(RefCell::new(StringInterner::new()));
+        let mut assets = asset_manager(&strings);
+
+        let mesh = assets.load_asset("handles_mesh", Mesh(1)).unwrap();
+        let copy = assets.handle::<Mesh>("handles_mesh").unwrap();
+        assert_eq!(assets.get(&copy), Some(&Mesh(1)));
+        assert_eq!(mesh.strong_count(), 2);
+
+        // the same name as another type doesn't resolve
+        assert!(assets.handle::<Sound>("handles_mesh").is_none());
+        assert!(matches!(
+            assets.load_asset("handles_mesh", Sound),
+            Err(QPError::AssetTypeMismatch(..))
+        ));
+
+        // still used by the copy
+        assets.unload_asset(mesh);
+        assets.flush();
+        assert_eq!(assets.get(&copy), Some(&Mesh(1)));
+
+        // a handle read back from a file resolves, but only counts while bound
+        let saved = serde_yaml::to_string(&copy).unwrap();
+        let unbound: Handle<Mesh> = serde_yaml::from_str(&saved).unwrap();
+        assert!(!unbound.is_bound());
+        assert_eq!(assets.get(&unbound), Some(&Mesh(1)));
+
+        let weak = copy.downgrade();
+        drop(copy);
+        let loaded: Handle<Mesh> = {
+            let _handles = assets.bind_handles();
+            serde_yaml::from_str(&saved).unwrap()
+        };
+        assets.flush();
+        assert_eq!(assets.get(&loaded), Some(&Mesh(1)));
+
+        // another manager keeps its own counts, even for the same name
+        let mut other = asset_manager(&strings);
+        let other_mesh = other.load_asset("handles_mesh", Mesh(2)).unwrap();
+        let unloaded: Handle<Sound> = {
+            let _handles = other.bind_handles();
+            serde_yaml::from_str(&saved).unwrap()
+        };
+        assert_eq!(other_mesh.strong_count(), 1);
+        assert!(!unloaded.is_bound());
+
+        drop(loaded);
+        assert!(weak.upgrade().is_none());
+        assets.flush();
+        assert!(assets.is_empty());
+        assert!(assets.handle::<Mesh>("handles_mesh").is_none());
+    }
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Text(String);
+
+    fn read_text(path: &std::path::Path) -> QPResult<Text> {
+        match std::fs::read_to_string(path)? {
+            text if text.is_empty() => Err(QPError::FileContainsNil),
+            text => Ok(Text(text)),
+        }
+    }
+
+    fn write(path: &std::path::Path, text: &str, age: u64) {
+        std::fs::write(path, text).unwrap();
+
+        // the mtime alone marks a change, and some filesystems only keep whole seconds
+        let modified = std::time::SystemTime::now() - std::time::Duration::from_secs(age);
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn asset_hot_reload_keeps_handles() {
+        let strings = Rc::new(RefCell::new(StringInterner::new()));
+        let mut assets = asset_manager(&strings);
+        assets.register_asset::<Text>();
+
+        let path = std::env::temp_dir().join(format!("qp_hot_reload_{}.txt", std::process::id()));
+        write(&path, "first", 30);
+
+        let source = path.clone();
+        let text = assets
+            .load_watched("hot_reload_text", vec![path.clone()], move || {
+                read_text(&source)
+            })
+            .unwrap();
+
+        // nothing is checked until hot reloading is turned on
+        write(&path, "second", 20);
+        assert!(assets.poll_changes().is_empty());
+
+        assets.set_hot_reload(true);
+        assert_eq!(
+            assets.poll_changes(),
+            [AssetEvent::Reloaded { id: text.id() }]
+        );
+        assert_eq!(assets.get(&text), Some(&Text("second".into())));
+        assert!(assets.poll_changes().is_empty());
+
+        // a failed reload keeps the previous version
+        write(&path, "", 10);
+        assert!(matches!(
+            assets.poll_changes()[..],
+            [AssetEvent::ReloadFailed { id, .. }] if id == text.id()
+        ));
+        assert_eq!(assets.get(&text), Some(&Text("second".into())));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    // polls like the frame loop would, until nothing is loading anymore
+    fn finish_all(assets: &mut AssetManager) -> Vec<AssetEvent> {
+        let mut events = vec![];
+        let start = std::time::Instant::now();
+        while assets.loading() > 0 {
+            assert!(
+                start.elapsed().as_secs() < 10,
+                "background loads never finished"
+            );
+
+            events.extend(assets.finish_loads());
+            std::thread::yield_now();
+        }
+
+        events
+    }
+
+    #[test]
+    fn asset_background_loads_report_their_state() {
+        let strings = Rc::new(RefCell::new(StringInterner::new()));
+        let mut assets = asset_manager(&strings);
+
+        let mesh = assets
+            .load_async("async_mesh", || Ok(3u32), |vertices| Ok(Mesh(vertices)))
+            .unwrap();
+        let broken = assets
+            .load_async::<u32, Mesh>(
+                "async_broken",
+                || Err(QPError::FileContainsNil),
+                |vertices| Ok(Mesh(vertices)),
+            )
+            .unwrap();
+        let dropped = assets
+            .load_async("async_dropped", || Ok(()), |_| Ok(Sound))
+            .unwrap();
+        let dropped_id = dropped.id();
+        drop(dropped);
+
+        assert_eq!(assets.load_state(mesh.id()), LoadState::Loading);
+        assert!(assets.get(&mesh).is_none());
+
+        // loading the same name again joins the pending load
+        let again = assets
+            .load_async("async_mesh", || Ok(5u32), |vertices| Ok(Mesh(vertices)))
+            .unwrap();
+        assert_eq!(again, mesh);
+        assert!(matches!(
+            assets.load_async("async_mesh", || Ok(()), |_| Ok(Sound)),
+            Err(QPError::AssetTypeMismatch(..))
+        ));
+
+        let events = finish_all(&mut assets);
+        assert!(events.contains(&AssetEvent::Loaded { id: mesh.id() }));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, AssetEvent::LoadFailed { id, .. } if *id == broken.id())));
+        assert_eq!(events.len(), 2);
+
+        assert_eq!(assets.load_state(mesh.id()), LoadState::Loaded);
+        assert_eq!(assets.get(&mesh), Some(&Mesh(3)));
+        assert!(matches!(
+            assets.load_state(broken.id()),
+            LoadState::Failed(_)
+        ));
+        assert_eq!(assets.load_state(dropped_id), LoadState::NotLoaded);
+    }
+}
@@ -1,9 +1,8 @@
-use std::fs::File;
 use std::thread;
-use std::io::BufReader;
+use std::io::Cursor;
 use rodio::{Decoder, OutputStream, Sink};
 
-use crate::{core::prelude::to_abs_path, QPResult};
+use crate::{core::prelude::vfs, QPResult};
 
 pub struct QPAudio {}
 
@@ -18,7 +17,7 @@ impl QPAudio {
             let sink = Sink::try_new(&stream_handle).unwrap();
 
             // Add a dummy source of the sake of the example.
-            let file = BufReader::new(File::open(to_abs_path("assets/audio/jingles_NES00.ogg").unwrap()).unwrap());
+            let file = Cursor::new(vfs().read("audio/jingles_NES00.ogg").unwrap());
             let source = Decoder::new(file).unwrap();
             sink.append(source);
 
@@ -0,0 +1,54 @@
+//! Packs an assets directory into a .qpak archive
+//!
+//! usage: qp-pack [--store] <assets dir> <output.qpak>
+
+use std::path::PathBuf;
+
+use quipi::prelude::{
+    qp_core::{QPakCompression, QPakWriter},
+    QPError,
+};
+
+const USAGE: &str = "usage: qp-pack [--store] <assets dir> <output.qpak>
+
+  --store   don't compress any entry";
+
+fn main() {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let compression = match args.iter().position(|arg| arg == "--store") {
+        Some(i) => {
+            args.remove(i);
+            QPakCompression::Stored
+        }
+        None => QPakCompression::Deflate,
+    };
+
+    let [dir, output] = &args[..] else {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    };
+
+    if let Err(e) = pack(dir.into(), output.into(), compression) {
+        eprintln!("qp-pack: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn pack(dir: PathBuf, output: PathBuf, compression: QPakCompression) -> Result<(), QPError> {
+    let mut writer = QPakWriter::new();
+    let count = writer.add_dir(&dir, compression)?;
+    writer.save(&output)?;
+
+    let (size, stored_size) = writer.entries().fold((0, 0), |(size, stored), (_, entry)| {
+        (size + entry.size, stored + entry.stored_size)
+    });
+
+    println!(
+        "packed {count} files from {} into {}, {size} bytes stored as {stored_size}",
+        dir.display(),
+        output.display(),
+    );
+
+    Ok(())
+}
@@ -22,6 +22,24 @@ impl AnyMap {
             .map(|any| any.downcast_mut::<C>()).unwrap_or(None)
     }
 
+    pub fn remove<C: 'static>(&mut self) -> Option<C> {
+        self.0.remove(&TypeId::of::<C>())
+            .and_then(|any| any.downcast::<C>().ok())
+            .map(|item| *item)
+    }
+
+    pub fn contains<C: 'static>(&self) -> bool {
+        self.0.contains_key(&TypeId::of::<C>())
+    }
+
+    /// a pointer to every value, for handing disjoint values to different threads
+    pub(crate) fn value_ptrs(&mut self) -> HashMap<TypeId, *mut dyn std::any::Any> {
+        self.0
+            .iter_mut()
+            .map(|(type_id, value)| (*type_id, &mut **value as *mut dyn std::any::Any))
+            .collect()
+    }
+
     pub fn all_collections(&mut self) -> Vec<&Box<dyn std::any::Any>> {
         self.0.values().into_iter().collect()
     }
@@ -29,4 +47,26 @@ impl AnyMap {
     pub fn len(&self) -> usize {
         self.0.len()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Score(u32);
+
+    #[test]
+    fn anymap_insert_get_remove() {
+        let mut map = AnyMap::new();
+        assert!(map.get::<Score>().is_none());
+
+        map.insert(Score(0));
+        map.get_mut::<Score>().unwrap().0 += 10;
+        assert_eq!(map.get::<Score>(), Some(&Score(10)));
+
+        assert_eq!(map.remove::<Score>(), Some(Score(10)));
+        assert!(!map.contains::<Score>());
+        assert_eq!(map.len(), 0);
+    }
+}
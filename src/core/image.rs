@@ -24,6 +24,17 @@ impl QPImage {
         })
     }
 
+    /// decodes an image file that was read into memory
+    pub fn from_bytes(bytes: &[u8]) -> QPResult<Self> {
+        let img = image::load_from_memory(bytes)?;
+
+        Ok(Self {
+            width: img.width(),
+            height: img.height(),
+            img
+        })
+    }
+
     pub fn from_pixel_3(
         color: &[f32; 3]
     ) -> QPResult<Self> {
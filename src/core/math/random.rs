@@ -1,4 +1,5 @@
 use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
 pub struct Random {
     rng: rand_chacha::ChaCha8Rng,
@@ -11,6 +12,23 @@ impl Random {
         Self { rng }
     }
 
+    /// where the generator is in its sequence, to carry on from later with `from_state`
+    pub fn state(&self) -> RandomState {
+        RandomState {
+            seed: self.rng.get_seed(),
+            stream: self.rng.get_stream(),
+            word_pos: self.rng.get_word_pos(),
+        }
+    }
+
+    pub fn from_state(state: &RandomState) -> Self {
+        let mut rng = rand_chacha::ChaCha8Rng::from_seed(state.seed);
+        rng.set_stream(state.stream);
+        rng.set_word_pos(state.word_pos);
+
+        Self { rng }
+    }
+
     pub fn random(&mut self) -> f32 {
         self.rng.gen::<f32>()
     }
@@ -23,3 +41,10 @@ impl Random {
         self.random() < bias
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RandomState {
+    seed: [u8; 32],
+    stream: u64,
+    word_pos: u128,
+}
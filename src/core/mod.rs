@@ -2,8 +2,10 @@ mod collections;
 mod image;
 mod math;
 mod path;
+mod qpak;
 mod strings;
 mod time;
+mod vfs;
 
 pub mod prelude {
     use super::*;
@@ -11,8 +13,10 @@ pub mod prelude {
     pub use collections::*;
     pub use math::*;
     pub use path::*;
+    pub use qpak::*;
     pub use strings::*;
     pub use time::*;
+    pub use vfs::*;
 
     pub use self::image::QPImage;
 }
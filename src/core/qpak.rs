@@ -0,0 +1,532 @@
+/*!
+ * The .qpak asset archive, a single file that holds a whole assets/ tree.
+ * Every number is little endian.
+ *
+ * ```text
+ * header   magic       4 bytes   "QPAK"
+ *          version     u16       1
+ *          flags       u16       0, reserved
+ *          toc_offset  u64       where the table of contents starts
+ * data     the entries' stored bytes, one after the other
+ * toc      count       u32
+ *          count times:
+ *            path_len     u16
+ *            path         path_len bytes, utf-8, relative, `/` separated
+ *            offset       u64    where the stored bytes start
+ *            stored_size  u64
+ *            size         u64    after decompressing
+ *            compression  u8     0 stored as is, 1 deflate
+ *            crc32        u32    of the decompressed bytes
+ *          toc_crc32   u32       of every toc byte before it
+ * ```
+ *
+ * Build one from a directory with `cargo run --bin qp-pack -- <dir> <file.qpak>`
+ */
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
+    path::Path,
+    sync::Mutex,
+};
+
+use flate2::{read::DeflateDecoder, write::DeflateEncoder};
+
+use crate::{prelude::QPError, QPResult};
+
+use super::vfs::Mount;
+
+const MAGIC: &[u8; 4] = b"QPAK";
+const VERSION: u16 = 1;
+const HEADER_SIZE: u64 = 16;
+// the largest file an archive may hold after decompressing
+const MAX_ENTRY_SIZE: u64 = 1 << 32;
+// an entry with an empty path: path_len, offset, stored_size, size, compression and crc32
+const MIN_ENTRY_LEN: usize = 2 + 8 + 8 + 8 + 1 + 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QPakCompression {
+    Stored,
+    Deflate,
+}
+
+impl QPakCompression {
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::Stored => 0,
+            Self::Deflate => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Stored),
+            1 => Some(Self::Deflate),
+            _ => None,
+        }
+    }
+}
+
+/// a file in the table of contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct QPakEntry {
+    pub offset: u64,
+    pub stored_size: u64,
+    pub size: u64,
+    pub compression: QPakCompression,
+    pub crc32: u32,
+}
+
+/**
+ * Collects files and writes them out as a .qpak. Entries are compressed
+ * when they're added, and stored as is when compressing doesn't make
+ * them smaller, eg for png and ogg files
+ */
+#[derive(Debug, Default)]
+pub struct QPakWriter {
+    entries: Vec<(String, QPakEntry, Vec<u8>)>,
+}
+
+impl QPakWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// adding a path twice replaces the first file
+    pub fn add(&mut self, path: &str, data: &[u8], compression: QPakCompression) -> QPResult<()> {
+        let path = path.replace('\\', "/");
+        if path.len() > u16::MAX as usize {
+            return Err(QPError::InvalidArchive(format!(
+                "path is too long: {}",
+                path
+            )));
+        }
+
+        let compressed = match compression {
+            QPakCompression::Stored => None,
+            QPakCompression::Deflate => {
+                let mut encoder = DeflateEncoder::new(vec![], flate2::Compression::best());
+                encoder.write_all(data)?;
+
+                Some(encoder.finish()?).filter(|compressed| compressed.len() < data.len())
+            }
+        };
+
+        let (compression, stored) = match compressed {
+            Some(compressed) => (QPakCompression::Deflate, compressed),
+            None => (QPakCompression::Stored, data.to_vec()),
+        };
+        let entry = QPakEntry {
+            offset: 0,
+            stored_size: stored.len() as u64,
+            size: data.len() as u64,
+            compression,
+            crc32: crc32fast::hash(data),
+        };
+
+        self.entries.retain(|(existing, ..)| *existing != path);
+        self.entries.push((path, entry, stored));
+
+        Ok(())
+    }
+
+    /// adds every file under `dir`, named by their path relative to it. Returns how many were added
+    pub fn add_dir(&mut self, dir: &Path, compression: QPakCompression) -> QPResult<usize> {
+        let mut files = vec![];
+        collect_files(dir, &mut files)?;
+        files.sort();
+
+        for file in files.iter() {
+            let Ok(path) = file.strip_prefix(dir) else {
+                continue;
+            };
+
+            self.add(&path.to_string_lossy(), &fs::read(file)?, compression)?;
+        }
+
+        Ok(files.len())
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &QPakEntry)> {
+        self.entries
+            .iter()
+            .map(|(path, entry, _)| (path.as_str(), entry))
+    }
+
+    pub fn write_to(&self, mut out: impl Write) -> QPResult<()> {
+        let data_size: u64 = self
+            .entries
+            .iter()
+            .map(|(_, entry, _)| entry.stored_size)
+            .sum();
+
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&0u16.to_le_bytes())?;
+        out.write_all(&(HEADER_SIZE + data_size).to_le_bytes())?;
+
+        for (_, _, stored) in self.entries.iter() {
+            out.write_all(stored)?;
+        }
+
+        let mut toc = vec![];
+        toc.extend((self.entries.len() as u32).to_le_bytes());
+
+        let mut offset = HEADER_SIZE;
+        for (path, entry, _) in self.entries.iter() {
+            toc.extend((path.len() as u16).to_le_bytes());
+            toc.extend(path.as_bytes());
+            toc.extend(offset.to_le_bytes());
+            toc.extend(entry.stored_size.to_le_bytes());
+            toc.extend(entry.size.to_le_bytes());
+            toc.push(entry.compression.to_byte());
+            toc.extend(entry.crc32.to_le_bytes());
+
+            offset += entry.stored_size;
+        }
+
+        toc.extend(crc32fast::hash(&toc).to_le_bytes());
+        out.write_all(&toc)?;
+        out.flush()?;
+
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> QPResult<()> {
+        self.write_to(io::BufWriter::new(File::create(path)?))
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) -> QPResult<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        match path.is_dir() {
+            true => collect_files(&path, files)?,
+            false => files.push(path),
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * Reads files out of a .qpak. Only the table of contents is kept in
+ * memory, entries are read and checked against their checksum when
+ * they're asked for. Mount it on the vfs to load assets from it
+ */
+pub struct QPak<R = BufReader<File>> {
+    toc: HashMap<String, QPakEntry>,
+    reader: Mutex<R>,
+}
+
+impl QPak {
+    pub fn open(path: impl AsRef<Path>) -> QPResult<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> QPak<R> {
+    pub fn new(mut reader: R) -> QPResult<Self> {
+        let mut header = [0u8; HEADER_SIZE as usize];
+        reader.read_exact(&mut header)?;
+
+        if &header[0..4] != MAGIC {
+            return Err(QPError::InvalidArchive("not a qpak file".to_string()));
+        }
+
+        let version = u16::from_le_bytes([header[4], header[5]]);
+        if version != VERSION {
+            return Err(QPError::InvalidArchive(format!(
+                "unsupported version {}",
+                version
+            )));
+        }
+
+        let toc_offset = u64::from_le_bytes(header[8..16].try_into().unwrap());
+        reader.seek(SeekFrom::Start(toc_offset))?;
+
+        let mut toc = vec![];
+        reader.read_to_end(&mut toc)?;
+        let toc = parse_toc(&toc)?;
+
+        // every entry's bytes have to be in the data section, between the header and the toc
+        for (path, entry) in &toc {
+            let end = entry.offset.checked_add(entry.stored_size);
+            let in_data = entry.offset >= HEADER_SIZE && end.is_some_and(|end| end <= toc_offset);
+            let stored_as_is = entry.compression == QPakCompression::Stored;
+
+            if !in_data
+                || entry.size > MAX_ENTRY_SIZE
+                || (stored_as_is && entry.stored_size != entry.size)
+            {
+                return Err(QPError::InvalidArchive(format!(
+                    "the entry for {} is out of bounds",
+                    path
+                )));
+            }
+        }
+
+        Ok(Self {
+            toc,
+            reader: Mutex::new(reader),
+        })
+    }
+
+    pub fn entry(&self, path: &str) -> Option<&QPakEntry> {
+        self.toc.get(path)
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.toc.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.toc.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toc.is_empty()
+    }
+
+    pub fn read(&self, path: &str) -> QPResult<Vec<u8>> {
+        let Some(entry) = self.toc.get(path) else {
+            return Err(QPError::AssetFileNotFound(path.to_string()));
+        };
+
+        let mut stored = vec![0u8; entry.stored_size as usize];
+        {
+            let mut reader = self
+                .reader
+                .lock()
+                .map_err(|e| QPError::MutexLockFailed(e.to_string()))?;
+
+            reader.seek(SeekFrom::Start(entry.offset))?;
+            reader.read_exact(&mut stored)?;
+        }
+
+        let data = match entry.compression {
+            QPakCompression::Stored => stored,
+            QPakCompression::Deflate => {
+                // never more than the toc says, whatever the stored bytes decompress to
+                let mut data = vec![];
+                DeflateDecoder::new(&stored[..])
+                    .take(entry.size)
+                    .read_to_end(&mut data)?;
+
+                data
+            }
+        };
+
+        if data.len() as u64 != entry.size || crc32fast::hash(&data) != entry.crc32 {
+            return Err(QPError::ArchiveChecksumMismatch(path.to_string()));
+        }
+
+        Ok(data)
+    }
+}
+
+impl<R: Read + Seek + Send> Mount for QPak<R> {
+    fn read(&self, path: &str) -> Option<io::Result<Vec<u8>>> {
+        if !self.toc.contains_key(path) {
+            return None;
+        }
+
+        Some(
+            QPak::<R>::read(self, path)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string())),
+        )
+    }
+
+    fn contains(&self, path: &str) -> bool {
+        self.toc.contains_key(path)
+    }
+}
+
+fn parse_toc(toc: &[u8]) -> QPResult<HashMap<String, QPakEntry>> {
+    let corrupt = || QPError::InvalidArchive("the table of contents is corrupt".to_string());
+
+    let Some(checked) = toc.len().checked_sub(4) else {
+        return Err(corrupt());
+    };
+    let (body, crc) = toc.split_at(checked);
+    if crc32fast::hash(body) != u32::from_le_bytes(crc.try_into().unwrap()) {
+        return Err(corrupt());
+    }
+
+    let mut bytes = body;
+    let mut take = |len: usize| -> QPResult<&[u8]> {
+        if bytes.len() < len {
+            return Err(corrupt());
+        }
+
+        let (taken, rest) = bytes.split_at(len);
+        bytes = rest;
+
+        Ok(taken)
+    };
+
+    let count = u32::from_le_bytes(take(4)?.try_into().unwrap()) as usize;
+    // checked before allocating anything for them
+    if count > body.len() / MIN_ENTRY_LEN {
+        return Err(corrupt());
+    }
+
+    let mut entries = HashMap::with_capacity(count);
+    for _ in 0..count {
+        let path_len = u16::from_le_bytes(take(2)?.try_into().unwrap());
+        let path = String::from_utf8(take(path_len as usize)?.to_vec()).map_err(|_| corrupt())?;
+
+        let entry = QPakEntry {
+            offset: u64::from_le_bytes(take(8)?.try_into().unwrap()),
+            stored_size: u64::from_le_bytes(take(8)?.try_into().unwrap()),
+            size: u64::from_le_bytes(take(8)?.try_into().unwrap()),
+            compression: QPakCompression::from_byte(take(1)?[0]).ok_or_else(corrupt)?,
+            crc32: u32::from_le_bytes(take(4)?.try_into().unwrap()),
+        };
+
+        entries.insert(path, entry);
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::prelude::qp_core::{DirectoryMount, Vfs};
+
+    fn archive() -> Vec<u8> {
+        let mut writer = QPakWriter::new();
+        writer
+            .add(
+                "scenes/level.yaml",
+                "entities: []\n".repeat(100).as_bytes(),
+                QPakCompression::Deflate,
+            )
+            .unwrap();
+        // doesn't get smaller, so it's stored as is
+        writer
+            .add(
+                "textures/noise.png",
+                &[7, 1, 250, 3],
+                QPakCompression::Deflate,
+            )
+            .unwrap();
+        writer
+            .add("fonts/empty.ttf", &[], QPakCompression::Stored)
+            .unwrap();
+
+        let mut bytes = vec![];
+        writer.write_to(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    #[test]
+    fn qpak_round_trip() {
+        let pak = QPak::new(Cursor::new(archive())).unwrap();
+        assert_eq!(pak.len(), 3);
+
+        let level = pak.read("scenes/level.yaml").unwrap();
+        assert_eq!(level, "entities: []\n".repeat(100).as_bytes());
+        let entry = pak.entry("scenes/level.yaml").unwrap();
+        assert_eq!(entry.compression, QPakCompression::Deflate);
+        assert!(entry.stored_size < entry.size);
+
+        assert_eq!(pak.read("textures/noise.png").unwrap(), [7, 1, 250, 3]);
+        assert_eq!(
+            pak.entry("textures/noise.png").unwrap().compression,
+            QPakCompression::Stored
+        );
+        assert!(pak.read("fonts/empty.ttf").unwrap().is_empty());
+        assert!(matches!(
+            pak.read("fonts/missing.ttf"),
+            Err(QPError::AssetFileNotFound(_))
+        ));
+
+        // the archive overrides the directory mounted before it
+        let dir = std::env::temp_dir().join(format!("qp_qpak_{}", std::process::id()));
+        fs::create_dir_all(dir.join("scenes")).unwrap();
+        fs::write(dir.join("scenes/level.yaml"), "loose").unwrap();
+        fs::write(dir.join("scenes/menu.yaml"), "menu").unwrap();
+
+        let mut vfs = Vfs::new();
+        vfs.mount(DirectoryMount::new(&dir)).mount(pak);
+        assert_eq!(vfs.read("scenes/level.yaml").unwrap(), level);
+        assert_eq!(vfs.read_to_string("scenes/menu.yaml").unwrap(), "menu");
+        assert_eq!(vfs.disk_path("scenes/level.yaml"), None);
+
+        // and packing the directory gives back the same files
+        let mut writer = QPakWriter::new();
+        assert_eq!(writer.add_dir(&dir, QPakCompression::Deflate).unwrap(), 2);
+        let mut bytes = vec![];
+        writer.write_to(&mut bytes).unwrap();
+
+        let pak = QPak::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(pak.read("scenes/level.yaml").unwrap(), b"loose");
+        assert_eq!(pak.read("scenes/menu.yaml").unwrap(), b"menu");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn qpak_detects_corruption() {
+        let bytes = archive();
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'Z';
+        assert!(matches!(
+            QPak::new(Cursor::new(wrong_magic)),
+            Err(QPError::InvalidArchive(_))
+        ));
+
+        let mut truncated = bytes.clone();
+        truncated.truncate(truncated.len() - 10);
+        assert!(matches!(
+            QPak::new(Cursor::new(truncated)),
+            Err(QPError::InvalidArchive(_))
+        ));
+
+        // the first entry's data starts right after the header
+        let mut flipped = bytes;
+        flipped[HEADER_SIZE as usize + 2] ^= 0xff;
+        let pak = QPak::new(Cursor::new(flipped)).unwrap();
+        assert!(pak.read("scenes/level.yaml").is_err());
+        assert_eq!(pak.read("textures/noise.png").unwrap(), [7, 1, 250, 3]);
+
+        // a toc with a valid checksum can still point outside the data
+        let patched = |at: fn(&[u8], usize) -> usize, value: &[u8]| {
+            let mut bytes = archive();
+            let toc_offset = u64::from_le_bytes(bytes[8..16].try_into().unwrap()) as usize;
+            let at = at(&bytes, toc_offset);
+            bytes[at..at + value.len()].copy_from_slice(value);
+
+            let crc_at = bytes.len() - 4;
+            let crc = crc32fast::hash(&bytes[toc_offset..crc_at]);
+            bytes[crc_at..].copy_from_slice(&crc.to_le_bytes());
+
+            QPak::new(Cursor::new(bytes))
+        };
+
+        // the first entry's offset, stored_size and size
+        let fields: [fn(&[u8], usize) -> usize; 3] = [
+            |bytes, toc| toc + 6 + u16::from_le_bytes([bytes[toc + 4], bytes[toc + 5]]) as usize,
+            |bytes, toc| toc + 14 + u16::from_le_bytes([bytes[toc + 4], bytes[toc + 5]]) as usize,
+            |bytes, toc| toc + 22 + u16::from_le_bytes([bytes[toc + 4], bytes[toc + 5]]) as usize,
+        ];
+        for (field, value) in [(0, 0), (0, u64::MAX), (1, u64::MAX - 8), (2, u64::MAX)] {
+            assert!(matches!(
+                patched(fields[field], &value.to_le_bytes()),
+                Err(QPError::InvalidArchive(_))
+            ));
+        }
+
+        // more entries than the toc has room for, rejected before allocating for them
+        assert!(matches!(
+            patched(|_, toc| toc, &u32::MAX.to_le_bytes()),
+            Err(QPError::InvalidArchive(_))
+        ));
+    }
+}
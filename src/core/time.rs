@@ -1,5 +1,5 @@
 use crate::QPResult;
-use std::time::{Instant, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 #[derive(Debug)]
 pub struct Timer {
@@ -39,6 +39,15 @@ impl Timer {
 
         (tick - self.first_tick) as f32 / 1000.0
     }
+
+    /// winds the timer so `elapsed` carries on from `seconds`
+    pub fn set_elapsed(&mut self, seconds: f32) {
+        let elapsed = Duration::from_secs_f32(seconds.max(0.0));
+
+        self.timer = Instant::now().checked_sub(elapsed).unwrap_or_else(Instant::now);
+        self.first_tick = 0;
+        self.last_tick = self.ticks();
+    }
 }
 
 pub struct Interval {
@@ -0,0 +1,340 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use crate::{prelude::QPError, QPResult};
+
+use super::qpak::QPak;
+
+/**
+ * A source of asset files, eg a directory or an archive. Paths are
+ * relative to the root of the mount and use `/`
+ */
+pub trait Mount: Send + Sync {
+    /// None if the mount doesn't have the file
+    fn read(&self, path: &str) -> Option<io::Result<Vec<u8>>>;
+
+    fn contains(&self, path: &str) -> bool;
+
+    /// where the mount keeps the file on disk, for mounts backed by a directory
+    fn disk_path(&self, _path: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// the files under a directory
+#[derive(Debug, Clone)]
+pub struct DirectoryMount {
+    root: PathBuf,
+}
+
+impl DirectoryMount {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl Mount for DirectoryMount {
+    fn read(&self, path: &str) -> Option<io::Result<Vec<u8>>> {
+        let path = self.root.join(path);
+        if !path.is_file() {
+            return None;
+        }
+
+        Some(fs::read(path))
+    }
+
+    fn contains(&self, path: &str) -> bool {
+        self.root.join(path).is_file()
+    }
+
+    fn disk_path(&self, path: &str) -> Option<PathBuf> {
+        self.root.is_dir().then(|| self.root.join(path))
+    }
+}
+
+/**
+ * Every asset loader reads its files through here. Mounts are searched
+ * from the last one to the first, so a file in a later mount overrides
+ * the same path in earlier ones, eg for mods and patches. In debug
+ * builds the game's source assets, see `mount_source`, come before all of them.
+ *
+ * Absolute paths skip the mounts and are read from disk
+ */
+#[derive(Default)]
+pub struct Vfs {
+    mounts: Vec<Box<dyn Mount>>,
+    source: Option<DirectoryMount>,
+}
+
+impl Vfs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * mounts, in order, the assets directory next to the executable, the
+     * .qpak archives next to the executable, sorted by name so eg
+     * patch_02.qpak overrides patch_01.qpak, and last the assets directory
+     * in the user's data dir, so mods there override what the game ships with
+     */
+    pub fn with_default_mounts() -> Self {
+        Self::with_mounts_in(exe_dir(), user_data_dir())
+    }
+
+    fn with_mounts_in(exe_dir: Option<PathBuf>, user_dir: Option<PathBuf>) -> Self {
+        let mut vfs = Self::new();
+
+        if let Some(exe_dir) = &exe_dir {
+            vfs.mount(DirectoryMount::new(exe_dir.join("assets")));
+        }
+
+        for archive in exe_dir.map(archives_in).unwrap_or_default() {
+            match QPak::open(&archive) {
+                Ok(pak) => {
+                    vfs.mount(pak);
+                }
+                Err(_e) => {
+                    #[cfg(debug_assertions)]
+                    println!("[vfs] couldn't mount {}: {}", archive.display(), _e);
+                }
+            }
+        }
+
+        if let Some(user_dir) = user_dir {
+            vfs.mount(DirectoryMount::new(user_dir.join("assets")));
+        }
+
+        vfs
+    }
+
+    /// files in the new mount override the ones already mounted
+    pub fn mount(&mut self, mount: impl Mount + 'static) -> &mut Self {
+        self.mounts.push(Box::new(mount));
+
+        self
+    }
+
+    /**
+     * in debug builds, reads `<crate_dir>/assets` before every mount, so
+     * edited assets are used without a rebuild and hot reloading watches
+     * the files being edited. Games pass their own `env!("CARGO_MANIFEST_DIR")`.
+     * Does nothing in release builds, they only read what ships with the game
+     */
+    pub fn mount_source(&mut self, crate_dir: impl AsRef<Path>) -> &mut Self {
+        let source = crate_dir.as_ref().join("assets");
+        if cfg!(debug_assertions) && source.is_dir() {
+            self.source = Some(DirectoryMount::new(source));
+        }
+
+        self
+    }
+
+    pub fn unmount_all(&mut self) {
+        self.mounts.clear();
+        self.source = None;
+    }
+
+    pub fn read(&self, path: &str) -> QPResult<Vec<u8>> {
+        if Path::new(path).is_absolute() {
+            return Ok(fs::read(path)?);
+        }
+
+        let path = normalize(path);
+        for mount in self.search_order() {
+            if let Some(file) = mount.read(&path) {
+                return Ok(file?);
+            }
+        }
+
+        Err(QPError::AssetFileNotFound(path))
+    }
+
+    pub fn read_to_string(&self, path: &str) -> QPResult<String> {
+        String::from_utf8(self.read(path)?).map_err(|e| QPError::Generic(e.to_string()))
+    }
+
+    pub fn exists(&self, path: &str) -> bool {
+        if Path::new(path).is_absolute() {
+            return Path::new(path).is_file();
+        }
+
+        let path = normalize(path);
+        self.search_order().any(|mount| mount.contains(&path))
+    }
+
+    /**
+     * the file on disk that `read` would return, or where it would be
+     * created in the first directory searched when no mount has it. None
+     * for files read from an archive. Used to watch files and to save them
+     */
+    pub fn disk_path(&self, path: &str) -> Option<PathBuf> {
+        if Path::new(path).is_absolute() {
+            return Some(path.into());
+        }
+
+        let path = normalize(path);
+        match self.search_order().find(|mount| mount.contains(&path)) {
+            Some(mount) => mount.disk_path(&path),
+            None => self.search_order().find_map(|mount| mount.disk_path(&path)),
+        }
+    }
+
+    fn search_order(&self) -> impl Iterator<Item = &dyn Mount> {
+        let source = self.source.iter().map(|mount| mount as &dyn Mount);
+
+        source.chain(self.mounts.iter().rev().map(|mount| mount.as_ref()))
+    }
+}
+
+fn global() -> &'static RwLock<Vfs> {
+    static VFS: OnceLock<RwLock<Vfs>> = OnceLock::new();
+
+    VFS.get_or_init(|| RwLock::new(Vfs::with_default_mounts()))
+}
+
+/// the virtual filesystem shared by every loader, created with the default mounts
+pub fn vfs() -> RwLockReadGuard<'static, Vfs> {
+    global().read().unwrap()
+}
+
+pub fn vfs_mut() -> RwLockWriteGuard<'static, Vfs> {
+    global().write().unwrap()
+}
+
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+fn archives_in(dir: PathBuf) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut archives = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "qpak"))
+        .collect::<Vec<_>>();
+    archives.sort();
+
+    archives
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    let mut path = std::env::current_exe().ok()?;
+    path.pop();
+
+    Some(path)
+}
+
+// <data dir>/<executable name>, eg ~/.local/share/bubbles
+fn user_data_dir() -> Option<PathBuf> {
+    let app = std::env::current_exe().ok()?.file_stem()?.to_owned();
+    let env_dir = |name: &str| std::env::var_os(name).map(PathBuf::from);
+
+    let data_dir = if cfg!(target_os = "windows") {
+        env_dir("APPDATA")?
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME")?.join("Library/Application Support")
+    } else {
+        env_dir("XDG_DATA_HOME").or_else(|| Some(env_dir("HOME")?.join(".local/share")))?
+    };
+
+    Some(data_dir.join(app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::qp_core::{QPakCompression, QPakWriter};
+
+    #[test]
+    fn vfs_later_mounts_override_earlier_ones() {
+        let root = std::env::temp_dir().join(format!("qp_vfs_{}", std::process::id()));
+        let (base, patch) = (root.join("base"), root.join("patch"));
+        fs::create_dir_all(base.join("scenes")).unwrap();
+        fs::create_dir_all(patch.join("scenes")).unwrap();
+
+        fs::write(base.join("scenes/level.yaml"), "base").unwrap();
+        fs::write(base.join("scenes/menu.yaml"), "menu").unwrap();
+        fs::write(patch.join("scenes/level.yaml"), "patched").unwrap();
+
+        let mut vfs = Vfs::new();
+        vfs.mount(DirectoryMount::new(&base))
+            .mount(DirectoryMount::new(&patch));
+
+        assert_eq!(vfs.read_to_string("scenes/level.yaml").unwrap(), "patched");
+        assert_eq!(vfs.read_to_string("./scenes/menu.yaml").unwrap(), "menu");
+        assert!(matches!(
+            vfs.read("scenes/missing.yaml"),
+            Err(QPError::AssetFileNotFound(_))
+        ));
+
+        // files are found where they are, new ones go to the last mount
+        assert_eq!(
+            vfs.disk_path("scenes/menu.yaml"),
+            Some(base.join("scenes/menu.yaml"))
+        );
+        assert_eq!(
+            vfs.disk_path("scenes/new.yaml"),
+            Some(patch.join("scenes/new.yaml"))
+        );
+
+        let absolute = base.join("scenes/level.yaml");
+        assert_eq!(vfs.read(absolute.to_str().unwrap()).unwrap(), b"base");
+
+        // the source assets come first in debug builds, even over later mounts
+        let source = root.join("game");
+        fs::create_dir_all(source.join("assets/scenes")).unwrap();
+        fs::write(source.join("assets/scenes/level.yaml"), "edited").unwrap();
+        vfs.mount_source(&source)
+            .mount(DirectoryMount::new(root.join("mod")));
+
+        #[cfg(debug_assertions)]
+        {
+            assert_eq!(vfs.read_to_string("scenes/level.yaml").unwrap(), "edited");
+            assert_eq!(
+                vfs.disk_path("scenes/new.yaml"),
+                Some(source.join("assets/scenes/new.yaml"))
+            );
+        }
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn vfs_user_data_overrides_shipped_assets() {
+        let root = std::env::temp_dir().join(format!("qp_vfs_mounts_{}", std::process::id()));
+        let (exe, user) = (root.join("exe"), root.join("user"));
+        fs::create_dir_all(exe.join("assets/scenes")).unwrap();
+        fs::create_dir_all(user.join("assets/scenes")).unwrap();
+
+        fs::write(exe.join("assets/scenes/level.yaml"), "loose").unwrap();
+        fs::write(exe.join("assets/scenes/menu.yaml"), "loose").unwrap();
+        let mut pak = QPakWriter::new();
+        pak.add("scenes/level.yaml", b"archive", QPakCompression::Stored)
+            .unwrap();
+        pak.add("scenes/menu.yaml", b"archive", QPakCompression::Stored)
+            .unwrap();
+        pak.save(exe.join("data.qpak")).unwrap();
+        fs::write(user.join("assets/scenes/level.yaml"), "mod").unwrap();
+
+        let vfs = Vfs::with_mounts_in(Some(exe), Some(user.clone()));
+
+        assert_eq!(vfs.read_to_string("scenes/level.yaml").unwrap(), "mod");
+        assert_eq!(vfs.read_to_string("scenes/menu.yaml").unwrap(), "archive");
+        // saves go to the user's data dir
+        assert_eq!(
+            vfs.disk_path("scenes/new.yaml"),
+            Some(user.join("assets/scenes/new.yaml"))
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+use super::{entity_manager::EntityManager, indexed_array::VersionedIndex, prelude::Component};
+
+/**
+ * A group of components that are added and removed together. Every
+ * component is a bundle of one, and tuples of up to 12 bundles are
+ * bundles. Derive it for a struct whose fields are all bundles
+ */
+pub trait Bundle: Sized + 'static {
+    fn add_to(self, entity: &VersionedIndex, entity_manager: &mut EntityManager);
+
+    /// true if the entity has every component in the bundle
+    fn is_in(entity: &VersionedIndex, entity_manager: &EntityManager) -> bool;
+
+    /// removes the components in the bundle that the entity has
+    fn remove_from(entity: &VersionedIndex, entity_manager: &mut EntityManager);
+
+    /// removes every component in the bundle and hands them back. Takes nothing unless the entity has them all
+    fn take_from(entity: &VersionedIndex, entity_manager: &mut EntityManager) -> Option<Self>;
+
+    /// adds a bundle to each entity, looking every storage up once
+    fn add_batch(
+        bundles: Vec<Self>,
+        entities: &[VersionedIndex],
+        entity_manager: &mut EntityManager,
+    ) {
+        for (bundle, entity) in bundles.into_iter().zip(entities.iter()) {
+            bundle.add_to(entity, entity_manager);
+        }
+    }
+}
+
+impl<C: Component + std::fmt::Debug + PartialEq + 'static> Bundle for C {
+    fn add_to(self, entity: &VersionedIndex, entity_manager: &mut EntityManager) {
+        entity_manager.insert(entity, self);
+        C::add_required(entity, entity_manager);
+    }
+
+    fn is_in(entity: &VersionedIndex, entity_manager: &EntityManager) -> bool {
+        entity_manager.get::<C>(entity).is_some()
+    }
+
+    fn remove_from(entity: &VersionedIndex, entity_manager: &mut EntityManager) {
+        entity_manager.take::<C>(entity);
+    }
+
+    fn take_from(entity: &VersionedIndex, entity_manager: &mut EntityManager) -> Option<Self> {
+        entity_manager.take::<C>(entity)
+    }
+
+    fn add_batch(
+        bundles: Vec<Self>,
+        entities: &[VersionedIndex],
+        entity_manager: &mut EntityManager,
+    ) {
+        entity_manager.add_many(entities, bundles);
+    }
+}
+
+/// an optional part of a bundle. Taking it always succeeds
+impl<B: Bundle> Bundle for Option<B> {
+    fn add_to(self, entity: &VersionedIndex, entity_manager: &mut EntityManager) {
+        if let Some(bundle) = self {
+            bundle.add_to(entity, entity_manager);
+        }
+    }
+
+    fn is_in(_entity: &VersionedIndex, _entity_manager: &EntityManager) -> bool {
+        true
+    }
+
+    fn remove_from(entity: &VersionedIndex, entity_manager: &mut EntityManager) {
+        B::remove_from(entity, entity_manager);
+    }
+
+    fn take_from(entity: &VersionedIndex, entity_manager: &mut EntityManager) -> Option<Self> {
+        Some(B::take_from(entity, entity_manager))
+    }
+}
+
+macro_rules! impl_bundle {
+    ($($name:ident $item:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$($name: Bundle),+> Bundle for ($($name,)+) {
+            fn add_to(self, entity: &VersionedIndex, entity_manager: &mut EntityManager) {
+                let ($($name,)+) = self;
+
+                $($name.add_to(entity, entity_manager);)+
+            }
+
+            fn is_in(entity: &VersionedIndex, entity_manager: &EntityManager) -> bool {
+                $($name::is_in(entity, entity_manager))&&+
+            }
+
+            fn remove_from(entity: &VersionedIndex, entity_manager: &mut EntityManager) {
+                $($name::remove_from(entity, entity_manager);)+
+            }
+
+            fn take_from(entity: &VersionedIndex, entity_manager: &mut EntityManager) -> Option<Self> {
+                // checked up front, so a missing part doesn't lose the parts taken before it
+                if !Self::is_in(entity, entity_manager) {
+                    return None;
+                }
+
+                Some(($($name::take_from(entity, entity_manager)?,)+))
+            }
+
+            fn add_batch(
+                bundles: Vec<Self>,
+                entities: &[VersionedIndex],
+                entity_manager: &mut EntityManager
+            ) {
+                // split the bundles into a column per part
+                let ($(mut $name,)+) = ($(Vec::<$name>::with_capacity(bundles.len()),)+);
+                for ($($item,)+) in bundles {
+                    $($name.push($item);)+
+                }
+
+                $(Bundle::add_batch($name, entities, entity_manager);)+
+            }
+        }
+    };
+}
+
+impl_bundle!(A a);
+impl_bundle!(A a, B b);
+impl_bundle!(A a, B b, C c);
+impl_bundle!(A a, B b, C c, D d);
+impl_bundle!(A a, B b, C c, D d, E e);
+impl_bundle!(A a, B b, C c, D d, E e, F f);
+impl_bundle!(A a, B b, C c, D d, E e, F f, G g);
+impl_bundle!(A a, B b, C c, D d, E e, F f, G g, H h);
+impl_bundle!(A a, B b, C c, D d, E e, F f, G g, H h, I i);
+impl_bundle!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j);
+impl_bundle!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j, K k);
+impl_bundle!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j, K k, L l);
@@ -0,0 +1,232 @@
+use crate::{
+    prelude::{qp_ecs::Bundle, Schema, VersionedIndex},
+    registry::GlobalRegistry,
+};
+
+type Command = Box<dyn FnOnce(&mut GlobalRegistry, &mut [Option<VersionedIndex>])>;
+
+/**
+ * An entity that commands can target. Either one that already exists,
+ * or a placeholder for an entity spawned earlier in the same buffer.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommandEntity {
+    Entity(VersionedIndex),
+    Placeholder(usize),
+}
+
+impl From<VersionedIndex> for CommandEntity {
+    fn from(entity: VersionedIndex) -> Self {
+        Self::Entity(entity)
+    }
+}
+
+impl CommandEntity {
+    fn resolve(
+        &self,
+        registry: &GlobalRegistry,
+        spawned: &[Option<VersionedIndex>],
+    ) -> Option<VersionedIndex> {
+        let entity = match self {
+            Self::Entity(entity) => *entity,
+            Self::Placeholder(id) => spawned.get(*id).copied().flatten()?,
+        };
+
+        registry.entity_manager.is_valid(&entity).then_some(entity)
+    }
+}
+
+/**
+ * Queues structural changes to the entity manager so they can be
+ * made while iterating. Commands are applied in the order they were
+ * queued when the world is flushed.
+ */
+#[derive(Default)]
+pub struct Commands {
+    queue: Vec<Command>,
+    placeholders: usize,
+}
+
+impl Commands {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn(&mut self) -> CommandEntity {
+        let placeholder = self.next_placeholder();
+
+        self.queue.push(Box::new(move |registry, spawned| {
+            spawned[placeholder] = Some(registry.entity_manager.create());
+        }));
+
+        CommandEntity::Placeholder(placeholder)
+    }
+
+    pub fn spawn_schema(&mut self, schema: impl Schema + 'static) -> CommandEntity {
+        let placeholder = self.next_placeholder();
+
+        self.queue.push(Box::new(move |registry, spawned| {
+            match schema.build_entity(registry) {
+                Ok(entity) => spawned[placeholder] = Some(entity),
+                Err(_e) => {
+                    #[cfg(debug_assertions)]
+                    println!("[commands] failed to spawn schema: {:?}", _e);
+                }
+            }
+        }));
+
+        CommandEntity::Placeholder(placeholder)
+    }
+
+    pub fn despawn(&mut self, entity: impl Into<CommandEntity>) {
+        let entity = entity.into();
+
+        self.queue.push(Box::new(move |registry, spawned| {
+            if let Some(entity) = entity.resolve(registry, spawned) {
+                registry.entity_manager.set_to_delete(entity);
+            }
+        }));
+    }
+
+    /// despawns the entity and all its descendants
+    pub fn despawn_recursive(&mut self, entity: impl Into<CommandEntity>) {
+        let entity = entity.into();
+
+        self.queue.push(Box::new(move |registry, spawned| {
+            if let Some(entity) = entity.resolve(registry, spawned) {
+                registry.entity_manager.set_to_delete_recursive(entity);
+            }
+        }));
+    }
+
+    /// attaches `child` to `parent`. Both can be placeholders
+    pub fn set_parent(
+        &mut self,
+        child: impl Into<CommandEntity>,
+        parent: impl Into<CommandEntity>,
+    ) -> &mut Self {
+        let child = child.into();
+        let parent = parent.into();
+
+        self.queue.push(Box::new(move |registry, spawned| {
+            let child = child.resolve(registry, spawned);
+            let parent = parent.resolve(registry, spawned);
+
+            if let (Some(child), Some(parent)) = (child, parent) {
+                registry.entity_manager.set_parent(&child, &parent);
+            }
+        }));
+
+        self
+    }
+
+    pub fn add<B: Bundle>(&mut self, entity: impl Into<CommandEntity>, bundle: B) -> &mut Self {
+        let entity = entity.into();
+
+        self.queue.push(Box::new(move |registry, spawned| {
+            if let Some(entity) = entity.resolve(registry, spawned) {
+                registry.entity_manager.add(&entity, bundle);
+            }
+        }));
+
+        self
+    }
+
+    pub fn remove<B: Bundle>(&mut self, entity: impl Into<CommandEntity>) -> &mut Self {
+        let entity = entity.into();
+
+        self.queue.push(Box::new(move |registry, spawned| {
+            if let Some(entity) = entity.resolve(registry, spawned) {
+                registry.entity_manager.remove::<B>(&entity);
+            }
+        }));
+
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /**
+     * runs every queued command in order and returns the entities
+     * that were spawned, indexed by placeholder
+     */
+    pub fn apply(&mut self, registry: &mut GlobalRegistry) -> Vec<Option<VersionedIndex>> {
+        let mut spawned = vec![None; self.placeholders];
+
+        for command in self.queue.drain(..) {
+            command(registry, &mut spawned);
+        }
+
+        self.placeholders = 0;
+
+        spawned
+    }
+
+    fn next_placeholder(&mut self) -> usize {
+        self.placeholders += 1;
+
+        self.placeholders - 1
+    }
+}
+
+impl std::fmt::Debug for Commands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Commands")
+            .field("queued", &self.queue.len())
+            .field("placeholders", &self.placeholders)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::qp_ecs::Component;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Health(u32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Poisoned;
+
+    #[test]
+    fn commands_apply_in_order() {
+        let mut registry = GlobalRegistry::init().unwrap();
+        registry
+            .entity_manager
+            .register_component::<Health>()
+            .register_component::<Poisoned>();
+
+        let existing = registry.entity_manager.create();
+        registry.entity_manager.add(&existing, Health(5));
+
+        let mut commands = Commands::new();
+        let spawned = commands.spawn();
+        commands
+            .add(spawned, Health(10))
+            .add(spawned, Poisoned)
+            .remove::<Poisoned>(spawned)
+            .add(existing, Poisoned);
+        commands.despawn(existing);
+
+        // nothing happens until the buffer is applied
+        assert_eq!(registry.entity_manager.count(), 1);
+        assert!(registry.entity_manager.get::<Poisoned>(&existing).is_none());
+
+        let entities = commands.apply(&mut registry);
+        registry.flush();
+
+        let spawned = entities[0].unwrap();
+        assert!(commands.is_empty());
+        assert_eq!(registry.entity_manager.count(), 1);
+        assert!(!registry.entity_manager.is_valid(&existing));
+        assert_eq!(registry.entity_manager.get::<Health>(&spawned), Some(&Health(10)));
+        assert!(registry.entity_manager.get::<Poisoned>(&spawned).is_none());
+    }
+}
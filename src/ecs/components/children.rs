@@ -3,7 +3,11 @@ use super::super::prelude::{
     VersionedIndex
 };
 
-#[derive(Component, Debug, PartialEq)]
+/**
+ * Entities attached to this one. Managed by `EntityManager::set_parent`
+ */
+#[derive(Component, Debug, PartialEq, Clone, Default)]
+#[component(name = "CChildren")]
 pub struct CChildren {
     pub list: Vec<VersionedIndex>
 }
@@ -1,7 +1,8 @@
 use super::super::prelude::Component;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Component, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Component, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[component(name = "CCircle")]
 pub struct CCircle {
     pub radius: f32,
     pub center_x: f32,
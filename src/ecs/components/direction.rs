@@ -2,6 +2,7 @@
 * 3D direction vector
 */
 #[derive(Debug, Component, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[component(name = "CDirection")]
 pub struct CDirection {
     pub x: f32,
     pub y: f32,
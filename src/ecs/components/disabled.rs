@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+use super::super::prelude::Component;
+
+/**
+ * Queries skip disabled entities unless they opt in with `IncludeDisabled`.
+ * Children of a disabled entity are disabled too. Managed by
+ * `EntityManager::set_enabled`, don't add it directly
+ */
+#[derive(Debug, Component, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[component(name = "CDisabled")]
+pub struct CDisabled {
+    /// disabled itself, rather than only through a disabled ancestor
+    pub explicit: bool,
+}
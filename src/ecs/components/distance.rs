@@ -1,5 +1,6 @@
 use super::super::prelude::Component;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Component, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Component, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[component(name = "CDistance")]
 pub struct CDistance(pub f32);
\ No newline at end of file
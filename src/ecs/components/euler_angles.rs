@@ -6,6 +6,7 @@ use super::super::prelude::Component;
 * https://en.wikipedia.org/wiki/Euler_angles
 */
 #[derive(Debug, Component, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[component(name = "CEulerAngles")]
 pub struct CEulerAngles {
     pub pitch: f32,
     pub yaw: f32,
@@ -2,6 +2,7 @@ use super::super::prelude::Component;
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Component, Serialize, Deserialize, PartialEq)]
+#[component(name = "CGizmo")]
 pub struct CGizmo {
     pub front: glm::Vec3,
     pub up: glm::Vec3,
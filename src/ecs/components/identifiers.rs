@@ -2,12 +2,14 @@ use serde::{Serialize, Deserialize};
 
 use super::super::prelude::Component;
 
-#[derive(Debug, Component, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Component, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[component(name = "CTag")]
 pub struct CTag {
     pub tag: String
 }
 
 #[derive(Debug, Component, Clone, Serialize, Deserialize, PartialEq)]
+#[component(name = "CName")]
 pub struct CName {
     pub name: String
 }
\ No newline at end of file
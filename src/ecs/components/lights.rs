@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 * https://wiki.ogre3d.org/tiki-index.php?page=-Point+Light+Attenuation
 */
 #[derive(Debug, Component, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[component(name = "CAttenuation")]
 pub struct CAttenuation {
     pub constant: f32,
     pub linear: f32,
@@ -19,6 +20,7 @@ pub struct CAttenuation {
 * https://learnopengl.com/Lighting/Light-casters
 */
 #[derive(Debug, Component, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[component(name = "CCutoff")]
 pub struct CCutoff {
     pub inner_cutoff: f32,
     pub outer_cutoff: f32
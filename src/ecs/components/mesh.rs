@@ -1,6 +1,7 @@
 use super::super::prelude::Component;
 
 #[derive(Debug, Component, PartialEq, Default)]
+#[component(name = "CMeshData")]
 pub struct CMeshData {
     pub indices: Vec<u32>,
     pub normals: Vec<f32>,
@@ -1,10 +1,12 @@
 mod children;
+mod disabled;
 mod distance;
 mod euler_angles;
 mod identifiers;
 mod gizmo;
 mod mesh;
 mod mvp;
+mod parent;
 mod scene;
 mod states;
 mod circle;
@@ -13,7 +15,9 @@ mod sprite;
 mod target;
 mod transform;
 mod velocity;
+mod visibility;
 
+// the components here are named with #[component(name)] so scene files refer to them by their short names
 pub mod components {
     use super::*;
 
@@ -24,10 +28,16 @@ pub mod components {
     pub use gizmo::CGizmo;
     pub use transform::CTransform;
     pub use transform::CTransform2D;
+    pub use transform::CGlobalTransform;
+    pub use transform::CGlobalTransform2D;
     pub use sprite::CSprite;
+    pub use sprite::SpriteBundle;
     pub use velocity::CVelocity;
     pub use velocity::CVelocity2D;
+    pub use visibility::CVisibility;
     pub use children::CChildren;
+    pub use disabled::CDisabled;
+    pub use parent::CParent;
     pub use identifiers::CTag;
     pub use mvp::CModelMatrix;
     pub use mvp::CProjectionMatrix;
@@ -40,28 +50,14 @@ pub mod components {
 
     use crate::prelude::GlobalRegistry;
 
+    /**
+     * Components register themselves the first time they are added.
+     * Registering every derived component up front makes them available
+     * by name from the start, for scene files and the editor
+     */
     pub fn register_components(registry: &mut GlobalRegistry) {
         registry.entity_manager
-            .register_component::<CChildren>()
-            .register_component::<CDistance>()
-            .register_component::<CEulerAngles>()
-            .register_component::<CGizmo>()
-            .register_component::<CMeshData>()
-            .register_component::<CModelMatrix>()
-            .register_component::<CProjectionMatrix>()
-            .register_component::<CViewMatrix>()
-            .register_component::<CMVPMatrix>()
-            .register_component::<CMouseBtnState>()
-            .register_component::<CScene>()
-            .register_component::<CTag>()
-            .register_component::<CCircle>()
-            .register_component::<CTransform>()
-            .register_component::<CTransform2D>()
-            .register_component::<CQuad>()
-            .register_component::<CSprite>()
-            .register_component::<CTarget>()
-            .register_component::<CVelocity>()
-            .register_component::<CVelocity2D>()
+            .register_all_components()
             .register_component::<()>(); // empty component
     }
 }
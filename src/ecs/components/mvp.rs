@@ -4,13 +4,17 @@ use super::super::prelude::Component;
 
 
 #[derive(Debug, Component, Serialize, Deserialize, PartialEq)]
+#[component(name = "CModelMatrix")]
 pub struct CModelMatrix(pub glm::Mat4);
 
 #[derive(Debug, Component, Serialize, Deserialize, PartialEq)]
+#[component(name = "CViewMatrix")]
 pub struct CViewMatrix(pub glm::Mat4);
 
 #[derive(Debug, Component, Serialize, Deserialize, PartialEq)]
+#[component(name = "CProjectionMatrix")]
 pub struct CProjectionMatrix(pub glm::Mat4);
 
 #[derive(Debug, Component, Serialize, Deserialize, PartialEq)]
+#[component(name = "CMVPMatrix")]
 pub struct CMVPMatrix(pub glm::Mat4);
\ No newline at end of file
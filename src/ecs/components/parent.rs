@@ -0,0 +1,12 @@
+use super::super::prelude::{
+    Component,
+    VersionedIndex
+};
+
+/**
+ * The entity this one is attached to. Managed by `EntityManager::set_parent`,
+ * which keeps the parent's `CChildren` in step.
+ */
+#[derive(Component, Debug, PartialEq, Clone, Copy, Default)]
+#[component(name = "CParent")]
+pub struct CParent(pub VersionedIndex);
@@ -3,6 +3,7 @@ use serde::{Serialize, Deserialize};
 use super::super::prelude::Component;
 
 #[derive(Debug, Component, Serialize, Deserialize, Clone, PartialEq)]
+#[component(name = "CQuad")]
 pub struct CQuad {
     pub width: f32,
     pub height: f32,
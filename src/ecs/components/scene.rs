@@ -1,11 +1,17 @@
 use serde::{Deserialize, Serialize};
 
 use super::super::prelude::Component;
+use crate::prelude::qp_assets::{Handle, RCamera2D, RShader, RTexture};
+use crate::prelude::VersionedIndex;
 
-#[derive(Debug, Component, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Component, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[component(name = "CScene")]
 pub struct CScene {
     pub id: u64,
-    pub cameras: Vec<u64>,
-    pub shaders: Vec<u64>,
-    pub textures: Vec<u64>
-}
\ No newline at end of file
+    pub cameras: Vec<Handle<RCamera2D>>,
+    pub shaders: Vec<Handle<RShader>>,
+    pub textures: Vec<Handle<RTexture>>,
+    /// the root entities the scene load created, despawned when the scene is reloaded
+    #[serde(default)]
+    pub entities: Vec<VersionedIndex>,
+}
@@ -1,4 +1,4 @@
-use super::super::prelude::Component;
+use super::super::prelude::{Bundle, Component};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -6,14 +6,17 @@ use crate::{
     schemas::sprite::TextureAtlas,
 };
 
-use super::components::CQuad;
+use super::components::{CQuad, CTag, CTransform2D, CVelocity2D};
 
 #[derive(Debug, Component, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[component(name = "CSprite")]
+#[require(CTransform2D, CQuad)]
 pub struct CSprite {
     pub skip: bool, // skip rendering
     pub color: glm::Vec4,
     pub texture_atlas: Option<TextureAtlas>,
 
+    #[reflect(ignore)]
     mvp: glm::Mat4,
     positions: [glm::Vec4; 4],
 }
@@ -41,6 +44,16 @@ impl CSprite {
     }
 }
 
+/// the components a 2d sprite entity is made of
+#[derive(Debug, Bundle, Clone)]
+pub struct SpriteBundle {
+    pub tag: CTag,
+    pub transform: CTransform2D,
+    pub quad: CQuad,
+    pub sprite: CSprite,
+    pub velocity: Option<CVelocity2D>,
+}
+
 impl Mesh for CSprite {
     fn indices() -> Vec<i32> {
         CQuad::indices().to_vec()
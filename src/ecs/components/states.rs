@@ -1,6 +1,7 @@
 use super::super::prelude::Component;
 
 #[derive(Debug, Component, Default, PartialEq)]
+#[component(name = "CMouseBtnState")]
 pub struct CMouseBtnState {
     pub btn_left: bool,
     pub btn_right: bool,
@@ -1,6 +1,7 @@
 use super::super::prelude::Component;
 
-#[derive(Debug, Component, PartialEq, Clone)]
+#[derive(Debug, Component, PartialEq, Clone, Default)]
+#[component(name = "CTarget")]
 pub struct CTarget {
     pub x: f32,
     pub y: f32,
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 * 3d transform
 */
 #[derive(Debug, Component, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[component(name = "CTransform")]
 pub struct CTransform {
     pub translate: glm::Vec3,
     pub rotate: glm::Vec3,
@@ -51,6 +52,7 @@ impl CTransform {
 * rotation is expressed in radians
 */
 #[derive(Debug, Component, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[component(name = "CTransform2D")]
 pub struct CTransform2D {
     pub translate: glm::Vec2,
     pub rotate: f32, // rotation only happens on z-axis
@@ -97,3 +99,23 @@ impl CTransform2D {
         rotate2d(&glm::vec2(0.0, 1.0), self.rotate).normalize()
     }
 }
+
+/**
+ * world space matrix of a `CTransform`, after applying every parent's transform.
+ * Written by the transform propagation pass
+ */
+#[derive(Debug, Component, PartialEq, Clone, Copy)]
+#[component(name = "CGlobalTransform")]
+pub struct CGlobalTransform {
+    pub matrix: glm::Mat4,
+}
+
+/**
+ * world space matrix of a `CTransform2D`, after applying every parent's transform.
+ * Written by the transform propagation pass
+ */
+#[derive(Debug, Component, PartialEq, Clone, Copy)]
+#[component(name = "CGlobalTransform2D")]
+pub struct CGlobalTransform2D {
+    pub matrix: glm::Mat4,
+}
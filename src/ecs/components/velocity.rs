@@ -2,18 +2,21 @@ use super::super::prelude::Component;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Component, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[component(name = "CVelocity2D")]
 pub struct CVelocity2D {
     pub x: f32,
     pub y: f32
 }
 
 #[derive(Debug, Component, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[component(name = "CSpeed")]
 pub struct CSpeed(f32);
 
 /**
 * 3D velocity vector
 */
 #[derive(Debug, Component, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[component(name = "CVelocity")]
 pub struct CVelocity {
     pub x: f32,
     pub y: f32,
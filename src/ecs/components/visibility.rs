@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use super::super::prelude::Component;
+
+/// renderers skip entities that aren't visible. Entities without it are visible
+#[derive(Debug, Component, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[component(name = "CVisibility")]
+pub struct CVisibility {
+    pub visible: bool,
+}
+
+impl Default for CVisibility {
+    fn default() -> Self {
+        Self { visible: true }
+    }
+}
+
+impl CVisibility {
+    pub fn hidden() -> Self {
+        Self { visible: false }
+    }
+}
@@ -0,0 +1,62 @@
+use super::{
+    components::components::CDisabled,
+    prelude::{EntityManager, VersionedIndex},
+};
+
+impl EntityManager {
+    /**
+     * disables or re-enables the entity. Its descendants follow, except
+     * for ones that were disabled themselves. Enabling an entity whose
+     * ancestor is disabled keeps it disabled until the ancestor is enabled
+     */
+    pub fn set_enabled(&mut self, entity: &VersionedIndex, enabled: bool) {
+        if !self.is_valid(entity) {
+            return;
+        }
+
+        let was_disabled = !self.is_enabled(entity);
+        let disabled = !enabled || self.parent_disabled(entity);
+
+        match (disabled, self.get_mut::<CDisabled>(entity)) {
+            (true, Some(state)) => state.explicit = !enabled,
+            (true, None) => self.add(entity, CDisabled { explicit: !enabled }),
+            (false, _) => self.remove::<CDisabled>(entity),
+        }
+
+        if disabled != was_disabled {
+            for child in self.children(entity).to_vec() {
+                self.refresh_disabled(&child, disabled);
+            }
+        }
+    }
+
+    /// false if the entity or one of its ancestors is disabled
+    pub fn is_enabled(&self, entity: &VersionedIndex) -> bool {
+        self.get::<CDisabled>(entity).is_none()
+    }
+
+    /// brings the entity's subtree in line after its parent was enabled, disabled or changed
+    pub(super) fn refresh_disabled(&mut self, entity: &VersionedIndex, parent_disabled: bool) {
+        let current = self.get::<CDisabled>(entity).copied();
+        let disabled = parent_disabled || current.is_some_and(|state| state.explicit);
+
+        // the descendants already match this entity
+        if disabled == current.is_some() {
+            return;
+        }
+
+        match disabled {
+            true => self.add(entity, CDisabled { explicit: false }),
+            false => self.remove::<CDisabled>(entity),
+        }
+
+        for child in self.children(entity).to_vec() {
+            self.refresh_disabled(&child, disabled);
+        }
+    }
+
+    fn parent_disabled(&self, entity: &VersionedIndex) -> bool {
+        self.parent(entity)
+            .is_some_and(|parent| !self.is_enabled(&parent))
+    }
+}
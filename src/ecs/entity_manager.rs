@@ -1,35 +1,151 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
 use super::{
-    indexed_array::{IndexedArray, VersionedIndex, VersionedIndexAllocator},
+    bundle::Bundle,
+    components::components::{CChildren, CDisabled, CParent},
+    indexed_array::{VersionedIndex, VersionedIndexAllocator},
     prelude::Component,
+    query::{valid_entities, Query, QueryFilter, QueryIter, StorageSource},
+    reflect::{Reflect, Value},
+    type_registry::{registrars, MissingRequirement, TypeRegistry},
+    sparse_set::{ComponentStorage, ComponentTicks, SparseSet},
+    system_storages::StoragePtrs,
 };
-use crate::{prelude::qp_core::AnyMap, QPResult};
+use crate::{prelude::QPError, QPResult};
 
-type EntityMap<C> = IndexedArray<C>;
+pub(super) type EntityMap<C> = SparseSet<C>;
+type ComponentMaps = HashMap<TypeId, Box<dyn ComponentStorage>>;
+type RemoveHook = Box<dyn FnMut(VersionedIndex, &dyn Any)>;
 
 #[derive(Debug)]
 pub struct EntityManager {
     entity_allocator: VersionedIndexAllocator,
-    component_maps: AnyMap,
+    component_maps: ComponentMaps,
+    remove_hooks: RemoveHooks,
+    types: TypeRegistry,
 
-    entities: Vec<VersionedIndex>,
+    // live entities only, so iterating and counting them doesn't depend on how many have died
+    entities: SparseSet<()>,
     to_delete: Vec<VersionedIndex>,
+
+    // stamped on components when they are added or mutated
+    change_tick: u64,
+    // change_tick at the last flush, ie the start of the frame
+    frame_tick: u64,
+    // entities that lost a component since the last flush, by component type
+    removed: HashMap<TypeId, Vec<VersionedIndex>>,
 }
 
 impl EntityManager {
     pub fn new() -> QPResult<Self> {
         let entity_manager = Self {
             entity_allocator: VersionedIndexAllocator::default(),
-            component_maps: AnyMap::new(),
-            entities: Vec::<VersionedIndex>::new(),
+            component_maps: ComponentMaps::new(),
+            remove_hooks: RemoveHooks::default(),
+            types: TypeRegistry::default(),
+            entities: SparseSet::default(),
             to_delete: Vec::<VersionedIndex>::new(),
+            change_tick: 1,
+            frame_tick: 0,
+            removed: HashMap::new(),
         };
 
         Ok(entity_manager)
     }
 
-    pub fn register_component<C: Component + PartialEq + 'static>(&mut self) -> &mut Self {
+    /**
+     * creates the storage for C and adds it to the type registry. `add`
+     * does this on its own, registering up front lets C be looked up by name
+     */
+    pub fn register_component<C: Component + std::fmt::Debug + PartialEq + 'static>(
+        &mut self,
+    ) -> &mut Self {
         self.component_maps
-            .insert::<EntityMap<C>>(EntityMap::<C>::default());
+            .entry(TypeId::of::<C>())
+            .or_insert_with(|| Box::<EntityMap<C>>::default());
+
+        let builder: fn(&mut Self, &VersionedIndex, Value) -> QPResult<()> =
+            |entity_manager, entity, value| {
+                entity_manager.add(entity, C::from_value(value)?);
+
+                Ok(())
+            };
+        self.types.register::<C>(None, C::can_build().then_some(builder));
+
+        self
+    }
+
+    /// same as register_component, but C can also be added by name
+    pub fn register_default_component<
+        C: Component + Default + std::fmt::Debug + PartialEq + 'static,
+    >(
+        &mut self,
+    ) -> &mut Self {
+        self.register_component::<C>();
+        self.types.register::<C>(
+            Some(|entity_manager, entity| entity_manager.add(entity, C::default())),
+            Some(|entity_manager, entity, value| {
+                let mut component = C::default();
+                component.set_value(value)?;
+                entity_manager.add(entity, component);
+
+                Ok(())
+            }),
+        );
+
+        self
+    }
+
+    /**
+     * registers every component that derives `Component`, so all of them
+     * can be looked up by name. Panics if two of them share a name
+     */
+    pub fn register_all_components(&mut self) -> &mut Self {
+        for registrar in registrars() {
+            (registrar.register)(self);
+        }
+
+        self
+    }
+
+    // with the default as the constructor if C has one, like register_all_components does
+    fn register_on_first_use<C: Component + std::fmt::Debug + PartialEq + 'static>(&mut self) {
+        if self.component_maps.contains_key(&TypeId::of::<C>()) {
+            return;
+        }
+
+        match registrars().find(|registrar| (registrar.type_id)() == TypeId::of::<C>()) {
+            Some(registrar) => (registrar.register)(self),
+            None => {
+                self.register_component::<C>();
+            }
+        }
+    }
+
+    pub fn types(&self) -> &TypeRegistry {
+        &self.types
+    }
+
+    /**
+     * run `hook` whenever a component of type C is removed,
+     * either through `remove` or because its entity was deleted
+     */
+    pub fn on_remove<C: Component + 'static>(
+        &mut self,
+        mut hook: impl FnMut(VersionedIndex, &C) + 'static,
+    ) -> &mut Self {
+        self.remove_hooks
+            .0
+            .entry(TypeId::of::<C>())
+            .or_default()
+            .push(Box::new(move |entity, component| {
+                if let Some(component) = component.downcast_ref::<C>() {
+                    hook(entity, component);
+                }
+            }));
 
         self
     }
@@ -37,80 +153,219 @@ impl EntityManager {
     pub fn create(&mut self) -> VersionedIndex {
         let entity = self.entity_allocator.allocate();
 
-        self.entities.push(entity);
+        let _ = self.entities.insert(&entity, (), self.change_tick);
 
         entity
     }
 
+    pub fn is_valid(&self, entity: &VersionedIndex) -> bool {
+        self.entity_allocator.validate(entity)
+    }
+
     pub fn set_to_delete(&mut self, entity: VersionedIndex) {
         self.to_delete.push(entity);
     }
 
     pub fn flush(&mut self) {
-        for entity in self.to_delete.iter_mut() {
-            self.entity_allocator.deallocate(*entity);
+        self.removed.clear();
+
+        let to_delete = std::mem::take(&mut self.to_delete);
+        for entity in to_delete {
+            self.delete_now(entity);
         }
 
-        self.to_delete.clear();
+        self.change_tick += 1;
+        self.frame_tick = self.change_tick;
     }
 
-    pub fn add<C: Component + std::fmt::Debug + PartialEq + 'static>(
+    /// adds a component, or every component in a bundle. Does nothing if the entity was deleted
+    pub fn add<B: Bundle>(&mut self, entity: &VersionedIndex, bundle: B) {
+        if !self.is_valid(entity) {
+            #[cfg(debug_assertions)]
+            println!("[entity manager] tried to add components to deleted entity {}", entity);
+
+            return;
+        }
+
+        bundle.add_to(entity, self);
+    }
+
+    pub(super) fn insert<C: Component + std::fmt::Debug + PartialEq + 'static>(
         &mut self,
         entity: &VersionedIndex,
         component: C,
     ) {
-        match self.component_maps.get_mut::<EntityMap<C>>() {
-            None => {
+        let tick = self.change_tick;
+        self.register_on_first_use::<C>();
+
+        if let Some(cmp_map) = storage_mut::<C>(&mut self.component_maps) {
+            if cmp_map.insert(entity, component, tick).is_err() {
                 #[cfg(debug_assertions)]
-                println!(
-                    "component {:?} has not been registered",
-                    std::any::type_name::<C>()
-                );
-            }
-            Some(cmp_map) => {
-                cmp_map.set(&entity, component);
+                println!("[entity manager] another version of entity {} still holds its slot", entity);
             }
         }
     }
 
-    pub fn remove<C: Component + std::fmt::Debug + PartialEq + 'static>(
+    /// adds one component to each entity, looking the storage up once. Deleted entities are skipped
+    pub fn add_many<C: Component + std::fmt::Debug + PartialEq + 'static>(
         &mut self,
-        entity: &VersionedIndex,
+        entities: &[VersionedIndex],
+        components: impl IntoIterator<Item = C>,
     ) {
-        match self.component_maps.get_mut::<EntityMap<C>>() {
-            None => {
-                #[cfg(debug_assertions)]
-                println!(
-                    "component {:?} has not been registered",
-                    std::any::type_name::<C>()
-                );
+        let tick = self.change_tick;
+        self.register_on_first_use::<C>();
+
+        let allocator = &self.entity_allocator;
+        let mut added = Vec::with_capacity(entities.len());
+        if let Some(cmp_map) = storage_mut::<C>(&mut self.component_maps) {
+            cmp_map.reserve(entities.len());
+
+            for (entity, component) in entities.iter().zip(components) {
+                if !allocator.validate(entity) {
+                    #[cfg(debug_assertions)]
+                    println!("[entity manager] tried to add components to deleted entity {}", entity);
+
+                    continue;
+                }
+
+                if cmp_map.insert(entity, component, tick).is_ok() {
+                    added.push(*entity);
+                }
             }
-            Some(cmp_map) => {
-                cmp_map.unset(&entity);
+        }
+
+        if !C::REQUIRED.is_empty() {
+            for entity in added {
+                C::add_required(&entity, self);
             }
         }
     }
 
-    pub fn get<C: Component + PartialEq + 'static>(&self, entity: &VersionedIndex) -> Option<&C> {
+    /// creates an entity for each bundle
+    pub fn spawn_batch<B: Bundle>(
+        &mut self,
+        bundles: impl IntoIterator<Item = B>,
+    ) -> Vec<VersionedIndex> {
+        let bundles: Vec<B> = bundles.into_iter().collect();
+        let entities: Vec<VersionedIndex> = bundles.iter().map(|_| self.create()).collect();
+
+        B::add_batch(bundles, &entities, self);
+
+        entities
+    }
+
+    /**
+     * adds the default value of the component registered under `name`
+     * and returns it, so it can be filled in through reflection
+     */
+    pub fn add_by_name(
+        &mut self,
+        entity: &VersionedIndex,
+        name: &str,
+    ) -> QPResult<&mut dyn Reflect> {
+        let registration = self
+            .types
+            .get(name)
+            .ok_or_else(|| QPError::UnknownComponent(name.to_string()))?;
+        let constructor = registration
+            .constructor()
+            .ok_or_else(|| QPError::ComponentNotConstructible(name.to_string()))?;
+
+        if !self.is_valid(entity) {
+            return Err(QPError::ProblemCreatingEntity);
+        }
+
+        constructor(self, entity);
+
+        self.reflect_component_mut(entity, name)
+            .ok_or_else(|| QPError::UnknownComponent(name.to_string()))
+    }
+
+    /**
+     * builds the named component from a saved value and adds it, eg when
+     * loading a scene. Works for components without a default too
+     */
+    pub fn build_by_name(
+        &mut self,
+        entity: &VersionedIndex,
+        name: &str,
+        value: Value,
+    ) -> QPResult<()> {
+        let registration = self
+            .types
+            .get(name)
+            .ok_or_else(|| QPError::UnknownComponent(name.to_string()))?;
+        let builder = registration
+            .builder()
+            .ok_or_else(|| QPError::ComponentNotConstructible(name.to_string()))?;
+
+        if !self.is_valid(entity) {
+            return Err(QPError::ProblemCreatingEntity);
+        }
+
+        builder(self, entity, value)
+    }
+
+    /// removes a component, or every component in a bundle
+    pub fn remove<B: Bundle>(&mut self, entity: &VersionedIndex) {
+        B::remove_from(entity, self);
+    }
+
+    /// removes the component and hands it back
+    pub fn take<C: Component + std::fmt::Debug + PartialEq + 'static>(
+        &mut self,
+        entity: &VersionedIndex,
+    ) -> Option<C> {
+        // nothing to remove if C was never registered
+        let cmp_map = storage_mut::<C>(&mut self.component_maps)?;
+        let component = cmp_map.remove(entity)?;
+
+        self.remove_hooks.run(TypeId::of::<C>(), *entity, &component);
+        self.removed
+            .entry(TypeId::of::<C>())
+            .or_default()
+            .push(*entity);
+        self.unlink(entity, &component);
+
+        Some(component)
+    }
+
+    /**
+     * entities that had component C removed since the last flush
+     */
+    pub fn removed<C: Component + 'static>(&self) -> &[VersionedIndex] {
+        match self.removed.get(&TypeId::of::<C>()) {
+            Some(entities) => entities,
+            None => &[],
+        }
+    }
+
+    pub fn get_ticks<C: Component + 'static>(&self, entity: &VersionedIndex) -> Option<ComponentTicks> {
         if !self.entity_allocator.validate(entity) {
             return None;
         }
 
-        match self.component_maps.get::<EntityMap<C>>() {
-            None => {
-                #[cfg(debug_assertions)]
-                println!(
-                    "component {:?} has not been registered",
-                    std::any::type_name::<C>()
-                );
+        self.storage::<C>()?.ticks(entity)
+    }
 
-                return None;
-            }
-            Some(cmp_map) => match cmp_map.get(entity) {
-                None => None,
-                Some(cmp) => Some(cmp),
-            },
+    /// component C was added to the entity at or after `since`
+    pub fn is_added<C: Component + 'static>(&self, entity: &VersionedIndex, since: u64) -> bool {
+        self.get_ticks::<C>(entity)
+            .is_some_and(|ticks| ticks.is_added(since))
+    }
+
+    /// component C was added or mutated at or after `since`
+    pub fn is_changed<C: Component + 'static>(&self, entity: &VersionedIndex, since: u64) -> bool {
+        self.get_ticks::<C>(entity)
+            .is_some_and(|ticks| ticks.is_changed(since))
+    }
+
+    pub fn get<C: Component + PartialEq + 'static>(&self, entity: &VersionedIndex) -> Option<&C> {
+        if !self.entity_allocator.validate(entity) {
+            return None;
         }
+
+        storage::<C>(&self.component_maps)?.get(entity)
     }
 
     pub fn get_mut<C: Component + PartialEq + 'static>(
@@ -121,85 +376,323 @@ impl EntityManager {
             return None;
         }
 
-        match self.component_maps.get_mut::<EntityMap<C>>() {
-            None => {
-                #[cfg(debug_assertions)]
-                println!(
-                    "component {:?} has not been registered",
-                    std::any::type_name::<C>()
-                );
+        let tick = self.change_tick;
 
-                return None;
-            }
-            Some(cmp_map) => match cmp_map.get_mut(entity) {
-                None => None,
-                Some(cmp) => Some(cmp),
-            },
-        }
+        storage_mut::<C>(&mut self.component_maps)?.get_mut_at_tick(entity, tick)
     }
 
     pub fn query_all<C: Component + PartialEq + 'static>(&self) -> Vec<VersionedIndex> {
-        let Some(cmp_map) = self.component_maps.get::<EntityMap<C>>() else {
+        let Some(cmp_map) = self.storage::<C>() else {
             return vec![];
         };
 
-        cmp_map.get_entities(&self.entity_allocator)
+        let mut entities = valid_entities(cmp_map, &self.entity_allocator);
+        if TypeId::of::<C>() != TypeId::of::<CDisabled>() {
+            entities.retain(|entity| self.is_enabled(entity));
+        }
+
+        entities
     }
 
     pub fn query<C: Component + PartialEq + 'static>(&self, filter: C) -> Vec<VersionedIndex> {
-        let Some(cmp_map) = self.component_maps.get::<EntityMap<C>>() else {
+        let Some(cmp_map) = self.storage::<C>() else {
             return vec![];
         };
-        let all_entities = cmp_map.get_entities(&self.entity_allocator);
 
-        let mut result = Vec::<VersionedIndex>::new();
+        let include_disabled = TypeId::of::<C>() == TypeId::of::<CDisabled>();
+        cmp_map
+            .entities()
+            .iter()
+            .zip(cmp_map.values())
+            .filter(|(entity, cmp)| {
+                **cmp == filter
+                    && self.entity_allocator.validate(entity)
+                    && (include_disabled || self.is_enabled(entity))
+            })
+            .map(|(entity, _)| *entity)
+            .collect()
+    }
 
-        for entity in all_entities {
-            if let Some(cmp) = cmp_map.get(&entity) {
-                if *cmp == filter {
-                    result.push(entity);
-                }
-            };
-        }
+    /**
+     * iterate every entity that matches the query tuple, ie
+     * `query_iter::<(&CTransform2D, &mut CVelocity2D, Option<&CQuad>)>()`.
+     *
+     * panics if the query borrows the same component mutably more than once
+     */
+    pub fn query_iter<Q: Query>(&mut self) -> QueryIter<'_, Q> {
+        self.query_iter_filtered::<Q, ()>()
+    }
 
-        result
+    /**
+     * same as query_iter, but only yields entities that pass the filter, ie
+     * `query_iter_filtered::<(&CSprite,), (With<CVelocity2D>, Without<CTag>)>()`.
+     *
+     * `Added` and `Changed` filters match anything that happened since the last flush.
+     * Use `EMQuery` to match changes since the last time a query ran instead.
+     */
+    pub fn query_iter_filtered<Q: Query, F: QueryFilter>(&mut self) -> QueryIter<'_, Q, F> {
+        let since = self.frame_tick;
+        let tick = self.change_tick;
+
+        QueryIter::new(self, since, tick)
     }
 
     pub fn reset(&mut self) -> QPResult<()> {
-        for entity in self.entities.iter() {
-            self.entity_allocator.deallocate(*entity);
+        for entity in self.get_valid_entities() {
+            self.delete_now(entity);
+        }
+
+        self.to_delete.clear();
+
+        Ok(())
+    }
+
+    /// deletes the entity and all its descendants right away, instead of at the next flush
+    pub fn delete_recursive_now(&mut self, entity: VersionedIndex) {
+        for entity in self.descendants(&entity) {
+            self.delete_now(entity);
         }
+    }
 
-        self.entities.clear();
+    /**
+     * deletes every entity, then brings back the given ones under the same
+     * ids, without components. Used when restoring a snapshot
+     */
+    pub(crate) fn restore_entities(&mut self, entities: &[VersionedIndex]) -> QPResult<()> {
+        self.reset()?;
+
+        self.entity_allocator.restore(entities);
+        for entity in entities {
+            let _ = self.entities.insert(entity, (), self.change_tick);
+        }
 
         Ok(())
     }
 
+    /**
+     * drops every component belonging to the entity, running the remove hooks,
+     * and frees the entity index for reuse
+     */
+    fn delete_now(&mut self, entity: VersionedIndex) {
+        if !self.entity_allocator.validate(&entity) {
+            return;
+        }
+
+        self.detach_from_hierarchy(&entity);
+
+        for (type_id, storage) in self.component_maps.iter_mut() {
+            if let Some(component) = storage.get_any(&entity) {
+                self.remove_hooks.run(*type_id, entity, component);
+            }
+
+            if storage.remove_entity(&entity) {
+                self.removed.entry(*type_id).or_default().push(entity);
+            }
+        }
+
+        self.entities.remove(&entity);
+        self.entity_allocator.deallocate(entity);
+    }
+
+    /**
+     * finds entities missing a component that another component on them
+     * requires. Adding a component adds what it requires, so this only
+     * catches required components that were removed afterwards
+     */
+    pub fn validate_requirements(&self) -> Vec<MissingRequirement> {
+        let mut missing = vec![];
+
+        for entity in self.get_valid_entities() {
+            let names: Vec<&'static str> = self
+                .reflect_components(&entity)
+                .iter()
+                .map(|component| component.type_name())
+                .collect();
+
+            for name in names.iter() {
+                let Some(registration) = self.types.get(name) else {
+                    continue;
+                };
+
+                for required in registration.required.iter() {
+                    if !names.contains(required) {
+                        missing.push(MissingRequirement {
+                            entity,
+                            component: registration.name,
+                            required,
+                        });
+                    }
+                }
+            }
+        }
+
+        missing
+    }
+
+    /// every component on the entity, sorted by type name
+    pub fn reflect_components(&self, entity: &VersionedIndex) -> Vec<&dyn Reflect> {
+        if !self.entity_allocator.validate(entity) {
+            return vec![];
+        }
+
+        let mut components: Vec<&dyn Reflect> = self
+            .component_maps
+            .values()
+            .filter_map(|storage| storage.reflect(entity))
+            .collect();
+        components.sort_by_key(|component| component.type_name());
+
+        components
+    }
+
+    /// the entity's component registered under this name, marked as changed
+    pub fn reflect_component_mut(
+        &mut self,
+        entity: &VersionedIndex,
+        type_name: &str,
+    ) -> Option<&mut dyn Reflect> {
+        if !self.entity_allocator.validate(entity) {
+            return None;
+        }
+
+        let tick = self.change_tick;
+        let type_id = self.types.get(type_name)?.type_id;
+
+        self.component_maps
+            .get_mut(&type_id)?
+            .reflect_mut(entity, tick)
+    }
+
+    /// removes the entity's component registered under this name, running the remove hooks
+    pub fn remove_by_name(&mut self, entity: &VersionedIndex, type_name: &str) {
+        let Some(type_id) = self.types.get(type_name).map(|r| r.type_id) else {
+            return;
+        };
+
+        // through take, so the other side of the link is updated
+        if type_id == TypeId::of::<CParent>() {
+            self.take::<CParent>(entity);
+            return;
+        }
+        if type_id == TypeId::of::<CChildren>() {
+            self.take::<CChildren>(entity);
+            return;
+        }
+
+        let Some(storage) = self.component_maps.get_mut(&type_id) else {
+            return;
+        };
+
+        if let Some(component) = storage.get_any(entity) {
+            self.remove_hooks.run(type_id, *entity, component);
+        }
+
+        if storage.remove_entity(entity) {
+            self.removed.entry(type_id).or_default().push(*entity);
+        }
+    }
+
     pub fn registered_components_len(&self) -> usize {
         self.component_maps.len()
     }
 
     pub fn allocator_size(&self) -> usize {
-        self.entity_allocator.length()
+        self.entity_allocator.capacity()
     }
 
     pub fn count(&self) -> usize {
-        self.entity_allocator.valid_count()
+        self.entities.len()
+    }
+
+    pub(super) fn storage<C: Component + 'static>(&self) -> Option<&EntityMap<C>> {
+        storage::<C>(&self.component_maps)
+    }
+
+    /// the tick stamped on anything added or mutated right now
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick
+    }
+
+    /// the change tick at the last flush. Anything stamped at or after it changed this frame
+    pub fn frame_tick(&self) -> u64 {
+        self.frame_tick
+    }
+
+    /// returns the current tick and moves on to the next one
+    pub(crate) fn advance_tick(&mut self) -> u64 {
+        self.change_tick += 1;
+
+        self.change_tick - 1
+    }
+
+    pub fn get_valid_entities(&self) -> Vec<VersionedIndex> {
+        self.entities.entities().to_vec()
+    }
+}
+
+impl EntityManager {
+    /**
+     * raw pointers to every storage, for systems that run in parallel.
+     * Nothing structural may happen to the entity manager while they are in use
+     */
+    pub(crate) fn storage_ptrs(&mut self) -> StoragePtrs {
+        StoragePtrs {
+            storages: self
+                .component_maps
+                .iter_mut()
+                .map(|(type_id, storage)| (*type_id, &mut **storage as *mut dyn ComponentStorage))
+                .collect(),
+            allocator: &self.entity_allocator,
+            entities: &self.entities,
+        }
+    }
+}
+
+impl StorageSource for EntityManager {
+    fn storage_ptr<C: Component + 'static>(&mut self) -> Option<*mut SparseSet<C>> {
+        storage_mut::<C>(&mut self.component_maps).map(|storage| storage as *mut SparseSet<C>)
     }
 
-    pub fn get_valid_entities(&mut self) -> Vec<VersionedIndex> {
-        let mut result = Vec::<VersionedIndex>::new();
+    fn allocator(&self) -> &VersionedIndexAllocator {
+        &self.entity_allocator
+    }
+
+    fn live_entities(&self) -> Vec<VersionedIndex> {
+        self.get_valid_entities()
+    }
+}
+
+#[derive(Default)]
+struct RemoveHooks(HashMap<TypeId, Vec<RemoveHook>>);
 
-        for entity in self.entities.iter() {
-            if self.entity_allocator.validate(entity) {
-                result.push(*entity);
+impl RemoveHooks {
+    fn run(&mut self, type_id: TypeId, entity: VersionedIndex, component: &dyn Any) {
+        if let Some(hooks) = self.0.get_mut(&type_id) {
+            for hook in hooks.iter_mut() {
+                hook(entity, component);
             }
         }
+    }
+}
 
-        result
+impl std::fmt::Debug for RemoveHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoveHooks")
+            .field("len", &self.0.len())
+            .finish()
     }
 }
 
+fn storage<C: Component + 'static>(maps: &ComponentMaps) -> Option<&EntityMap<C>> {
+    maps.get(&TypeId::of::<C>())
+        .and_then(|storage| storage.as_any().downcast_ref::<EntityMap<C>>())
+}
+
+fn storage_mut<C: Component + 'static>(maps: &mut ComponentMaps) -> Option<&mut EntityMap<C>> {
+    maps.get_mut(&TypeId::of::<C>())
+        .and_then(|storage| storage.as_any_mut().downcast_mut::<EntityMap<C>>())
+}
+
 pub struct EntityBuilder<'a> {
     entity_manager: &'a mut EntityManager,
     entity: VersionedIndex,
@@ -207,9 +700,7 @@ pub struct EntityBuilder<'a> {
 
 impl<'a> EntityBuilder<'a> {
     pub fn create(entity_manager: &'a mut EntityManager) -> Self {
-        let entity = entity_manager.entity_allocator.allocate();
-
-        entity_manager.entities.push(entity);
+        let entity = entity_manager.create();
 
         Self {
             entity_manager,
@@ -217,8 +708,8 @@ impl<'a> EntityBuilder<'a> {
         }
     }
 
-    pub fn with<C: Component + std::fmt::Debug + PartialEq + 'static>(self, component: C) -> Self {
-        self.entity_manager.add(&self.entity, component);
+    pub fn with<B: Bundle>(self, bundle: B) -> Self {
+        self.entity_manager.add(&self.entity, bundle);
 
         self
     }
@@ -0,0 +1,255 @@
+use std::any::Any;
+
+use crate::{
+    app::{Controller, FrameResult},
+    prelude::World,
+};
+
+use super::{
+    components::components::{
+        CChildren, CGlobalTransform, CGlobalTransform2D, CParent, CTransform, CTransform2D,
+    },
+    prelude::{Component, EntityManager, VersionedIndex, Without},
+};
+
+impl EntityManager {
+    /**
+     * attaches `child` to `parent`, detaching it from its previous parent.
+     * Refuses if `parent` is `child` or one of its descendants
+     */
+    pub fn set_parent(&mut self, child: &VersionedIndex, parent: &VersionedIndex) {
+        if !self.is_valid(child) || !self.is_valid(parent) {
+            return;
+        }
+
+        if child == parent || self.is_ancestor(child, parent) {
+            #[cfg(debug_assertions)]
+            println!(
+                "[entity manager] can't make {} the parent of {}, it would create a cycle",
+                parent, child
+            );
+
+            return;
+        }
+
+        self.remove_parent(child);
+
+        self.add(child, CParent(*parent));
+        match self.get_mut::<CChildren>(parent) {
+            Some(children) => children.list.push(*child),
+            None => self.add(parent, CChildren { list: vec![*child] }),
+        }
+
+        let parent_disabled = !self.is_enabled(parent);
+        self.refresh_disabled(child, parent_disabled);
+    }
+
+    /// detaches `child` from its parent, making it a root
+    pub fn remove_parent(&mut self, child: &VersionedIndex) {
+        self.remove::<CParent>(child);
+    }
+
+    pub fn parent(&self, entity: &VersionedIndex) -> Option<VersionedIndex> {
+        self.get::<CParent>(entity).map(|parent| parent.0)
+    }
+
+    pub fn children(&self, entity: &VersionedIndex) -> &[VersionedIndex] {
+        match self.get::<CChildren>(entity) {
+            Some(children) => &children.list,
+            None => &[],
+        }
+    }
+
+    /// the entity and everything below it, parents before children
+    pub fn descendants(&self, entity: &VersionedIndex) -> Vec<VersionedIndex> {
+        let mut result = vec![*entity];
+        let mut i = 0;
+        while i < result.len() {
+            result.extend_from_slice(self.children(&result[i]));
+            i += 1;
+        }
+
+        result
+    }
+
+    /// deletes the entity and all its descendants at the next flush
+    pub fn set_to_delete_recursive(&mut self, entity: VersionedIndex) {
+        for entity in self.descendants(&entity) {
+            self.set_to_delete(entity);
+        }
+    }
+
+    /**
+     * keeps parents and children consistent when an entity is deleted.
+     * Its children become roots
+     */
+    pub(super) fn detach_from_hierarchy(&mut self, entity: &VersionedIndex) {
+        self.remove::<CParent>(entity);
+        self.remove::<CChildren>(entity);
+    }
+
+    /**
+     * updates the other side of the link after an entity lost its
+     * `CParent` or `CChildren`, however it was removed. Children of an
+     * entity that lost `CChildren` become roots
+     */
+    pub(super) fn unlink(&mut self, entity: &VersionedIndex, removed: &dyn Any) {
+        if let Some(CParent(parent)) = removed.downcast_ref::<CParent>() {
+            self.remove_child_from(parent, entity);
+            self.refresh_disabled(entity, false);
+        }
+
+        if let Some(children) = removed.downcast_ref::<CChildren>() {
+            for child in children.list.iter() {
+                if self.parent(child) == Some(*entity) {
+                    self.remove::<CParent>(child);
+                }
+            }
+        }
+    }
+
+    fn remove_child_from(&mut self, parent: &VersionedIndex, child: &VersionedIndex) {
+        let Some(children) = self.get_mut::<CChildren>(parent) else {
+            return;
+        };
+
+        children.list.retain(|entity| entity != child);
+        if children.list.is_empty() {
+            self.remove::<CChildren>(parent);
+        }
+    }
+
+    fn is_ancestor(&self, ancestor: &VersionedIndex, entity: &VersionedIndex) -> bool {
+        let mut current = self.parent(entity);
+        while let Some(parent) = current {
+            if parent == *ancestor {
+                return true;
+            }
+
+            current = self.parent(&parent);
+        }
+
+        false
+    }
+}
+
+trait LocalTransform: Component + PartialEq + 'static {
+    type Global: Component + std::fmt::Debug + PartialEq + 'static;
+
+    fn to_matrix(&self) -> glm::Mat4;
+    fn global(matrix: glm::Mat4) -> Self::Global;
+    fn global_matrix(global: &Self::Global) -> glm::Mat4;
+    fn set_global_matrix(global: &mut Self::Global, matrix: glm::Mat4);
+}
+
+impl LocalTransform for CTransform2D {
+    type Global = CGlobalTransform2D;
+
+    fn to_matrix(&self) -> glm::Mat4 {
+        CTransform2D::to_matrix(self)
+    }
+
+    fn global(matrix: glm::Mat4) -> Self::Global {
+        CGlobalTransform2D { matrix }
+    }
+
+    fn global_matrix(global: &Self::Global) -> glm::Mat4 {
+        global.matrix
+    }
+
+    fn set_global_matrix(global: &mut Self::Global, matrix: glm::Mat4) {
+        global.matrix = matrix;
+    }
+}
+
+impl LocalTransform for CTransform {
+    type Global = CGlobalTransform;
+
+    fn to_matrix(&self) -> glm::Mat4 {
+        CTransform::to_matrix(self)
+    }
+
+    fn global(matrix: glm::Mat4) -> Self::Global {
+        CGlobalTransform { matrix }
+    }
+
+    fn global_matrix(global: &Self::Global) -> glm::Mat4 {
+        global.matrix
+    }
+
+    fn set_global_matrix(global: &mut Self::Global, matrix: glm::Mat4) {
+        global.matrix = matrix;
+    }
+}
+
+/**
+ * Computes `CGlobalTransform2D` and `CGlobalTransform` from the local
+ * transforms, walking down from every root. A child without a local
+ * transform passes its parent's matrix on to its own children.
+ */
+pub fn propagate_transforms(entity_manager: &mut EntityManager) {
+    propagate::<CTransform2D>(entity_manager);
+    propagate::<CTransform>(entity_manager);
+}
+
+fn propagate<T: LocalTransform>(entity_manager: &mut EntityManager) {
+    // roots without a transform still pass the identity on to their children
+    let mut stack: Vec<(VersionedIndex, glm::Mat4)> = entity_manager
+        .query_iter_filtered::<(Option<&T>,), Without<CParent>>()
+        .map(|(entity, transform)| {
+            let matrix = transform.map_or_else(glm::Mat4::identity, |local| local.to_matrix());
+
+            (entity, matrix)
+        })
+        .collect();
+
+    while let Some((entity, matrix)) = stack.pop() {
+        if entity_manager.get::<T>(&entity).is_some() {
+            set_global::<T>(entity_manager, &entity, matrix);
+        }
+
+        for child in entity_manager.children(&entity) {
+            let child_matrix = match entity_manager.get::<T>(child) {
+                Some(local) => matrix * local.to_matrix(),
+                None => matrix,
+            };
+
+            stack.push((*child, child_matrix));
+        }
+    }
+}
+
+fn set_global<T: LocalTransform>(
+    entity_manager: &mut EntityManager,
+    entity: &VersionedIndex,
+    matrix: glm::Mat4,
+) {
+    let current = entity_manager
+        .get::<T::Global>(entity)
+        .map(|global| T::global_matrix(global));
+
+    match current {
+        // only touch it when it moved, so Changed<CGlobalTransform2D> means something
+        Some(current) if current == matrix => (),
+        Some(_) => {
+            if let Some(global) = entity_manager.get_mut::<T::Global>(entity) {
+                T::set_global_matrix(global, matrix);
+            }
+        }
+        None => entity_manager.add(entity, T::global(matrix)),
+    }
+}
+
+/**
+ * Runs `propagate_transforms` every frame. `App` adds it to the
+ * post update stage so global transforms are ready for the renderers
+ */
+pub struct TransformPropagation;
+
+impl Controller for TransformPropagation {
+    fn update(&mut self, world: &mut World) -> FrameResult {
+        propagate_transforms(&mut world.registry.entity_manager);
+
+        FrameResult::None
+    }
+}
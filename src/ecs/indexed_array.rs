@@ -16,6 +16,11 @@ pub struct VersionedIndex {
     version: u64
 }
 
+impl VersionedIndex {
+    pub fn index(&self) -> usize { self.index }
+    pub fn version(&self) -> u64 { self.version }
+}
+
 impl fmt::Display for VersionedIndex {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}.{}", self.index, self.version)
@@ -86,14 +91,45 @@ impl VersionedIndexAllocator {
         }
     }
 
-    pub fn length(&self) -> usize { self.entries.len() }
-    pub fn valid_count(&self) -> usize {
-        self.entries
-            .iter()
-            .filter(|e| matches!(e, AllocatorEntry::Occupied {..}))
-            .count()
+    /**
+     * frees every index, then occupies exactly the given ones with their
+     * versions. Used to bring saved entities back under the same ids
+     */
+    pub fn restore(&mut self, live: &[VersionedIndex]) {
+        let len = live.iter()
+            .map(|index| index.index + 1)
+            .max()
+            .unwrap_or(0)
+            .max(self.entries.len());
+
+        self.entries = vec![AllocatorEntry::default(); len];
+        for index in live {
+            self.entries[index.index] = AllocatorEntry::Occupied {
+                version: index.version
+            };
+
+            self.version = self.version.max(index.version);
+        }
+
+        self.next = None;
+        self.length = 0;
+        for i in (0..len).rev() {
+            match self.entries[i] {
+                AllocatorEntry::Occupied {..} => self.length += 1,
+                AllocatorEntry::Free {..} => {
+                    self.entries[i] = AllocatorEntry::Free { next: self.next };
+                    self.next = Some(i);
+                }
+            }
+        }
     }
 
+    /// number of live indexes
+    pub fn length(&self) -> usize { self.length }
+    /// number of slots, live or free
+    pub fn capacity(&self) -> usize { self.entries.len() }
+    pub fn valid_count(&self) -> usize { self.length }
+
     fn try_allocate(&mut self) -> Option<VersionedIndex> {
         match self.next {
             Some(i) => match self.entries[i] {
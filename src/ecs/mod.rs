@@ -1,22 +1,88 @@
+mod bundle;
+mod commands;
 mod components;
+mod enabled;
 mod entity_manager;
+mod hierarchy;
 mod indexed_array;
+mod pool;
 mod query;
+mod reflect;
+mod sparse_set;
+mod system_storages;
+mod type_registry;
 mod tests;
 
 pub mod prelude {
     use super::*;
 
+    pub use component_derive::Bundle;
     pub use component_derive::Component;
-    pub trait Component {}
-    impl Component for () {}
+    pub use component_derive::Reflect;
+    /**
+     * Anything stored in the entity manager. Derive it, the derive also
+     * implements Reflect and names the type after its module path and
+     * the struct, unless `#[component(name = "..")]` names it
+     */
+    pub trait Component: Reflect {
+        /// the stable name used by the type registry and scene files
+        const NAME: &'static str;
+        /// names of the components declared with `#[require(..)]`
+        const REQUIRED: &'static [&'static str] = &[];
+
+        /// adds the default of every required component the entity doesn't have yet
+        fn add_required(_entity: &VersionedIndex, _entity_manager: &mut EntityManager) {}
+    }
+
+    impl Component for () {
+        const NAME: &'static str = "()";
+    }
 
     pub use indexed_array::IndexedArray;
     pub use indexed_array::VersionedIndex;
     pub use indexed_array::VersionedIndexAllocator;
 
+    pub use bundle::Bundle;
+    pub use commands::CommandEntity;
+    pub use commands::Commands;
     pub use components::components;
     pub use entity_manager::EntityBuilder;
     pub use entity_manager::EntityManager;
+    pub use hierarchy::propagate_transforms;
+    pub use hierarchy::TransformPropagation;
+    pub use pool::EntityPool;
+    pub use pool::PoolStats;
+    pub use query::Added;
+    pub use query::Changed;
+    pub use query::ComponentAccess;
     pub use query::EMQuery;
+    pub use query::Fetch;
+    pub use query::IncludeDisabled;
+    pub use query::Or;
+    pub use query::Query;
+    pub use query::QueryFilter;
+    pub use query::QueryIter;
+    pub use query::StorageSource;
+    pub use query::With;
+    pub use query::Without;
+    pub use reflect::FieldInfo;
+    pub use reflect::Reflect;
+    pub use reflect::Value;
+    pub use sparse_set::ComponentStorage;
+    pub use sparse_set::ComponentTicks;
+    pub use sparse_set::SparseSet;
+    pub use system_storages::SystemStorages;
+    pub use type_registry::ComponentRegistration;
+    pub use type_registry::MissingRequirement;
+    pub use type_registry::TypeRegistry;
+
+    // used by the code `#[derive(Component)]` generates
+    #[doc(hidden)]
+    pub use inventory;
+    #[doc(hidden)]
+    pub use reflect::{DefaultProbe, ProbeDefault, ProbeNoDefault};
+    #[doc(hidden)]
+    pub use type_registry::{
+        ComponentRegistrar, RegisterDefault, RegisterNone, RegisterPlain, RegisterProbe,
+    };
 }
@@ -0,0 +1,144 @@
+use super::{bundle::Bundle, entity_manager::EntityManager, indexed_array::VersionedIndex};
+
+/**
+ * Reuses entities instead of deleting and creating them. Releasing an
+ * entity moves its bundle into the pool and disables it, so it stays
+ * allocated but drops out of every query. Acquiring enables it again and
+ * hands it back with those same components, so anything that should start
+ * fresh has to be set again
+ */
+#[derive(Debug)]
+pub struct EntityPool<B: Bundle + Clone> {
+    template: B,
+    free: Vec<(VersionedIndex, B)>,
+    // released entities past this are deleted
+    max_pooled: Option<usize>,
+    stats: PoolStats,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PoolStats {
+    /// entities handed out and not released yet
+    pub active: usize,
+    /// the most entities that were active at once
+    pub peak_active: usize,
+    /// released entities waiting to be reused
+    pub pooled: usize,
+    /// acquires that had to create a new entity
+    pub created: usize,
+    /// acquires served from the pool
+    pub reused: usize,
+    /// released entities that were deleted, because the pool was full or they were missing components
+    pub discarded: usize,
+}
+
+impl<B: Bundle + Clone> EntityPool<B> {
+    /// new entities are spawned with a clone of `template`
+    pub fn new(template: B) -> Self {
+        Self {
+            template,
+            free: vec![],
+            max_pooled: None,
+            stats: PoolStats::default(),
+        }
+    }
+
+    /// deletes released entities instead of pooling them once `max` are waiting
+    pub fn with_max_pooled(mut self, max: usize) -> Self {
+        self.max_pooled = Some(max);
+
+        self
+    }
+
+    /// spawns entities straight into the pool so the first acquires don't create any
+    pub fn prewarm(&mut self, entity_manager: &mut EntityManager, count: usize) {
+        self.free.reserve(count);
+
+        for _ in 0..count {
+            let entity = entity_manager.create();
+            entity_manager.set_enabled(&entity, false);
+            self.free.push((entity, self.template.clone()));
+        }
+
+        self.stats.pooled = self.free.len();
+    }
+
+    pub fn acquire(&mut self, entity_manager: &mut EntityManager) -> VersionedIndex {
+        // pooled entities may have been deleted from under the pool, eg by a world reset
+        let entity = loop {
+            match self.free.pop() {
+                Some((entity, bundle)) if entity_manager.is_valid(&entity) => {
+                    entity_manager.set_enabled(&entity, true);
+                    bundle.add_to(&entity, entity_manager);
+                    self.stats.reused += 1;
+
+                    break entity;
+                }
+                Some(_) => continue,
+                None => {
+                    let entity = entity_manager.create();
+                    self.template.clone().add_to(&entity, entity_manager);
+                    self.stats.created += 1;
+
+                    break entity;
+                }
+            }
+        };
+
+        self.stats.active += 1;
+        self.stats.peak_active = self.stats.peak_active.max(self.stats.active);
+        self.stats.pooled = self.free.len();
+
+        entity
+    }
+
+    /// acquires `count` entities at once
+    pub fn acquire_many(
+        &mut self,
+        entity_manager: &mut EntityManager,
+        count: usize,
+    ) -> Vec<VersionedIndex> {
+        (0..count).map(|_| self.acquire(entity_manager)).collect()
+    }
+
+    /**
+     * takes the bundle off the entity and keeps both for later. Components
+     * outside the bundle stay on it, the entity is disabled so queries skip
+     * them. Entities missing part of the bundle are deleted
+     */
+    pub fn release(&mut self, entity_manager: &mut EntityManager, entity: VersionedIndex) {
+        self.stats.active = self.stats.active.saturating_sub(1);
+
+        let is_full = self.max_pooled.is_some_and(|max| self.free.len() >= max);
+        let bundle = match is_full {
+            true => None,
+            false => B::take_from(&entity, entity_manager),
+        };
+
+        match bundle {
+            Some(bundle) => {
+                entity_manager.set_enabled(&entity, false);
+                self.free.push((entity, bundle));
+            }
+            None => {
+                entity_manager.set_to_delete(entity);
+                self.stats.discarded += 1;
+            }
+        }
+
+        self.stats.pooled = self.free.len();
+    }
+
+    /// deletes every pooled entity
+    pub fn clear(&mut self, entity_manager: &mut EntityManager) {
+        for (entity, _) in self.free.drain(..) {
+            entity_manager.set_to_delete(entity);
+        }
+
+        self.stats.pooled = 0;
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        self.stats
+    }
+}
@@ -1,57 +1,577 @@
-use crate::prelude::{
-    qp_ecs::Component,
-    GlobalRegistry,
-    VersionedIndex
+use std::{any::TypeId, marker::PhantomData};
+
+use super::{
+    components::components::CDisabled,
+    entity_manager::{EntityManager, EntityMap},
+    indexed_array::VersionedIndexAllocator,
+    prelude::{Component, SparseSet, VersionedIndex},
 };
 
-pub struct EMQuery<A, B = (), C = ()>
-    where
-        A: Component + PartialEq + 'static,
-        B: Component + PartialEq + 'static,
-        C: Component + PartialEq + 'static,
-{
-    _marker_a: ::std::marker::PhantomData<A>,
-    _marker_b: ::std::marker::PhantomData<B>,
-    _marker_c: ::std::marker::PhantomData<C>
-}
-
-impl<A, B, C> EMQuery<A, B, C>
-    where
-        A: Component + PartialEq + 'static,
-        B: Component + PartialEq + 'static,
-        C: Component + PartialEq + 'static,
-{
-    pub fn query_all(
-        registry: & GlobalRegistry
-    ) -> Vec<VersionedIndex> {
-        let entities_a = registry.entity_manager.query_all::<A>();
-
-        let entities = match std::any::type_name::<B>()  {
-            "()" => entities_a,
-            _ => {
-                let mut entities_b = vec![];
-                for entity in entities_a {
-                    if registry.entity_manager.get::<B>(&entity).is_some() {
-                        entities_b.push(entity);
+/**
+ * Describes how a query borrows a single component storage.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentAccess {
+    pub type_id: TypeId,
+    pub name: &'static str,
+    pub mutable: bool,
+}
+
+impl ComponentAccess {
+    pub fn of<C: 'static>(mutable: bool) -> Self {
+        Self {
+            type_id: TypeId::of::<C>(),
+            name: std::any::type_name::<C>(),
+            mutable,
+        }
+    }
+}
+
+/**
+ * Hands component storages out to queries. The entity manager is one,
+ * `SystemStorages` is another for systems running on other threads.
+ */
+pub trait StorageSource {
+    /// panics if the query's access isn't allowed from here
+    fn check_access(&self, _access: &[ComponentAccess]) {}
+
+    fn storage_ptr<C: Component + 'static>(&mut self) -> Option<*mut SparseSet<C>>;
+
+    fn allocator(&self) -> &VersionedIndexAllocator;
+
+    fn live_entities(&self) -> Vec<VersionedIndex>;
+}
+
+/**
+ * A single element of a query tuple: `&C`, `&mut C`, `Option<&C>` or `Option<&mut C>`.
+ *
+ * # Safety
+ * `fetch` hands out references derived from the raw storage pointer created in `init`.
+ * Implementors must report every storage they touch in `access`, so that conflicting
+ * queries are rejected before any reference is created.
+ */
+pub unsafe trait Fetch {
+    type Item<'a>;
+    type State: Copy;
+
+    fn access(access: &mut Vec<ComponentAccess>);
+
+    /// returns None if a required storage doesn't exist. `tick` is stamped on mutably fetched components
+    fn init<S: StorageSource>(source: &mut S, tick: u64) -> Option<Self::State>;
+
+    /// candidate entities, or None if this fetch doesn't restrict the query
+    fn entities(state: &Self::State, allocator: &VersionedIndexAllocator) -> Option<Vec<VersionedIndex>>;
+
+    /// # Safety
+    /// `state` must come from `init` on an entity manager that is still borrowed for `'a`
+    unsafe fn fetch<'a>(state: &Self::State, entity: &VersionedIndex) -> Option<Self::Item<'a>>;
+}
+
+unsafe impl<C: Component + 'static> Fetch for &C {
+    type Item<'a> = &'a C;
+    type State = *const EntityMap<C>;
+
+    fn access(access: &mut Vec<ComponentAccess>) {
+        access.push(ComponentAccess::of::<C>(false));
+    }
+
+    fn init<S: StorageSource>(source: &mut S, _tick: u64) -> Option<Self::State> {
+        source
+            .storage_ptr::<C>()
+            .map(|storage| storage as *const EntityMap<C>)
+    }
+
+    fn entities(state: &Self::State, allocator: &VersionedIndexAllocator) -> Option<Vec<VersionedIndex>> {
+        Some(valid_entities(unsafe { &**state }, allocator))
+    }
+
+    unsafe fn fetch<'a>(state: &Self::State, entity: &VersionedIndex) -> Option<Self::Item<'a>> {
+        SparseSet::value_ptr(*state, entity).map(|value| &*value)
+    }
+}
+
+unsafe impl<C: Component + 'static> Fetch for &mut C {
+    type Item<'a> = &'a mut C;
+    type State = (*mut EntityMap<C>, u64);
+
+    fn access(access: &mut Vec<ComponentAccess>) {
+        access.push(ComponentAccess::of::<C>(true));
+    }
+
+    fn init<S: StorageSource>(source: &mut S, tick: u64) -> Option<Self::State> {
+        source.storage_ptr::<C>().map(|storage| (storage, tick))
+    }
+
+    fn entities(state: &Self::State, allocator: &VersionedIndexAllocator) -> Option<Vec<VersionedIndex>> {
+        Some(valid_entities(unsafe { &*state.0 }, allocator))
+    }
+
+    // through raw pointers, a `&mut` to the storage would invalidate the items already handed out
+    unsafe fn fetch<'a>(state: &Self::State, entity: &VersionedIndex) -> Option<Self::Item<'a>> {
+        SparseSet::value_ptr_at_tick(state.0, entity, state.1).map(|value| &mut *value)
+    }
+}
+
+unsafe impl<C: Component + 'static> Fetch for Option<&C> {
+    type Item<'a> = Option<&'a C>;
+    type State = Option<*const EntityMap<C>>;
+
+    fn access(access: &mut Vec<ComponentAccess>) {
+        access.push(ComponentAccess::of::<C>(false));
+    }
+
+    fn init<S: StorageSource>(source: &mut S, tick: u64) -> Option<Self::State> {
+        Some(<&C>::init(source, tick))
+    }
+
+    fn entities(_state: &Self::State, _allocator: &VersionedIndexAllocator) -> Option<Vec<VersionedIndex>> {
+        None
+    }
+
+    unsafe fn fetch<'a>(state: &Self::State, entity: &VersionedIndex) -> Option<Self::Item<'a>> {
+        Some(state.and_then(|storage| <&C>::fetch(&storage, entity)))
+    }
+}
+
+unsafe impl<C: Component + 'static> Fetch for Option<&mut C> {
+    type Item<'a> = Option<&'a mut C>;
+    type State = Option<(*mut EntityMap<C>, u64)>;
+
+    fn access(access: &mut Vec<ComponentAccess>) {
+        access.push(ComponentAccess::of::<C>(true));
+    }
+
+    fn init<S: StorageSource>(source: &mut S, tick: u64) -> Option<Self::State> {
+        Some(<&mut C>::init(source, tick))
+    }
+
+    fn entities(_state: &Self::State, _allocator: &VersionedIndexAllocator) -> Option<Vec<VersionedIndex>> {
+        None
+    }
+
+    unsafe fn fetch<'a>(state: &Self::State, entity: &VersionedIndex) -> Option<Self::Item<'a>> {
+        Some(state.and_then(|state| <&mut C>::fetch(&state, entity)))
+    }
+}
+
+/**
+ * A tuple of fetches, ie `(&CTransform2D, &mut CVelocity2D, Option<&CQuad>)`.
+ *
+ * Iterating a query yields the entity followed by one item per fetch.
+ *
+ * # Safety
+ * see `Fetch`
+ */
+pub unsafe trait Query {
+    type Item<'a>;
+    type State: Copy;
+
+    fn access() -> Vec<ComponentAccess>;
+    fn init<S: StorageSource>(source: &mut S, tick: u64) -> Option<Self::State>;
+    fn entities(state: &Self::State, allocator: &VersionedIndexAllocator) -> Option<Vec<VersionedIndex>>;
+
+    /// # Safety
+    /// see `Fetch::fetch`
+    unsafe fn fetch<'a>(state: &Self::State, entity: &VersionedIndex) -> Option<Self::Item<'a>>;
+}
+
+macro_rules! impl_query {
+    ($($name:ident),+) => {
+        #[allow(non_snake_case)]
+        unsafe impl<$($name: Fetch),+> Query for ($($name,)+) {
+            type Item<'a> = (VersionedIndex, $($name::Item<'a>,)+);
+            type State = ($($name::State,)+);
+
+            fn access() -> Vec<ComponentAccess> {
+                let mut access = vec![];
+                $($name::access(&mut access);)+
+
+                access
+            }
+
+            fn init<S: StorageSource>(source: &mut S, tick: u64) -> Option<Self::State> {
+                Some(($($name::init(source, tick)?,)+))
+            }
+
+            fn entities(
+                state: &Self::State,
+                allocator: &VersionedIndexAllocator
+            ) -> Option<Vec<VersionedIndex>> {
+                let ($($name,)+) = state;
+                let mut smallest: Option<Vec<VersionedIndex>> = None;
+
+                $(
+                    if let Some(entities) = $name::entities($name, allocator) {
+                        let is_smaller = match &smallest {
+                            Some(s) => entities.len() < s.len(),
+                            None => true,
+                        };
+
+                        if is_smaller {
+                            smallest = Some(entities);
+                        }
                     }
-                }
+                )+
+
+                smallest
+            }
+
+            unsafe fn fetch<'a>(
+                state: &Self::State,
+                entity: &VersionedIndex
+            ) -> Option<Self::Item<'a>> {
+                let ($($name,)+) = state;
+
+                Some((*entity, $($name::fetch($name, entity)?,)+))
+            }
+        }
+    };
+}
+
+impl_query!(A);
+impl_query!(A, B);
+impl_query!(A, B, C);
+impl_query!(A, B, C, D);
+impl_query!(A, B, C, D, E);
+impl_query!(A, B, C, D, E, F);
+impl_query!(A, B, C, D, E, F, G);
+impl_query!(A, B, C, D, E, F, G, H);
+impl_query!(A, B, C, D, E, F, G, H, I);
+impl_query!(A, B, C, D, E, F, G, H, I, J);
+impl_query!(A, B, C, D, E, F, G, H, I, J, K);
+impl_query!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/**
+ * Narrows down the entities yielded by a query without fetching anything.
+ *
+ * Tuples of filters must all match, `Or` needs any of its filters to match.
+ *
+ * # Safety
+ * see `Fetch`
+ */
+pub unsafe trait QueryFilter {
+    type State: Copy;
+
+    fn access(_access: &mut Vec<ComponentAccess>) {}
+
+    fn init<S: StorageSource>(source: &mut S) -> Self::State;
+
+    /// true if disabled entities should be yielded too
+    fn includes_disabled() -> bool {
+        false
+    }
+
+    /// # Safety
+    /// `state` must come from `init` on an entity manager that is still borrowed
+    unsafe fn matches(state: &Self::State, entity: &VersionedIndex, since: u64) -> bool;
+}
+
+unsafe impl QueryFilter for () {
+    type State = ();
+
+    fn init<S: StorageSource>(_source: &mut S) -> Self::State {}
+
+    unsafe fn matches(_state: &Self::State, _entity: &VersionedIndex, _since: u64) -> bool {
+        true
+    }
+}
+
+/// entity has component C
+pub struct With<C>(PhantomData<C>);
+
+/// entity doesn't have component C
+pub struct Without<C>(PhantomData<C>);
+
+/// component C was added since the query last ran
+pub struct Added<C>(PhantomData<C>);
+
+/// component C was added or mutated since the query last ran
+pub struct Changed<C>(PhantomData<C>);
+
+/// any of the filters in the tuple match
+pub struct Or<T>(PhantomData<T>);
+
+/// yields disabled entities as well as enabled ones
+pub struct IncludeDisabled;
+
+unsafe impl QueryFilter for IncludeDisabled {
+    type State = ();
+
+    fn init<S: StorageSource>(_source: &mut S) -> Self::State {}
 
-                entities_b
+    fn includes_disabled() -> bool {
+        true
+    }
+
+    unsafe fn matches(_state: &Self::State, _entity: &VersionedIndex, _since: u64) -> bool {
+        true
+    }
+}
+
+unsafe impl<C: Component + 'static> QueryFilter for With<C> {
+    type State = Option<*const EntityMap<C>>;
+
+    fn access(access: &mut Vec<ComponentAccess>) {
+        access.push(ComponentAccess::of::<C>(false));
+    }
+
+    fn init<S: StorageSource>(source: &mut S) -> Self::State {
+        <&C>::init(source, 0)
+    }
+
+    // asking for disabled entities
+    fn includes_disabled() -> bool {
+        TypeId::of::<C>() == TypeId::of::<CDisabled>()
+    }
+
+    unsafe fn matches(state: &Self::State, entity: &VersionedIndex, _since: u64) -> bool {
+        state.is_some_and(|storage| (*storage).contains(entity))
+    }
+}
+
+unsafe impl<C: Component + 'static> QueryFilter for Without<C> {
+    type State = Option<*const EntityMap<C>>;
+
+    fn access(access: &mut Vec<ComponentAccess>) {
+        access.push(ComponentAccess::of::<C>(false));
+    }
+
+    fn init<S: StorageSource>(source: &mut S) -> Self::State {
+        <&C>::init(source, 0)
+    }
+
+    unsafe fn matches(state: &Self::State, entity: &VersionedIndex, _since: u64) -> bool {
+        match state {
+            Some(storage) => !(**storage).contains(entity),
+            None => true,
+        }
+    }
+}
+
+unsafe impl<C: Component + 'static> QueryFilter for Added<C> {
+    type State = Option<*const EntityMap<C>>;
+
+    fn access(access: &mut Vec<ComponentAccess>) {
+        access.push(ComponentAccess::of::<C>(false));
+    }
+
+    fn init<S: StorageSource>(source: &mut S) -> Self::State {
+        <&C>::init(source, 0)
+    }
+
+    unsafe fn matches(state: &Self::State, entity: &VersionedIndex, since: u64) -> bool {
+        state
+            .and_then(|storage| (*storage).ticks(entity))
+            .is_some_and(|ticks| ticks.is_added(since))
+    }
+}
+
+unsafe impl<C: Component + 'static> QueryFilter for Changed<C> {
+    type State = Option<*const EntityMap<C>>;
+
+    fn access(access: &mut Vec<ComponentAccess>) {
+        access.push(ComponentAccess::of::<C>(false));
+    }
+
+    fn init<S: StorageSource>(source: &mut S) -> Self::State {
+        <&C>::init(source, 0)
+    }
+
+    unsafe fn matches(state: &Self::State, entity: &VersionedIndex, since: u64) -> bool {
+        state
+            .and_then(|storage| (*storage).ticks(entity))
+            .is_some_and(|ticks| ticks.is_changed(since))
+    }
+}
+
+macro_rules! impl_query_filter {
+    ($($name:ident),+) => {
+        #[allow(non_snake_case)]
+        unsafe impl<$($name: QueryFilter),+> QueryFilter for ($($name,)+) {
+            type State = ($($name::State,)+);
+
+            fn access(access: &mut Vec<ComponentAccess>) {
+                $($name::access(access);)+
+            }
+
+            fn init<S: StorageSource>(source: &mut S) -> Self::State {
+                ($($name::init(source),)+)
+            }
+
+            fn includes_disabled() -> bool {
+                $($name::includes_disabled())||+
+            }
+
+            unsafe fn matches(state: &Self::State, entity: &VersionedIndex, since: u64) -> bool {
+                let ($($name,)+) = state;
+
+                $($name::matches($name, entity, since))&&+
+            }
+        }
+
+        #[allow(non_snake_case)]
+        unsafe impl<$($name: QueryFilter),+> QueryFilter for Or<($($name,)+)> {
+            type State = ($($name::State,)+);
+
+            fn access(access: &mut Vec<ComponentAccess>) {
+                $($name::access(access);)+
+            }
+
+            fn init<S: StorageSource>(source: &mut S) -> Self::State {
+                ($($name::init(source),)+)
+            }
+
+            fn includes_disabled() -> bool {
+                $($name::includes_disabled())||+
+            }
+
+            unsafe fn matches(state: &Self::State, entity: &VersionedIndex, since: u64) -> bool {
+                let ($($name,)+) = state;
+
+                $($name::matches($name, entity, since))||+
+            }
+        }
+    };
+}
+
+impl_query_filter!(A);
+impl_query_filter!(A, B);
+impl_query_filter!(A, B, C);
+impl_query_filter!(A, B, C, D);
+impl_query_filter!(A, B, C, D, E);
+impl_query_filter!(A, B, C, D, E, F);
+impl_query_filter!(A, B, C, D, E, F, G);
+impl_query_filter!(A, B, C, D, E, F, G, H);
+
+/**
+ * A query that remembers when it last ran, so `Added` and `Changed`
+ * filters only match what happened since the previous call to `iter`.
+ *
+ * Keep one per controller. Component references in a struct field
+ * need a lifetime, ie `EMQuery<(&'static CTransform2D,), Changed<CTransform2D>>`
+ */
+pub struct EMQuery<Q: Query, F: QueryFilter = ()> {
+    last_run: u64,
+
+    _marker: PhantomData<fn() -> (Q, F)>,
+}
+
+impl<Q: Query, F: QueryFilter> EMQuery<Q, F> {
+    pub fn new() -> Self {
+        Self {
+            last_run: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn iter<'a>(&mut self, entity_manager: &'a mut EntityManager) -> QueryIter<'a, Q, F> {
+        let since = self.last_run;
+        let tick = entity_manager.advance_tick();
+
+        // anything this query mutates is stamped with `tick`, so it won't see its own changes
+        self.last_run = tick + 1;
+
+        QueryIter::new(entity_manager, since, tick)
+    }
+}
+
+impl<Q: Query, F: QueryFilter> Default for EMQuery<Q, F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct QueryIter<'a, Q: Query, F: QueryFilter = ()> {
+    state: Option<(Q::State, F::State)>,
+    entities: std::vec::IntoIter<VersionedIndex>,
+    since: u64,
+    // None when disabled entities are included, or nothing is disabled
+    disabled: Option<*const EntityMap<CDisabled>>,
+
+    _marker: PhantomData<&'a mut EntityManager>,
+}
+
+impl<'a, Q: Query, F: QueryFilter> QueryIter<'a, Q, F> {
+    pub(crate) fn new<S: StorageSource>(source: &'a mut S, since: u64, tick: u64) -> Self {
+        let access = Q::access();
+        let mut filter_access = vec![];
+        F::access(&mut filter_access);
+
+        source.check_access(&access);
+        source.check_access(&filter_access);
+        validate_access(&access);
+
+        let state = Q::init(source, tick).map(|state| (state, F::init(source)));
+        let entities = match &state {
+            None => vec![],
+            Some((state, _)) => match Q::entities(state, source.allocator()) {
+                Some(entities) => entities,
+                None => source.live_entities(),
             },
         };
 
-        match std::any::type_name::<C>()  {
-            "()" => entities,
-            _ => {
-                let mut entities_c = vec![];
-                for entity in entities {
-                    if registry.entity_manager.get::<C>(&entity).is_some() {
-                        entities_c.push(entity);
-                    }
-                }
+        let disabled = match F::includes_disabled() {
+            true => None,
+            false => source.storage_ptr::<CDisabled>().map(|storage| storage as *const _),
+        };
 
-                entities_c
-            },
+        Self {
+            state,
+            entities: entities.into_iter(),
+            since,
+            disabled,
+            _marker: PhantomData,
         }
     }
-}
\ No newline at end of file
+}
+
+impl<'a, Q: Query, F: QueryFilter> Iterator for QueryIter<'a, Q, F> {
+    type Item = Q::Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (state, filter) = self.state.as_ref()?;
+
+        for entity in self.entities.by_ref() {
+            if !unsafe { F::matches(filter, &entity, self.since) } {
+                continue;
+            }
+
+            if self.disabled.is_some_and(|disabled| unsafe { (*disabled).contains(&entity) }) {
+                continue;
+            }
+
+            // every entity is visited once and the access was validated in `new`,
+            // so no two items alias the same component mutably
+            if let Some(item) = unsafe { Q::fetch(state, &entity) } {
+                return Some(item);
+            }
+        }
+
+        None
+    }
+}
+
+/// the storage's entities, minus the ones that have been deleted
+pub(super) fn valid_entities<C>(storage: &EntityMap<C>, allocator: &VersionedIndexAllocator) -> Vec<VersionedIndex> {
+    storage
+        .entities()
+        .iter()
+        .filter(|entity| allocator.validate(entity))
+        .copied()
+        .collect()
+}
+
+// filters only look at which entities a storage has and their ticks, never at the
+// components, so only the fetches themselves can alias
+fn validate_access(access: &[ComponentAccess]) {
+    for (i, a) in access.iter().enumerate() {
+        for b in access.iter().skip(i + 1) {
+            if a.type_id == b.type_id && (a.mutable || b.mutable) {
+                panic!(
+                    "[entity manager] query has conflicting access to component {}",
+                    a.name
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,773 @@
+use std::marker::PhantomData;
+
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    prelude::{QPError, VersionedIndex},
+    QPResult,
+};
+
+// the map key an entity reference is saved under, so it can't be mistaken for a struct
+const ENTITY_KEY: &str = "$entity";
+
+/**
+ * A reflected value, detached from the type it was read from.
+ * Structs become a list of named fields in declaration order
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    None,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    Vec2(glm::Vec2),
+    Vec3(glm::Vec3),
+    Vec4(glm::Vec4),
+    Mat4(glm::Mat4),
+    Entity(VersionedIndex),
+    List(Vec<Value>),
+    Struct(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Bool(_) => "bool",
+            Self::Int(_) => "int",
+            Self::UInt(_) => "uint",
+            Self::Float(_) => "float",
+            Self::String(_) => "string",
+            Self::Vec2(_) => "vec2",
+            Self::Vec3(_) => "vec3",
+            Self::Vec4(_) => "vec4",
+            Self::Mat4(_) => "mat4",
+            Self::Entity(_) => "entity",
+            Self::List(_) => "list",
+            Self::Struct(_) => "struct",
+        }
+    }
+
+    /// any numeric value as a float
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(v) => Some(*v as f64),
+            Self::UInt(v) => Some(*v as f64),
+            Self::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// any integer value. Floats are not truncated
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(v) => Some(*v),
+            Self::UInt(v) => Some(*v as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Int(v) => Some(*v as u64),
+            Self::UInt(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// a field of a struct value
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        match self {
+            Self::Struct(fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /**
+     * rewrites every entity reference in the value. Returns false if
+     * `map` couldn't resolve one of them, which is then left as it was
+     */
+    pub fn map_entities(
+        &mut self,
+        map: &mut impl FnMut(VersionedIndex) -> Option<VersionedIndex>,
+    ) -> bool {
+        match self {
+            Self::Entity(entity) => match map(*entity) {
+                Some(mapped) => {
+                    *entity = mapped;
+                    true
+                }
+                None => false,
+            },
+            Self::List(items) => {
+                let mut resolved = true;
+                for item in items.iter_mut() {
+                    resolved &= item.map_entities(map);
+                }
+
+                resolved
+            }
+            Self::Struct(fields) => {
+                let mut resolved = true;
+                for (_, field) in fields.iter_mut() {
+                    resolved &= field.map_entities(map);
+                }
+
+                resolved
+            }
+            _ => true,
+        }
+    }
+}
+
+/**
+ * Values are saved without their variant, so scene files stay readable.
+ * Vectors and matrices become lists, structs become maps and entities
+ * become a map with a single "$entity" key
+ */
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::None => serializer.serialize_unit(),
+            Self::Bool(v) => serializer.serialize_bool(*v),
+            Self::Int(v) => serializer.serialize_i64(*v),
+            Self::UInt(v) => serializer.serialize_u64(*v),
+            Self::Float(v) => serializer.serialize_f64(*v),
+            Self::String(v) => serializer.serialize_str(v),
+            Self::Vec2(v) => serializer.collect_seq(v.iter()),
+            Self::Vec3(v) => serializer.collect_seq(v.iter()),
+            Self::Vec4(v) => serializer.collect_seq(v.iter()),
+            Self::Mat4(v) => serializer.collect_seq(v.iter()),
+            Self::Entity(entity) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(ENTITY_KEY, entity)?;
+                map.end()
+            }
+            Self::List(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items.iter() {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Self::Struct(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (name, value) in fields.iter() {
+                    map.serialize_entry(name, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a reflected value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::None)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Int(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::UInt(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Float(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = vec![];
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+
+        Ok(Value::List(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut fields = vec![];
+        while let Some(name) = map.next_key::<String>()? {
+            if name == ENTITY_KEY && fields.is_empty() {
+                return Ok(Value::Entity(map.next_value()?));
+            }
+
+            fields.push((name, map.next_value()?));
+        }
+
+        Ok(Value::Struct(fields))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub type_name: &'static str,
+}
+
+/**
+ * Runtime access to a type's fields, so tools like the editor and the
+ * scene serializer can work with any component. `#[derive(Component)]`
+ * and `#[derive(Reflect)]` implement it; fields marked `#[reflect(ignore)]`
+ * are left out.
+ *
+ * Paths are field names separated by dots, eg "translate.x". List
+ * elements are addressed by index
+ */
+pub trait Reflect {
+    fn type_name(&self) -> &'static str;
+
+    fn fields(&self) -> &'static [FieldInfo] {
+        &[]
+    }
+
+    fn field(&self, _name: &str) -> Option<&dyn Reflect> {
+        None
+    }
+
+    fn field_mut(&mut self, _name: &str) -> Option<&mut dyn Reflect> {
+        None
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Struct(
+            self.fields()
+                .iter()
+                .filter_map(|info| {
+                    self.field(info.name)
+                        .map(|field| (info.name.to_string(), field.to_value()))
+                })
+                .collect(),
+        )
+    }
+
+    /// structs only set the fields present in the value
+    fn set_value(&mut self, value: Value) -> QPResult<()> {
+        set_fields(self, value)
+    }
+
+    /**
+     * builds the type from a value, for types without a default. Missing
+     * struct fields fall back to their own default
+     */
+    fn from_value(_value: Value) -> QPResult<Self>
+    where
+        Self: Sized,
+    {
+        Err(QPError::ComponentNotConstructible(
+            std::any::type_name::<Self>().to_string(),
+        ))
+    }
+
+    /// true if `from_value` can build the type
+    fn can_build() -> bool
+    where
+        Self: Sized,
+    {
+        false
+    }
+
+    fn get_path(&self, path: &str) -> QPResult<Value> {
+        if path.is_empty() {
+            return Ok(self.to_value());
+        }
+
+        let (name, rest) = path.split_once('.').unwrap_or((path, ""));
+        match self.field(name) {
+            Some(field) => field.get_path(rest),
+            None => Err(QPError::ReflectFieldNotFound(
+                self.type_name().to_string(),
+                name.to_string(),
+            )),
+        }
+    }
+
+    fn set_path(&mut self, path: &str, value: Value) -> QPResult<()> {
+        if path.is_empty() {
+            return self.set_value(value);
+        }
+
+        let type_name = self.type_name();
+        let (name, rest) = path.split_once('.').unwrap_or((path, ""));
+        match self.field_mut(name) {
+            Some(field) => field.set_path(rest, value),
+            None => Err(QPError::ReflectFieldNotFound(
+                type_name.to_string(),
+                name.to_string(),
+            )),
+        }
+    }
+}
+
+fn set_fields<R: Reflect + ?Sized>(target: &mut R, value: Value) -> QPResult<()> {
+    let Value::Struct(fields) = value else {
+        return Err(mismatch(target.type_name(), &value));
+    };
+
+    for (name, value) in fields {
+        let type_name = target.type_name();
+        match target.field_mut(&name) {
+            Some(field) => field.set_value(value)?,
+            None => return Err(QPError::ReflectFieldNotFound(type_name.to_string(), name)),
+        }
+    }
+
+    Ok(())
+}
+
+/// sets the fields in declaration order from a list of the same length
+fn set_items<R: Reflect + ?Sized>(target: &mut R, len: usize, items: Vec<Value>) -> QPResult<()> {
+    if items.len() != len {
+        return Err(mismatch(target.type_name(), &Value::List(items)));
+    }
+
+    for (info, item) in target.fields().iter().zip(items) {
+        if let Some(field) = target.field_mut(info.name) {
+            field.set_value(item)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn from_default<T: Reflect + Default>(value: Value) -> QPResult<T> {
+    let mut built = T::default();
+    built.set_value(value)?;
+
+    Ok(built)
+}
+
+/**
+ * Lets the derive build fields that are ignored, or missing from a value,
+ * with their default when they have one. `(&&DefaultProbe::<T>::new()).default_value()`
+ * is Some if T implements Default and None otherwise
+ */
+#[doc(hidden)]
+pub struct DefaultProbe<T>(PhantomData<fn() -> T>);
+
+impl<T> DefaultProbe<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait ProbeDefault<T> {
+    fn default_value(&self) -> Option<T>;
+}
+
+impl<T: Default> ProbeDefault<T> for &DefaultProbe<T> {
+    fn default_value(&self) -> Option<T> {
+        Some(T::default())
+    }
+}
+
+#[doc(hidden)]
+pub trait ProbeNoDefault<T> {
+    fn default_value(&self) -> Option<T>;
+}
+
+impl<T> ProbeNoDefault<T> for DefaultProbe<T> {
+    fn default_value(&self) -> Option<T> {
+        None
+    }
+}
+
+fn mismatch(type_name: &str, value: &Value) -> QPError {
+    QPError::ReflectTypeMismatch(type_name.to_string(), value.kind().to_string())
+}
+
+macro_rules! reflect_int {
+    ($($t:ty => $variant:ident, $as:ident),*) => {
+        $(
+            impl Reflect for $t {
+                fn type_name(&self) -> &'static str {
+                    stringify!($t)
+                }
+
+                fn to_value(&self) -> Value {
+                    Value::$variant(*self as _)
+                }
+
+                fn set_value(&mut self, value: Value) -> QPResult<()> {
+                    *self = value.$as().ok_or_else(|| mismatch(stringify!($t), &value))? as $t;
+
+                    Ok(())
+                }
+
+                fn from_value(value: Value) -> QPResult<Self> {
+                    from_default(value)
+                }
+
+                fn can_build() -> bool {
+                    true
+                }
+            }
+        )*
+    };
+}
+
+reflect_int!(
+    i8 => Int, as_i64, i16 => Int, as_i64, i32 => Int, as_i64, i64 => Int, as_i64, isize => Int, as_i64,
+    u8 => UInt, as_u64, u16 => UInt, as_u64, u32 => UInt, as_u64, u64 => UInt, as_u64, usize => UInt, as_u64
+);
+
+macro_rules! reflect_float {
+    ($($t:ty),*) => {
+        $(
+            impl Reflect for $t {
+                fn type_name(&self) -> &'static str {
+                    stringify!($t)
+                }
+
+                fn to_value(&self) -> Value {
+                    Value::Float(*self as f64)
+                }
+
+                fn set_value(&mut self, value: Value) -> QPResult<()> {
+                    *self = value.as_f64().ok_or_else(|| mismatch(stringify!($t), &value))? as $t;
+
+                    Ok(())
+                }
+
+                fn from_value(value: Value) -> QPResult<Self> {
+                    from_default(value)
+                }
+
+                fn can_build() -> bool {
+                    true
+                }
+            }
+        )*
+    };
+}
+
+reflect_float!(f32, f64);
+
+/**
+ * Leaf types that map onto one Value variant. The glm vectors also
+ * expose their components as fields
+ */
+macro_rules! reflect_leaf {
+    ($($t:ty => $variant:ident $([$($field:ident),*])?),*) => {
+        $(
+            impl Reflect for $t {
+                fn type_name(&self) -> &'static str {
+                    stringify!($t)
+                }
+
+                $(
+                    fn fields(&self) -> &'static [FieldInfo] {
+                        &[$(FieldInfo { name: stringify!($field), type_name: "f32" }),*]
+                    }
+
+                    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+                        match name {
+                            $(stringify!($field) => Some(&self.$field as &dyn Reflect),)*
+                            _ => None,
+                        }
+                    }
+
+                    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+                        match name {
+                            $(stringify!($field) => Some(&mut self.$field as &mut dyn Reflect),)*
+                            _ => None,
+                        }
+                    }
+                )?
+
+                fn to_value(&self) -> Value {
+                    Value::$variant(self.clone())
+                }
+
+                fn set_value(&mut self, value: Value) -> QPResult<()> {
+                    match value {
+                        Value::$variant(value) => *self = value,
+                        // how vectors come back from a scene file
+                        Value::List(items) if !self.fields().is_empty() => {
+                            set_items(self, self.fields().len(), items)?
+                        }
+                        value => set_fields(self, value)?,
+                    }
+
+                    Ok(())
+                }
+
+                fn from_value(value: Value) -> QPResult<Self> {
+                    from_default(value)
+                }
+
+                fn can_build() -> bool {
+                    true
+                }
+            }
+        )*
+    };
+}
+
+reflect_leaf!(
+    bool => Bool,
+    String => String,
+    VersionedIndex => Entity,
+    glm::Vec2 => Vec2 [x, y],
+    glm::Vec3 => Vec3 [x, y, z],
+    glm::Vec4 => Vec4 [x, y, z, w]
+);
+
+impl Reflect for glm::Mat4 {
+    fn type_name(&self) -> &'static str {
+        "glm::Mat4"
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Mat4(*self)
+    }
+
+    /// also takes a list of 16 numbers, in column major order
+    fn set_value(&mut self, value: Value) -> QPResult<()> {
+        match value {
+            Value::Mat4(value) => *self = value,
+            Value::List(items) if items.len() == 16 => {
+                let mut values = [0.0; 16];
+                for (v, item) in values.iter_mut().zip(items.iter()) {
+                    *v = item.as_f64().ok_or_else(|| mismatch("glm::Mat4", item))? as f32;
+                }
+
+                *self = glm::Mat4::from_column_slice(&values);
+            }
+            value => return Err(mismatch("glm::Mat4", &value)),
+        }
+
+        Ok(())
+    }
+
+    fn from_value(value: Value) -> QPResult<Self> {
+        from_default(value)
+    }
+
+    fn can_build() -> bool {
+        true
+    }
+}
+
+impl Reflect for () {
+    fn type_name(&self) -> &'static str {
+        "()"
+    }
+
+    fn to_value(&self) -> Value {
+        Value::None
+    }
+
+    fn set_value(&mut self, value: Value) -> QPResult<()> {
+        match value {
+            Value::None => Ok(()),
+            value => Err(mismatch("()", &value)),
+        }
+    }
+
+    fn from_value(value: Value) -> QPResult<Self> {
+        from_default(value)
+    }
+
+    fn can_build() -> bool {
+        true
+    }
+}
+
+// read only, there is nothing to point it at
+impl Reflect for &'static str {
+    fn type_name(&self) -> &'static str {
+        "&str"
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+
+    fn set_value(&mut self, value: Value) -> QPResult<()> {
+        Err(mismatch("&str", &value))
+    }
+}
+
+/// None is Value::None, Some is the inner value
+impl<T: Reflect + Default> Reflect for Option<T> {
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        self.as_ref()?.field(name)
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        self.as_mut()?.field_mut(name)
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            Some(inner) => inner.to_value(),
+            None => Value::None,
+        }
+    }
+
+    fn set_value(&mut self, value: Value) -> QPResult<()> {
+        match value {
+            Value::None => *self = None,
+            value => self.get_or_insert_with(T::default).set_value(value)?,
+        }
+
+        Ok(())
+    }
+
+    fn from_value(value: Value) -> QPResult<Self> {
+        from_default(value)
+    }
+
+    fn can_build() -> bool {
+        true
+    }
+}
+
+impl<T: Reflect + Default> Reflect for Vec<T> {
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        let i = name.parse::<usize>().ok()?;
+
+        self.get(i).map(|item| item as &dyn Reflect)
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        let i = name.parse::<usize>().ok()?;
+
+        self.get_mut(i).map(|item| item as &mut dyn Reflect)
+    }
+
+    fn to_value(&self) -> Value {
+        Value::List(self.iter().map(|item| item.to_value()).collect())
+    }
+
+    /// resizes to the length of the list
+    fn set_value(&mut self, value: Value) -> QPResult<()> {
+        let Value::List(items) = value else {
+            return Err(mismatch(self.type_name(), &value));
+        };
+
+        self.resize_with(items.len(), T::default);
+        for (item, value) in self.iter_mut().zip(items) {
+            item.set_value(value)?;
+        }
+
+        Ok(())
+    }
+
+    fn from_value(value: Value) -> QPResult<Self> {
+        from_default(value)
+    }
+
+    fn can_build() -> bool {
+        true
+    }
+}
+
+impl<T: Reflect, const N: usize> Reflect for [T; N] {
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        let i = name.parse::<usize>().ok()?;
+
+        self.get(i).map(|item| item as &dyn Reflect)
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        let i = name.parse::<usize>().ok()?;
+
+        self.get_mut(i).map(|item| item as &mut dyn Reflect)
+    }
+
+    fn to_value(&self) -> Value {
+        Value::List(self.iter().map(|item| item.to_value()).collect())
+    }
+
+    fn set_value(&mut self, value: Value) -> QPResult<()> {
+        match value {
+            Value::List(items) if items.len() == N => {
+                for (item, value) in self.iter_mut().zip(items) {
+                    item.set_value(value)?;
+                }
+
+                Ok(())
+            }
+            value => Err(mismatch(self.type_name(), &value)),
+        }
+    }
+
+    fn from_value(value: Value) -> QPResult<Self> {
+        let type_name = std::any::type_name::<Self>();
+        let items = match value {
+            Value::List(items) if items.len() == N => items,
+            value => return Err(mismatch(type_name, &value)),
+        };
+
+        let built = items
+            .into_iter()
+            .map(T::from_value)
+            .collect::<QPResult<Vec<T>>>()?;
+
+        built
+            .try_into()
+            .map_err(|_| QPError::ComponentNotConstructible(type_name.to_string()))
+    }
+
+    fn can_build() -> bool {
+        T::can_build()
+    }
+}
@@ -0,0 +1,313 @@
+use std::any::Any;
+
+use super::{indexed_array::VersionedIndex, reflect::Reflect};
+
+/**
+ * the ticks at which a component was added and last changed
+ */
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct ComponentTicks {
+    pub added: u64,
+    pub changed: u64,
+}
+
+impl ComponentTicks {
+    pub fn is_added(&self, since: u64) -> bool {
+        self.added >= since
+    }
+
+    pub fn is_changed(&self, since: u64) -> bool {
+        self.changed >= since
+    }
+}
+
+/**
+ * Packed component storage.
+ *
+ * `sparse` maps an entity slot to a position in the dense arrays, so lookups
+ * are two array reads and iterating all values walks contiguous memory.
+ * Removing swaps the last value into the hole to keep the arrays packed.
+ *
+ * https://skypjack.github.io/2020-08-02-ecs-baf-part-9/
+ */
+#[derive(Debug)]
+pub struct SparseSet<T> {
+    sparse: Vec<Option<usize>>,
+
+    dense: Vec<T>,
+    entities: Vec<VersionedIndex>,
+    ticks: Vec<ComponentTicks>,
+}
+
+impl<T> Default for SparseSet<T> {
+    fn default() -> Self {
+        Self {
+            sparse: Vec::with_capacity(16),
+            dense: Vec::with_capacity(16),
+            entities: Vec::with_capacity(16),
+            ticks: Vec::with_capacity(16),
+        }
+    }
+}
+
+impl<T> SparseSet<T> {
+    /**
+     * inserts the value, or replaces it if the entity already has one.
+     * Returns the replaced value. A slot held by another version of the
+     * entity is left alone and the value is handed back as the error
+     */
+    pub fn insert(&mut self, entity: &VersionedIndex, value: T, tick: u64) -> Result<Option<T>, T> {
+        let ticks = ComponentTicks {
+            added: tick,
+            changed: tick,
+        };
+
+        if let Some(i) = self.dense_index(entity) {
+            self.ticks[i] = ticks;
+
+            return Ok(Some(std::mem::replace(&mut self.dense[i], value)));
+        }
+
+        if let Some(Some(_)) = self.sparse.get(entity.index()) {
+            return Err(value);
+        }
+
+        if entity.index() >= self.sparse.len() {
+            self.sparse.resize(entity.index() + 1, None);
+        }
+
+        self.sparse[entity.index()] = Some(self.dense.len());
+        self.dense.push(value);
+        self.entities.push(*entity);
+        self.ticks.push(ticks);
+
+        Ok(None)
+    }
+
+    /// makes room for `additional` more values, for inserting many at once
+    pub fn reserve(&mut self, additional: usize) {
+        self.dense.reserve(additional);
+        self.entities.reserve(additional);
+        self.ticks.reserve(additional);
+    }
+
+    pub fn remove(&mut self, entity: &VersionedIndex) -> Option<T> {
+        self.dense_index(entity)?;
+
+        self.remove_slot(entity.index())
+    }
+
+    pub fn get(&self, entity: &VersionedIndex) -> Option<&T> {
+        self.dense_index(entity).map(|i| &self.dense[i])
+    }
+
+    pub fn get_mut(&mut self, entity: &VersionedIndex) -> Option<&mut T> {
+        self.dense_index(entity).map(|i| &mut self.dense[i])
+    }
+
+    /// same as get_mut, but marks the value as changed at `tick`
+    pub fn get_mut_at_tick(&mut self, entity: &VersionedIndex, tick: u64) -> Option<&mut T> {
+        let i = self.dense_index(entity)?;
+
+        self.ticks[i].changed = tick;
+
+        Some(&mut self.dense[i])
+    }
+
+    /**
+     * a pointer to the entity's value. Unlike `get`, it never borrows the
+     * other values, so queries can hold on to them while fetching more
+     *
+     * # Safety
+     * `set` must point to a live set
+     */
+    pub(super) unsafe fn value_ptr(set: *const Self, entity: &VersionedIndex) -> Option<*const T> {
+        let i = (*set).dense_index(entity)?;
+
+        Some((*set).dense.as_ptr().add(i))
+    }
+
+    /**
+     * same as `value_ptr`, but mutable and marks the value as changed at `tick`
+     *
+     * # Safety
+     * `set` must point to a live set, and nothing else may be using the value
+     */
+    pub(super) unsafe fn value_ptr_at_tick(
+        set: *mut Self,
+        entity: &VersionedIndex,
+        tick: u64,
+    ) -> Option<*mut T> {
+        let i = (*set).dense_index(entity)?;
+
+        (*(*set).ticks.as_mut_ptr().add(i)).changed = tick;
+
+        Some((*set).dense.as_mut_ptr().add(i))
+    }
+
+    pub fn ticks(&self, entity: &VersionedIndex) -> Option<ComponentTicks> {
+        self.dense_index(entity).map(|i| self.ticks[i])
+    }
+
+    pub fn contains(&self, entity: &VersionedIndex) -> bool {
+        self.dense_index(entity).is_some()
+    }
+
+    /// entities in the same order as `values`
+    pub fn entities(&self) -> &[VersionedIndex] {
+        &self.entities
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.dense
+    }
+
+    pub fn len(&self) -> usize {
+        self.dense.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.sparse.clear();
+        self.dense.clear();
+        self.entities.clear();
+        self.ticks.clear();
+    }
+
+    fn dense_index(&self, entity: &VersionedIndex) -> Option<usize> {
+        match self.sparse.get(entity.index()) {
+            Some(Some(i)) if self.entities[*i] == *entity => Some(*i),
+            _ => None,
+        }
+    }
+
+    fn remove_slot(&mut self, slot: usize) -> Option<T> {
+        let i = self.sparse.get_mut(slot)?.take()?;
+
+        self.entities.swap_remove(i);
+        self.ticks.swap_remove(i);
+        let value = self.dense.swap_remove(i);
+
+        if let Some(moved) = self.entities.get(i) {
+            self.sparse[moved.index()] = Some(i);
+        }
+
+        Some(value)
+    }
+}
+
+/**
+ * type erased access to a SparseSet, so the entity manager can
+ * work with every storage without knowing the component types
+ */
+pub trait ComponentStorage {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn get_any(&self, entity: &VersionedIndex) -> Option<&dyn Any>;
+    fn reflect(&self, entity: &VersionedIndex) -> Option<&dyn Reflect>;
+    /// marks the component as changed at `tick`
+    fn reflect_mut(&mut self, entity: &VersionedIndex, tick: u64) -> Option<&mut dyn Reflect>;
+
+    /// drops the entity's component. Returns false if there was nothing to remove
+    fn remove_entity(&mut self, entity: &VersionedIndex) -> bool;
+    fn contains_entity(&self, entity: &VersionedIndex) -> bool;
+    fn len(&self) -> usize;
+    fn clear(&mut self);
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl std::fmt::Debug for dyn ComponentStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ComponentStorage")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl<T: Reflect + 'static> ComponentStorage for SparseSet<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_any(&self, entity: &VersionedIndex) -> Option<&dyn Any> {
+        self.get(entity).map(|value| value as &dyn Any)
+    }
+
+    fn reflect(&self, entity: &VersionedIndex) -> Option<&dyn Reflect> {
+        self.get(entity).map(|value| value as &dyn Reflect)
+    }
+
+    fn reflect_mut(&mut self, entity: &VersionedIndex, tick: u64) -> Option<&mut dyn Reflect> {
+        self.get_mut_at_tick(entity, tick)
+            .map(|value| value as &mut dyn Reflect)
+    }
+
+    fn remove_entity(&mut self, entity: &VersionedIndex) -> bool {
+        self.remove(entity).is_some()
+    }
+
+    fn contains_entity(&self, entity: &VersionedIndex) -> bool {
+        self.contains(entity)
+    }
+
+    fn len(&self) -> usize {
+        SparseSet::len(self)
+    }
+
+    fn clear(&mut self) {
+        SparseSet::clear(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::prelude::VersionedIndexAllocator;
+
+    #[test]
+    fn sparse_set_stays_packed() {
+        let mut allocator = VersionedIndexAllocator::default();
+        let mut set = SparseSet::<&str>::default();
+
+        let a = allocator.allocate();
+        let b = allocator.allocate();
+        let c = allocator.allocate();
+
+        assert_eq!(set.insert(&a, "a", 0), Ok(None));
+        assert_eq!(set.insert(&b, "b", 0), Ok(None));
+        assert_eq!(set.insert(&c, "c", 0), Ok(None));
+
+        assert_eq!(set.remove(&a), Some("a"));
+        assert_eq!(set.values(), &["c", "b"]);
+        assert_eq!(set.entities(), &[c, b]);
+        assert_eq!(set.get(&c), Some(&"c"));
+        assert_eq!(set.get(&a), None);
+
+        // a new entity in the old slot doesn't see the old value
+        allocator.deallocate(a);
+        let d = allocator.allocate();
+        assert_eq!(d.index(), a.index());
+        assert_eq!(set.get(&d), None);
+
+        // and the old entity can't take the slot back from the new one
+        assert_eq!(set.insert(&d, "d", 1), Ok(None));
+        assert_eq!(set.insert(&a, "a2", 1), Err("a2"));
+        assert_eq!(set.get(&d), Some(&"d"));
+        assert_eq!(set.remove(&a), None);
+
+        assert_eq!(set.insert(&b, "b2", 1), Ok(Some("b")));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.ticks(&b), Some(ComponentTicks { added: 1, changed: 1 }));
+    }
+}
@@ -0,0 +1,83 @@
+use std::{any::TypeId, collections::HashMap};
+
+use super::{
+    prelude::{Component, SparseSet, VersionedIndex, VersionedIndexAllocator},
+    query::{ComponentAccess, StorageSource},
+    sparse_set::ComponentStorage,
+};
+
+/**
+ * Every storage in the entity manager, taken once per batch of parallel systems
+ */
+pub(crate) struct StoragePtrs {
+    pub(super) storages: HashMap<TypeId, *mut dyn ComponentStorage>,
+    pub(super) allocator: *const VersionedIndexAllocator,
+    pub(super) entities: *const SparseSet<()>,
+}
+
+/**
+ * The storages one system declared it would touch. Queries made through
+ * it panic if they reach for anything else.
+ */
+pub struct SystemStorages {
+    system: String,
+    access: Vec<ComponentAccess>,
+
+    storages: HashMap<TypeId, *mut dyn ComponentStorage>,
+    allocator: *const VersionedIndexAllocator,
+    entities: *const SparseSet<()>,
+}
+
+impl SystemStorages {
+    pub(crate) fn new(ptrs: &StoragePtrs, system: &str, access: &[ComponentAccess]) -> Self {
+        let storages = access
+            .iter()
+            .filter_map(|a| ptrs.storages.get(&a.type_id).map(|ptr| (a.type_id, *ptr)))
+            .collect();
+
+        Self {
+            system: system.to_string(),
+            access: access.to_vec(),
+            storages,
+            allocator: ptrs.allocator,
+            entities: ptrs.entities,
+        }
+    }
+
+    pub fn allows(&self, access: &ComponentAccess) -> bool {
+        self.access
+            .iter()
+            .any(|a| a.type_id == access.type_id && (a.mutable || !access.mutable))
+    }
+}
+
+impl StorageSource for SystemStorages {
+    fn check_access(&self, access: &[ComponentAccess]) {
+        for a in access.iter() {
+            if !self.allows(a) {
+                panic!(
+                    "[scheduler] system {} accessed component {} ({}) without declaring it",
+                    self.system,
+                    a.name,
+                    if a.mutable { "write" } else { "read" }
+                );
+            }
+        }
+    }
+
+    fn storage_ptr<C: Component + 'static>(&mut self) -> Option<*mut SparseSet<C>> {
+        // the storage under TypeId::of::<C>() is always a SparseSet<C>
+        self.storages
+            .get(&TypeId::of::<C>())
+            .map(|ptr| *ptr as *mut SparseSet<C>)
+    }
+
+    fn allocator(&self) -> &VersionedIndexAllocator {
+        // entities are only created and deleted on the main thread, between batches
+        unsafe { &*self.allocator }
+    }
+
+    fn live_entities(&self) -> Vec<VersionedIndex> {
+        unsafe { (*self.entities).entities().to_vec() }
+    }
+}
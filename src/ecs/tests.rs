@@ -8,6 +8,15 @@ mod ecs_tests {
     #[derive(Component, Debug, PartialEq)]
     struct TransformComponent {}
 
+    #[derive(Component, Debug, PartialEq, Clone)]
+    struct Position(f32);
+
+    #[derive(Component, Debug, PartialEq, Clone)]
+    struct Velocity(f32);
+
+    #[derive(Component, Debug, PartialEq, Clone)]
+    struct Tag(&'static str);
+
     #[test]
     fn ecs_register_component() {
         let mut registry = EntityManager::new().unwrap();
@@ -19,5 +28,659 @@ mod ecs_tests {
         registry.register_component::<TransformComponent>();
         assert_eq!(registry.registered_components_len(), 2);
     }
-}
 
+    #[test]
+    fn ecs_query_iter_tuples() {
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager
+            .register_component::<Position>()
+            .register_component::<Velocity>()
+            .register_component::<Tag>();
+
+        let moving = EntityBuilder::create(&mut entity_manager)
+            .with(Position(0.0))
+            .with(Velocity(2.0))
+            .with(Tag("moving"))
+            .build();
+        let untagged = EntityBuilder::create(&mut entity_manager)
+            .with(Position(1.0))
+            .with(Velocity(3.0))
+            .build();
+        let still = EntityBuilder::create(&mut entity_manager)
+            .with(Position(5.0))
+            .build();
+
+        let mut visited = vec![];
+        for (entity, pos, vel, tag) in
+            entity_manager.query_iter::<(&mut Position, &Velocity, Option<&Tag>)>()
+        {
+            pos.0 += vel.0;
+            visited.push((entity, tag.map(|t| t.0)));
+        }
+
+        assert_eq!(visited, vec![(moving, Some("moving")), (untagged, None)]);
+        assert_eq!(entity_manager.get::<Position>(&moving), Some(&Position(2.0)));
+        assert_eq!(entity_manager.get::<Position>(&untagged), Some(&Position(4.0)));
+        assert_eq!(entity_manager.get::<Position>(&still), Some(&Position(5.0)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn ecs_query_iter_conflicting_access() {
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager.register_component::<Position>();
+
+        let _ = entity_manager.query_iter::<(&mut Position, &Position)>();
+    }
+
+    #[test]
+    fn ecs_query_filter_conflicting_access() {
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager.register_component::<Position>();
+        let entity = EntityBuilder::create(&mut entity_manager)
+            .with(Position(1.0))
+            .build();
+
+        // a filter can read what the query writes
+        for (_, position) in
+            entity_manager.query_iter_filtered::<(&mut Position,), Changed<Position>>()
+        {
+            position.0 += 1.0;
+        }
+        assert_eq!(entity_manager.get::<Position>(&entity), Some(&Position(2.0)));
+
+        // and the disabled filter doesn't stop writing CDisabled
+        let disabled = EntityBuilder::create(&mut entity_manager)
+            .with(components::CDisabled::default())
+            .build();
+        assert_eq!(entity_manager.query_iter::<(&mut components::CDisabled,)>().count(), 0);
+        assert_eq!(
+            entity_manager
+                .query_iter_filtered::<(&mut components::CDisabled,), IncludeDisabled>()
+                .map(|(entity, _)| entity)
+                .collect::<Vec<_>>(),
+            [disabled]
+        );
+    }
+
+    #[test]
+    fn ecs_query_filters() {
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager
+            .register_component::<Position>()
+            .register_component::<Velocity>()
+            .register_component::<Tag>();
+
+        let moving = EntityBuilder::create(&mut entity_manager)
+            .with(Position(0.0))
+            .with(Velocity(1.0))
+            .build();
+        let tagged = EntityBuilder::create(&mut entity_manager)
+            .with(Position(0.0))
+            .with(Velocity(1.0))
+            .with(Tag("static"))
+            .build();
+        let still = EntityBuilder::create(&mut entity_manager)
+            .with(Position(0.0))
+            .build();
+
+        let entities = |entity_manager: &mut EntityManager| -> Vec<VersionedIndex> {
+            entity_manager
+                .query_iter_filtered::<(&Position,), (With<Velocity>, Without<Tag>)>()
+                .map(|(entity, _)| entity)
+                .collect()
+        };
+        assert_eq!(entities(&mut entity_manager), vec![moving]);
+
+        let entities: Vec<VersionedIndex> = entity_manager
+            .query_iter_filtered::<(&Position,), Or<(With<Tag>, Without<Velocity>)>>()
+            .map(|(entity, _)| entity)
+            .collect();
+        assert_eq!(entities, vec![tagged, still]);
+    }
+
+    #[test]
+    fn ecs_query_change_detection() {
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager.register_component::<Position>();
+
+        let mut changed = EMQuery::<(&'static Position,), Changed<Position>>::new();
+        let mut added = EMQuery::<(&'static Position,), Added<Position>>::new();
+
+        let first = EntityBuilder::create(&mut entity_manager)
+            .with(Position(0.0))
+            .build();
+        let second = EntityBuilder::create(&mut entity_manager)
+            .with(Position(0.0))
+            .build();
+
+        assert_eq!(changed.iter(&mut entity_manager).count(), 2);
+        assert_eq!(added.iter(&mut entity_manager).count(), 2);
+
+        // nothing happened since the last run
+        assert_eq!(changed.iter(&mut entity_manager).count(), 0);
+        entity_manager.flush();
+        assert_eq!(changed.iter(&mut entity_manager).count(), 0);
+
+        entity_manager.get_mut::<Position>(&second).unwrap().0 = 1.0;
+
+        let entities: Vec<VersionedIndex> = changed
+            .iter(&mut entity_manager)
+            .map(|(entity, _)| entity)
+            .collect();
+        assert_eq!(entities, vec![second]);
+        assert_eq!(added.iter(&mut entity_manager).count(), 0);
+
+        // mutating through a query marks what it yields as changed
+        let mut mutate = EMQuery::<(&'static mut Position,)>::new();
+        assert_eq!(mutate.iter(&mut entity_manager).count(), 2);
+        assert_eq!(changed.iter(&mut entity_manager).count(), 2);
+        assert_eq!(changed.iter(&mut entity_manager).count(), 0);
+        assert!(entity_manager.get::<Position>(&first).is_some());
+    }
+
+    #[test]
+    fn ecs_component_ticks() {
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager
+            .register_component::<Position>()
+            .register_component::<Velocity>();
+
+        let entity = EntityBuilder::create(&mut entity_manager)
+            .with(Position(0.0))
+            .with(Velocity(0.0))
+            .build();
+        let added_at = entity_manager.change_tick();
+
+        entity_manager.flush();
+        let frame = entity_manager.frame_tick();
+
+        assert!(entity_manager.is_added::<Position>(&entity, added_at));
+        assert!(!entity_manager.is_changed::<Position>(&entity, frame));
+
+        entity_manager.get_mut::<Position>(&entity).unwrap().0 = 1.0;
+
+        assert!(entity_manager.is_changed::<Position>(&entity, frame));
+        assert!(!entity_manager.is_added::<Position>(&entity, frame));
+        assert_eq!(
+            entity_manager.get_ticks::<Position>(&entity),
+            Some(ComponentTicks { added: added_at, changed: frame })
+        );
+
+        // the removed log lasts until the next flush
+        entity_manager.remove::<Velocity>(&entity);
+        assert_eq!(entity_manager.removed::<Velocity>(), &[entity]);
+        assert!(entity_manager.removed::<Position>().is_empty());
+
+        entity_manager.flush();
+        assert!(entity_manager.removed::<Velocity>().is_empty());
+    }
+
+    #[test]
+    fn ecs_despawn_removes_components() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager
+            .register_component::<Position>()
+            .register_component::<Tag>();
+
+        let dropped = Rc::new(RefCell::new(Vec::<&'static str>::new()));
+        let hook_dropped = dropped.clone();
+        entity_manager.on_remove::<Tag>(move |_, tag| hook_dropped.borrow_mut().push(tag.0));
+
+        let doomed = EntityBuilder::create(&mut entity_manager)
+            .with(Position(1.0))
+            .with(Tag("doomed"))
+            .build();
+        let survivor = EntityBuilder::create(&mut entity_manager)
+            .with(Position(2.0))
+            .build();
+
+        entity_manager.set_to_delete(doomed);
+        entity_manager.flush();
+
+        assert_eq!(entity_manager.count(), 1);
+        assert_eq!(entity_manager.get_valid_entities(), vec![survivor]);
+        assert_eq!(entity_manager.query_all::<Position>(), vec![survivor]);
+        assert!(entity_manager.query_all::<Tag>().is_empty());
+        assert_eq!(*dropped.borrow(), vec!["doomed"]);
+        assert_eq!(entity_manager.removed::<Tag>(), &[doomed]);
+
+        // the freed slot is reused without stale components leaking into the new entity
+        let reused = entity_manager.create();
+        assert_eq!(reused.index(), doomed.index());
+        assert!(entity_manager.get::<Tag>(&reused).is_none());
+
+        // the stale handle can't add to or overwrite the entity now in its slot
+        entity_manager.add(&reused, Tag("reused"));
+        entity_manager.add(&doomed, Tag("ghost"));
+        entity_manager.add_many(&[doomed, survivor], [Position(3.0), Position(4.0)]);
+        entity_manager.flush();
+
+        assert_eq!(entity_manager.get::<Tag>(&reused), Some(&Tag("reused")));
+        assert!(entity_manager.get::<Position>(&reused).is_none());
+        assert_eq!(entity_manager.get::<Position>(&survivor), Some(&Position(4.0)));
+        assert!(entity_manager.removed::<Tag>().is_empty());
+        assert_eq!(*dropped.borrow(), vec!["doomed"]);
+    }
+
+    #[test]
+    fn ecs_hierarchy() {
+        use components::{CChildren, CGlobalTransform2D, CParent, CTransform2D};
+
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager
+            .register_component::<CParent>()
+            .register_component::<CChildren>()
+            .register_component::<CTransform2D>()
+            .register_component::<CGlobalTransform2D>();
+
+        let transform = |x: f32, y: f32| CTransform2D {
+            translate: glm::vec2(x, y),
+            ..CTransform2D::default()
+        };
+        let ship = EntityBuilder::create(&mut entity_manager)
+            .with(transform(10.0, 0.0))
+            .build();
+        let turret = EntityBuilder::create(&mut entity_manager)
+            .with(transform(0.0, 5.0))
+            .build();
+        let barrel = EntityBuilder::create(&mut entity_manager)
+            .with(transform(1.0, 0.0))
+            .build();
+        let other = entity_manager.create();
+
+        entity_manager.set_parent(&turret, &other);
+        entity_manager.set_parent(&turret, &ship);
+        entity_manager.set_parent(&barrel, &turret);
+        assert_eq!(entity_manager.parent(&turret), Some(ship));
+        assert_eq!(entity_manager.children(&ship), &[turret]);
+        assert!(entity_manager.children(&other).is_empty());
+
+        // would make a cycle
+        entity_manager.set_parent(&ship, &barrel);
+        assert_eq!(entity_manager.parent(&ship), None);
+
+        propagate_transforms(&mut entity_manager);
+        let global = entity_manager.get::<CGlobalTransform2D>(&barrel).unwrap();
+        assert_eq!(global.matrix.column(3).xy(), glm::vec2(11.0, 5.0));
+
+        // deleting the turret alone leaves the barrel as a root
+        entity_manager.set_to_delete(turret);
+        entity_manager.flush();
+        assert!(entity_manager.children(&ship).is_empty());
+        assert_eq!(entity_manager.parent(&barrel), None);
+
+        entity_manager.set_parent(&barrel, &ship);
+        entity_manager.set_to_delete_recursive(ship);
+        entity_manager.flush();
+        assert!(!entity_manager.is_valid(&barrel));
+        assert_eq!(entity_manager.count(), 1);
+
+        // a root without a transform passes the identity on
+        let group = entity_manager.create();
+        let marker = EntityBuilder::create(&mut entity_manager)
+            .with(transform(2.0, 3.0))
+            .build();
+        entity_manager.set_parent(&marker, &group);
+        propagate_transforms(&mut entity_manager);
+        let global = entity_manager.get::<CGlobalTransform2D>(&marker).unwrap();
+        assert_eq!(global.matrix.column(3).xy(), glm::vec2(2.0, 3.0));
+
+        // however CParent is removed, the parent lets go of the child
+        entity_manager.remove::<CParent>(&marker);
+        assert!(entity_manager.children(&group).is_empty());
+
+        entity_manager.set_parent(&marker, &group);
+        entity_manager.remove_by_name(&marker, "CParent");
+        assert!(entity_manager.children(&group).is_empty());
+
+        entity_manager.set_parent(&marker, &group);
+        entity_manager.remove::<CChildren>(&group);
+        assert_eq!(entity_manager.parent(&marker), None);
+    }
+
+    #[test]
+    fn ecs_reflect_components() {
+        use components::{CSprite, CTag, CTransform2D};
+
+        let mut transform = CTransform2D::default();
+        let fields: Vec<_> = transform.fields().iter().map(|f| (f.name, f.type_name)).collect();
+        assert_eq!(transform.type_name(), "CTransform2D");
+        assert_eq!(
+            fields,
+            [("translate", "glm::Vec2"), ("rotate", "f32"), ("scale", "glm::Vec2")]
+        );
+
+        transform.set_path("translate.y", Value::Float(4.0)).unwrap();
+        assert_eq!(transform.translate.y, 4.0);
+        assert_eq!(transform.get_path("translate").unwrap(), Value::Vec2(glm::vec2(0.0, 4.0)));
+        assert!(transform.set_path("translate.q", Value::Float(1.0)).is_err());
+        assert!(transform.set_path("rotate", Value::String("no".into())).is_err());
+
+        // ignored fields are left out
+        let sprite = CSprite::default();
+        assert!(sprite.fields().iter().all(|f| f.name != "mvp"));
+        assert_eq!(sprite.get_path("texture_atlas").unwrap(), Value::None);
+        assert_eq!(Velocity(2.0).get_path("0").unwrap(), Value::Float(2.0));
+
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager
+            .register_component::<CTag>()
+            .register_component::<CTransform2D>();
+        let entity = EntityBuilder::create(&mut entity_manager)
+            .with(CTag { tag: "player".into() })
+            .with(transform)
+            .build();
+
+        let names: Vec<_> = entity_manager
+            .reflect_components(&entity)
+            .iter()
+            .map(|c| c.type_name())
+            .collect();
+        assert_eq!(names, ["CTag", "CTransform2D"]);
+
+        let since = entity_manager.change_tick();
+        entity_manager
+            .reflect_component_mut(&entity, "CTag")
+            .unwrap()
+            .set_value(Value::Struct(vec![("tag".into(), Value::String("enemy".into()))]))
+            .unwrap();
+        assert_eq!(entity_manager.get::<CTag>(&entity).unwrap().tag, "enemy");
+        assert!(entity_manager.is_changed::<CTag>(&entity, since));
+
+        entity_manager.remove_by_name(&entity, "CTransform2D");
+        assert!(entity_manager.get::<CTransform2D>(&entity).is_none());
+    }
+
+    #[test]
+    fn ecs_type_registry() {
+        use components::CTag;
+
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager.register_default_component::<CTag>();
+        let entity = entity_manager.create();
+
+        // adding an unregistered component registers it
+        entity_manager.add(&entity, Position(1.0));
+        assert_eq!(entity_manager.get::<Position>(&entity), Some(&Position(1.0)));
+        let position = entity_manager.types().get(Position::NAME).unwrap();
+        assert_eq!(position.type_id, std::any::TypeId::of::<Position>());
+        assert!(!position.is_constructible());
+        assert!(entity_manager.types().get(Velocity::NAME).is_none());
+
+        entity_manager
+            .add_by_name(&entity, "CTag")
+            .unwrap()
+            .set_path("tag", Value::String("player".into()))
+            .unwrap();
+        assert_eq!(entity_manager.get::<CTag>(&entity).unwrap().tag, "player");
+
+        assert!(matches!(
+            entity_manager.add_by_name(&entity, Position::NAME),
+            Err(crate::prelude::QPError::ComponentNotConstructible(_))
+        ));
+        assert!(matches!(
+            entity_manager.add_by_name(&entity, "Missing"),
+            Err(crate::prelude::QPError::UnknownComponent(_))
+        ));
+
+        // without a default it can still be built from a saved value
+        assert!(entity_manager.types().get(Position::NAME).unwrap().can_build());
+        let other = entity_manager.create();
+        entity_manager
+            .build_by_name(&other, Position::NAME, Value::Struct(vec![("0".into(), Value::Float(4.0))]))
+            .unwrap();
+        assert_eq!(entity_manager.get::<Position>(&other), Some(&Position(4.0)));
+        assert!(matches!(
+            entity_manager.build_by_name(&other, Position::NAME, Value::Float(4.0)),
+            Err(crate::prelude::QPError::ReflectTypeMismatch(..))
+        ));
+
+        // a component with a default can be constructed after registering itself
+        #[derive(Component, Debug, PartialEq, Default)]
+        #[component(name = "Stamina")]
+        struct Stamina(f32);
+
+        entity_manager.add(&entity, Stamina(5.0));
+        assert!(entity_manager.types().get("Stamina").unwrap().is_constructible());
+
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager.register_all_components();
+        assert!(entity_manager.types().get("CTag").unwrap().is_constructible());
+        assert!(!entity_manager.types().get(Position::NAME).unwrap().is_constructible());
+
+        // types with the same name in different modules are told apart by their path
+        mod other {
+            use crate::prelude::qp_ecs::Component;
+
+            #[derive(Component, Debug, PartialEq)]
+            pub struct Position(pub f32);
+        }
+
+        let entity = entity_manager.create();
+        entity_manager.add(&entity, other::Position(2.0));
+        assert_eq!(Position::NAME, "quipi::ecs::tests::ecs_tests::Position");
+        assert_eq!(other::Position::NAME, "quipi::ecs::tests::ecs_tests::other::Position");
+        assert_eq!(
+            entity_manager.types().get(other::Position::NAME).unwrap().type_id,
+            std::any::TypeId::of::<other::Position>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "are both named quipi::ecs::tests::ecs_tests::Position")]
+    fn ecs_type_registry_name_collision() {
+        #[derive(Reflect, Debug, PartialEq)]
+        struct Impostor;
+
+        impl Component for Impostor {
+            const NAME: &'static str = Position::NAME;
+        }
+
+        let mut entity_manager = EntityManager::new().unwrap();
+        entity_manager
+            .register_component::<Position>()
+            .register_component::<Impostor>();
+    }
+
+    #[test]
+    fn ecs_spawn_batch_and_pool() {
+        let mut entity_manager = EntityManager::new().unwrap();
+
+        let entities = entity_manager.spawn_batch((0..4).map(|i| (Position(i as f32), Velocity(1.0))));
+        assert_eq!(entities.len(), 4);
+        assert_eq!(entity_manager.get::<Position>(&entities[2]), Some(&Position(2.0)));
+        assert_eq!(entity_manager.query_all::<Velocity>().len(), 4);
+
+        let mut pool = EntityPool::new((Position(0.0), Velocity(5.0))).with_max_pooled(2);
+        pool.prewarm(&mut entity_manager, 1);
+
+        let bullets = pool.acquire_many(&mut entity_manager, 3);
+        entity_manager.get_mut::<Position>(&bullets[0]).unwrap().0 = 9.0;
+        entity_manager.add(&bullets[0], Tag("tracer"));
+        assert_eq!(entity_manager.query_all::<Velocity>().len(), 7);
+
+        for bullet in bullets.iter() {
+            pool.release(&mut entity_manager, *bullet);
+        }
+        entity_manager.flush();
+
+        // released entities stay alive without their components
+        assert_eq!(entity_manager.query_all::<Velocity>().len(), 4);
+        assert!(entity_manager.is_valid(&bullets[0]));
+        assert!(entity_manager.is_valid(&bullets[1]));
+        assert!(!entity_manager.is_valid(&bullets[2]));
+
+        // and disabled, so the components outside the bundle don't show up in queries
+        assert_eq!(entity_manager.query_iter::<(&Tag,)>().count(), 0);
+
+        // the most recently released comes back first, with its old components
+        let reused = pool.acquire(&mut entity_manager);
+        assert_eq!(reused, bullets[1]);
+        let reused = pool.acquire(&mut entity_manager);
+        assert_eq!(reused, bullets[0]);
+        assert_eq!(entity_manager.get::<Position>(&reused), Some(&Position(9.0)));
+        assert!(entity_manager.is_enabled(&reused));
+        assert_eq!(
+            entity_manager.query_iter::<(&Tag,)>().map(|(entity, _)| entity).collect::<Vec<_>>(),
+            [reused]
+        );
+
+        assert_eq!(
+            pool.stats(),
+            PoolStats {
+                active: 2,
+                peak_active: 3,
+                pooled: 0,
+                created: 2,
+                reused: 3,
+                discarded: 1,
+            }
+        );
+    }
+
+    #[derive(Bundle, Debug, PartialEq, Clone)]
+    struct Body {
+        position: Position,
+        velocity: Option<Velocity>,
+    }
+
+    #[derive(Bundle, Debug, PartialEq, Clone)]
+    struct Named(Body, Tag);
+
+    #[test]
+    fn ecs_bundles() {
+        let mut entity_manager = EntityManager::new().unwrap();
+
+        let body = Body {
+            position: Position(1.0),
+            velocity: None,
+        };
+        let entity = EntityBuilder::create(&mut entity_manager)
+            .with(Named(body.clone(), Tag("rock")))
+            .build();
+        assert_eq!(entity_manager.get::<Position>(&entity), Some(&Position(1.0)));
+        assert_eq!(entity_manager.get::<Tag>(&entity), Some(&Tag("rock")));
+        assert!(entity_manager.get::<Velocity>(&entity).is_none());
+
+        entity_manager.add(&entity, Velocity(2.0));
+        entity_manager.remove::<Body>(&entity);
+        assert!(entity_manager.get::<Position>(&entity).is_none());
+        assert!(entity_manager.get::<Velocity>(&entity).is_none());
+        assert_eq!(entity_manager.get::<Tag>(&entity), Some(&Tag("rock")));
+        assert_eq!(entity_manager.removed::<Position>(), [entity]);
+
+        // taking a bundle the entity only partly has takes nothing
+        entity_manager.add(&entity, Position(3.0));
+        entity_manager.remove::<Tag>(&entity);
+        assert_eq!(Named::take_from(&entity, &mut entity_manager), None);
+        assert_eq!(entity_manager.get::<Position>(&entity), Some(&Position(3.0)));
+
+        // removing it removes what the entity has
+        entity_manager.remove::<Named>(&entity);
+        assert!(entity_manager.reflect_components(&entity).is_empty());
+
+        let entities = entity_manager.spawn_batch(vec![Named(body, Tag("pebble")); 3]);
+        assert_eq!(entity_manager.query_all::<Tag>().len(), 3);
+        assert_eq!(entity_manager.get::<Tag>(&entities[2]), Some(&Tag("pebble")));
+    }
+
+    #[derive(Component, Debug, PartialEq, Default)]
+    struct Mass(f32);
+
+    #[derive(Component, Debug, PartialEq, Default)]
+    #[require(Mass)]
+    struct Collider;
+
+    #[derive(Component, Debug, PartialEq)]
+    #[require(Collider, Mass)]
+    struct RigidBody(f32);
+
+    #[test]
+    fn ecs_required_components() {
+        use components::{CQuad, CSprite, CTransform2D};
+
+        let mut entity_manager = EntityManager::new().unwrap();
+
+        let heavy = EntityBuilder::create(&mut entity_manager)
+            .with(Mass(5.0))
+            .with(RigidBody(1.0))
+            .build();
+        assert_eq!(entity_manager.get::<Mass>(&heavy), Some(&Mass(5.0)));
+        assert_eq!(entity_manager.get::<Collider>(&heavy), Some(&Collider));
+
+        let bodies = entity_manager.spawn_batch((0..3).map(|i| RigidBody(i as f32)));
+        assert_eq!(entity_manager.get::<Mass>(&bodies[2]), Some(&Mass(0.0)));
+        assert_eq!(entity_manager.query_all::<Collider>().len(), 4);
+
+        let sprite = entity_manager.create();
+        entity_manager.add(&sprite, CSprite::default());
+        assert!(entity_manager.get::<CTransform2D>(&sprite).is_some());
+        assert!(entity_manager.get::<CQuad>(&sprite).is_some());
+        assert!(entity_manager.validate_requirements().is_empty());
+
+        entity_manager.remove::<Mass>(&heavy);
+        assert_eq!(
+            entity_manager.validate_requirements(),
+            [
+                MissingRequirement {
+                    entity: heavy,
+                    component: Collider::NAME,
+                    required: Mass::NAME,
+                },
+                MissingRequirement {
+                    entity: heavy,
+                    component: RigidBody::NAME,
+                    required: Mass::NAME,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ecs_enable_disable() {
+        use components::CDisabled;
+
+        let mut entity_manager = EntityManager::new().unwrap();
+
+        let parent = EntityBuilder::create(&mut entity_manager).with(Position(0.0)).build();
+        let child = EntityBuilder::create(&mut entity_manager).with(Position(1.0)).build();
+        let other = EntityBuilder::create(&mut entity_manager).with(Position(2.0)).build();
+        entity_manager.set_parent(&child, &parent);
+
+        entity_manager.set_enabled(&parent, false);
+        assert!(!entity_manager.is_enabled(&parent));
+        assert!(!entity_manager.is_enabled(&child));
+        assert_eq!(entity_manager.query_all::<Position>(), [other]);
+        assert_eq!(entity_manager.query_iter::<(&Position,)>().count(), 1);
+        assert_eq!(
+            entity_manager.query_iter_filtered::<(&Position,), IncludeDisabled>().count(),
+            3
+        );
+        assert_eq!(
+            entity_manager.query_iter_filtered::<(&Position,), With<CDisabled>>().count(),
+            2
+        );
+
+        // a child disabled on its own stays disabled when the parent comes back
+        entity_manager.set_enabled(&child, false);
+        entity_manager.set_enabled(&parent, true);
+        assert!(entity_manager.is_enabled(&parent));
+        assert!(!entity_manager.is_enabled(&child));
+
+        entity_manager.set_enabled(&child, true);
+        assert_eq!(entity_manager.query_iter::<(&Position,)>().count(), 3);
+
+        // moving under a disabled parent disables, moving out enables again
+        entity_manager.set_enabled(&other, false);
+        entity_manager.set_parent(&parent, &other);
+        assert!(!entity_manager.is_enabled(&child));
+
+        entity_manager.remove_parent(&parent);
+        assert!(entity_manager.is_enabled(&parent));
+        assert!(entity_manager.is_enabled(&child));
+        assert!(!entity_manager.is_enabled(&other));
+    }
+}
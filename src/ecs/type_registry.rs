@@ -0,0 +1,208 @@
+use std::{any::TypeId, collections::HashMap, marker::PhantomData};
+
+use super::{
+    entity_manager::EntityManager,
+    prelude::{Component, Value, VersionedIndex},
+};
+use crate::QPResult;
+
+type Constructor = fn(&mut EntityManager, &VersionedIndex);
+type Builder = fn(&mut EntityManager, &VersionedIndex, Value) -> QPResult<()>;
+
+/**
+ * What the type registry knows about one component type
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentRegistration {
+    /// the stable name from `Component::NAME`
+    pub name: &'static str,
+    pub type_id: TypeId,
+    /// the full rust path, for debugging
+    pub type_path: &'static str,
+    /// names of the components it requires
+    pub required: &'static [&'static str],
+
+    constructor: Option<Constructor>,
+    builder: Option<Builder>,
+}
+
+impl ComponentRegistration {
+    /// true if it can be added to an entity by name
+    pub fn is_constructible(&self) -> bool {
+        self.constructor.is_some()
+    }
+
+    /// true if it can be built from a saved value, see `EntityManager::build_by_name`
+    pub fn can_build(&self) -> bool {
+        self.builder.is_some()
+    }
+
+    pub(super) fn constructor(&self) -> Option<Constructor> {
+        self.constructor
+    }
+
+    pub(super) fn builder(&self) -> Option<Builder> {
+        self.builder
+    }
+}
+
+/**
+ * An entity missing a component that another one on it requires
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingRequirement {
+    pub entity: VersionedIndex,
+    pub component: &'static str,
+    pub required: &'static str,
+}
+
+/**
+ * Maps component names to their types, so scene files and the editor
+ * can refer to components by name. Every storage in the entity manager
+ * has an entry; types registered with a default can also be constructed
+ */
+#[derive(Debug, Default)]
+pub struct TypeRegistry {
+    by_name: HashMap<&'static str, ComponentRegistration>,
+    names: HashMap<TypeId, &'static str>,
+}
+
+impl TypeRegistry {
+    pub fn get(&self, name: &str) -> Option<&ComponentRegistration> {
+        self.by_name.get(name)
+    }
+
+    pub fn get_by_type(&self, type_id: &TypeId) -> Option<&ComponentRegistration> {
+        self.by_name.get(self.names.get(type_id)?)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.by_name.contains_key(name)
+    }
+
+    /// every registration, sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = &ComponentRegistration> {
+        let mut registrations: Vec<&ComponentRegistration> = self.by_name.values().collect();
+        registrations.sort_by_key(|registration| registration.name);
+
+        registrations.into_iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_name.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+
+    /**
+     * panics if another type already has the name, scene files couldn't
+     * tell them apart. Derived names only collide when given with `#[component(name)]`
+     */
+    pub(super) fn register<C: Component + 'static>(
+        &mut self,
+        constructor: Option<Constructor>,
+        builder: Option<Builder>,
+    ) {
+        if let Some(existing) = self.by_name.get_mut(C::NAME) {
+            if existing.type_id != TypeId::of::<C>() {
+                panic!(
+                    "[type registry] {} and {} are both named {}, give one of them another #[component(name)]",
+                    existing.type_path,
+                    std::any::type_name::<C>(),
+                    C::NAME
+                );
+            }
+
+            if constructor.is_some() {
+                existing.constructor = constructor;
+            }
+
+            if builder.is_some() {
+                existing.builder = builder;
+            }
+
+            return;
+        }
+
+        self.names.insert(TypeId::of::<C>(), C::NAME);
+        self.by_name.insert(
+            C::NAME,
+            ComponentRegistration {
+                name: C::NAME,
+                type_id: TypeId::of::<C>(),
+                type_path: std::any::type_name::<C>(),
+                required: C::REQUIRED,
+                constructor,
+                builder,
+            },
+        );
+    }
+}
+
+/**
+ * Submitted by `#[derive(Component)]` for every component that isn't
+ * generic, so components register themselves without being listed anywhere
+ */
+#[doc(hidden)]
+pub struct ComponentRegistrar {
+    pub type_id: fn() -> TypeId,
+    pub register: fn(&mut EntityManager),
+}
+
+inventory::collect!(ComponentRegistrar);
+
+/// every component the derive has seen, in no particular order
+pub(super) fn registrars() -> impl Iterator<Item = &'static ComponentRegistrar> {
+    inventory::iter::<ComponentRegistrar>.into_iter()
+}
+
+/**
+ * Lets the derive register a component with its default as the constructor
+ * when it has one, and without otherwise. Calling `register_with` on
+ * `&&&RegisterProbe::<C>::new()` picks the first of these impls that applies.
+ * Components that can't be stored, ie without PartialEq, are skipped
+ */
+#[doc(hidden)]
+pub struct RegisterProbe<C>(PhantomData<fn() -> C>);
+
+impl<C> RegisterProbe<C> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait RegisterDefault {
+    fn register_with(&self, entity_manager: &mut EntityManager);
+}
+
+impl<C: Component + Default + std::fmt::Debug + PartialEq + 'static> RegisterDefault
+    for &&RegisterProbe<C>
+{
+    fn register_with(&self, entity_manager: &mut EntityManager) {
+        entity_manager.register_default_component::<C>();
+    }
+}
+
+#[doc(hidden)]
+pub trait RegisterPlain {
+    fn register_with(&self, entity_manager: &mut EntityManager);
+}
+
+impl<C: Component + std::fmt::Debug + PartialEq + 'static> RegisterPlain for &RegisterProbe<C> {
+    fn register_with(&self, entity_manager: &mut EntityManager) {
+        entity_manager.register_component::<C>();
+    }
+}
+
+#[doc(hidden)]
+pub trait RegisterNone {
+    fn register_with(&self, entity_manager: &mut EntityManager);
+}
+
+impl<C> RegisterNone for RegisterProbe<C> {
+    fn register_with(&self, _entity_manager: &mut EntityManager) {}
+}
@@ -6,7 +6,7 @@ mod painter;
 #[cfg(feature = "qp_editor")]
 pub mod prelude {
     use super::{input::parse_event, painter::Painter};
-    use crate::prelude::World;
+    use crate::prelude::{qp_gfx::Viewport, Events, World};
     use crate::QPResult;
     use egui::RawInput;
     use sdl2::event::{Event, WindowEvent};
@@ -43,12 +43,15 @@ pub mod prelude {
         }
 
         fn handle_input(&mut self, world: &mut World) {
-            for event in world.events.iter() {
+            // taken for the loop, which needs the world too, and put back after
+            let events = std::mem::take(world.resource_mut::<Events>());
+
+            for event in events.iter() {
                 match event {
                     Event::Window { win_event, .. } => match win_event {
                         WindowEvent::Resized(width, height)
                         | WindowEvent::SizeChanged(width, height) => {
-                            world.viewport.set_dimensions(0, 0, *width, *height);
+                            world.resource_mut::<Viewport>().set_dimensions(0, 0, *width, *height);
                             self.painter.update_screen_rect();
                             self.raw_input.screen_rect = Some(self.painter.screen_rect);
                         }
@@ -61,6 +64,8 @@ pub mod prelude {
                     }
                 }
             }
+
+            *world.resource_mut::<Events>() = events;
         }
     }
 }
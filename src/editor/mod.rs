@@ -4,7 +4,7 @@ mod backend;
 pub mod prelude {
     use super::backend::prelude::*;
     use crate::prelude::{qp_core::Timer, FrameResult};
-    use crate::prelude::{Controller, World};
+    use crate::prelude::{Controller, DebugInfo, World};
     use crate::QPResult;
     use egui::Context;
 
@@ -45,7 +45,7 @@ pub mod prelude {
 
             self.backend.end_frame(world);
 
-            world.debug_info.editor_ms = (self.timer.delta() * 1000.0) as u32;
+            world.resource_mut::<DebugInfo>().editor_ms = (self.timer.delta() * 1000.0) as u32;
 
             FrameResult::None
         }
@@ -33,6 +33,9 @@ pub enum QPError {
     #[error("trying to load as existing asset")]
     DuplicateAsset,
 
+    #[error("asset {0} is already loaded as a {1}")]
+    AssetTypeMismatch(String, &'static str),
+
     #[error("shader not found")]
     ShaderNotFound,
 
@@ -71,4 +74,40 @@ pub enum QPError {
 
     #[error("failed to get a lock: {0}")]
     MutexLockFailed(String),
+
+    #[error("systems in stage {0} have a cycle in their ordering: {1}")]
+    SystemOrderCycle(String, String),
+
+    #[error("no system labeled {0} in stage {1}")]
+    UnknownSystemLabel(String, String),
+
+    #[error("more than one system is labeled {0}")]
+    DuplicateSystemLabel(String),
+
+    #[error("{0} has no field {1}")]
+    ReflectFieldNotFound(String, String),
+
+    #[error("can't set {0} from a {1} value")]
+    ReflectTypeMismatch(String, String),
+
+    #[error("no component is registered as {0}")]
+    UnknownComponent(String),
+
+    #[error("component {0} has no default or can't be built from a value, it can't be added by name")]
+    ComponentNotConstructible(String),
+
+    #[error("prefab {0} isn't loaded and couldn't be read from prefabs/")]
+    PrefabNotFound(String),
+
+    #[error("prefab {0} contains an instance of itself")]
+    PrefabCycle(String),
+
+    #[error("{0} isn't in any mounted asset directory or archive")]
+    AssetFileNotFound(String),
+
+    #[error("invalid qpak archive: {0}")]
+    InvalidArchive(String),
+
+    #[error("{0} doesn't match its checksum in the archive")]
+    ArchiveChecksumMismatch(String),
 }
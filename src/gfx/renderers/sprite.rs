@@ -1,8 +1,8 @@
 use crate::{
     platform::opengl::capabilities::{gl_blending_func, gl_enable, GLBlendingFactor, GLCapability},
     prelude::{
-        qp_assets::{RCamera2D, RShader},
-        qp_ecs::components::{CSprite, CTransform2D},
+        qp_assets::{Handle, RCamera2D, RShader},
+        qp_ecs::components::{CGlobalTransform2D, CSprite, CTransform2D, CVisibility},
         GlobalRegistry, QPError, Renderer, World,
     },
     QPResult,
@@ -11,19 +11,22 @@ use crate::{
 use super::super::batch_renderer::BatchRenderer;
 
 pub struct SpriteRenderer {
-    camera: u64,
-    shader: u64,
+    camera: Handle<RCamera2D>,
+    shader: Handle<RShader>,
 
     renderer: BatchRenderer<10000, CSprite>,
+    // the sprite being drawn with its matrices applied. Drawing a copy keeps
+    // the query read only, so sprites aren't marked as changed every frame
+    scratch: CSprite,
 }
 
 impl SpriteRenderer {
     pub fn new(registry: &mut GlobalRegistry, camera: &str, shader: &str) -> QPResult<Self> {
-        let Some(camera) = registry.asset_manager.get_asset_id(camera) else {
+        let Some(camera) = registry.asset_manager.handle(camera) else {
             return Err(QPError::CameraNotLoaded);
         };
 
-        let Some(shader) = registry.asset_manager.get_asset_id(shader) else {
+        let Some(shader) = registry.asset_manager.handle(shader) else {
             return Err(QPError::ShaderNotLoaded);
         };
 
@@ -31,14 +34,13 @@ impl SpriteRenderer {
             camera,
             shader,
             renderer: BatchRenderer::new(),
+            scratch: CSprite::default(),
         })
     }
 }
 
 impl Renderer for SpriteRenderer {
     fn draw(&mut self, world: &mut World) -> Option<u32> {
-        let entities = world.registry.entity_manager.query_all::<CSprite>();
-
         gl_enable(GLCapability::AlphaBlending);
         gl_blending_func(
             GLBlendingFactor::SrcAlpha,
@@ -48,7 +50,7 @@ impl Renderer for SpriteRenderer {
         if world
             .registry
             .asset_manager
-            .get::<RShader>(self.shader)
+            .get(&self.shader)
             .is_none()
         {
             #[cfg(debug_assertions)]
@@ -57,7 +59,7 @@ impl Renderer for SpriteRenderer {
             return None;
         };
 
-        let Some(camera) = world.registry.asset_manager.get::<RCamera2D>(self.camera) else {
+        let Some(camera) = world.registry.asset_manager.get(&self.camera) else {
             #[cfg(debug_assertions)]
             println!("[sprite controller] tried to use a camera that is not loaded");
 
@@ -66,52 +68,43 @@ impl Renderer for SpriteRenderer {
 
         self.renderer.reset_info();
         self.renderer.begin_batch();
-        for entity in entities.iter() {
-            let Some(sprite) = world.registry.entity_manager.get::<CSprite>(&entity) else {
-                #[cfg(debug_assertions)]
-                println!("[sprite controller] tried to render a sprite without a sprite component");
-
-                continue;
-            };
-
-            if sprite.skip {
+        for (_, sprite, transform, global, visibility) in world
+            .registry
+            .entity_manager
+            .query_iter::<(
+                &CSprite,
+                &CTransform2D,
+                Option<&CGlobalTransform2D>,
+                Option<&CVisibility>,
+            )>()
+        {
+            if sprite.skip || visibility.is_some_and(|v| !v.visible) {
                 continue;
             }
 
-            let Some(transform) = world.registry.entity_manager.get::<CTransform2D>(&entity) else {
-                #[cfg(debug_assertions)]
-                println!(
-                    "[sprite controller] tried to render a sprite without a tranform component"
-                );
-
-                continue;
+            // the global transform is missing until the first propagation after the sprite is spawned
+            let model = match global {
+                Some(global) => global.matrix,
+                None => transform.to_matrix(),
             };
-            let model = transform.to_matrix();
 
-            let Some(sprite) = world.registry.entity_manager.get_mut::<CSprite>(&entity) else {
-                continue;
-            };
-            sprite.apply_matrices(model, camera.view, camera.projection);
+            self.scratch.clone_from(sprite);
+            self.scratch.apply_matrices(model, camera.view, camera.projection);
 
-            let sprite = world
-                .registry
-                .entity_manager
-                .get::<CSprite>(&entity)
-                .unwrap();
             let texture = match &sprite.texture_atlas {
-                Some(atlas) => world.registry.asset_manager.get(atlas.texture),
+                Some(atlas) => world.registry.asset_manager.get(&atlas.texture),
                 _ => None,
             };
 
             self.renderer.draw_mesh(
-                sprite,
-                world.registry.asset_manager.get(self.shader)?,
+                &self.scratch,
+                world.registry.asset_manager.get(&self.shader)?,
                 texture,
             );
         }
         self.renderer.end_batch();
         self.renderer
-            .flush_batch(world.registry.asset_manager.get(self.shader)?);
+            .flush_batch(world.registry.asset_manager.get(&self.shader)?);
 
         Some(self.renderer.draw_calls)
     }
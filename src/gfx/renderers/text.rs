@@ -2,9 +2,9 @@ use crate::{
     gfx::batch_renderer::{Mesh, Vertex},
     platform::opengl::capabilities::*,
     prelude::{
-        qp_assets::{RFont, RShader},
-        qp_gfx::BatchRenderer,
-        Renderer, World,
+        qp_assets::{Handle, RFont, RShader},
+        qp_gfx::{BatchRenderer, Viewport},
+        Renderer, TextBuffer, World,
     },
     QPResult,
 };
@@ -34,17 +34,19 @@ impl Renderer for TextRenderer {
             GLBlendingFactor::OneMinusSrcAlpha,
         );
 
-        let (_x, _y, width, height) = world.viewport.get_dimensions();
+        let (_x, _y, width, height) = world.resource::<Viewport>().get_dimensions();
 
         let projection = &glm::ortho(0.0, width as f32, 0.0, height as f32, 0.0, 0.2);
 
         self.renderer.reset_info();
         self.renderer.begin_batch();
-        for text_obj in world.text_buffer.iter_mut() {
+        let text_buffer = world.resources.get_mut::<TextBuffer>()?;
+
+        for text_obj in text_buffer.iter_mut() {
             let Some(font) = world
                 .registry
                 .asset_manager
-                .get::<RFont>(text_obj.style.font)
+                .get(&text_obj.style.font)
             else {
                 #[cfg(debug_assertions)]
                 {
@@ -95,7 +97,7 @@ pub struct QPText {
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct QPTextStyle {
-    pub font: u64,
+    pub font: Handle<RFont>,
     pub color: glm::Vec4,
     pub scale: f32,
 }
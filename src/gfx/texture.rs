@@ -4,7 +4,7 @@ pub mod texture {
         prelude::QPError,
         prelude::qp_core::{
             QPImage,
-            to_abs_path,
+            vfs,
         },
         platform::opengl::textures::{
             Texture,
@@ -45,10 +45,38 @@ pub mod texture {
     pub fn from_image(
         file_path: &str,
     ) -> QPResult<Texture> {
-        let file_path = &to_abs_path(file_path)?;
-        let format = get_format(file_path);
-        let img = QPImage::from_file(file_path)?;
+        Ok(from_decoded(&decode_image(file_path)?))
+    }
+
+    /// an image read into memory, ready to be uploaded
+    #[derive(Debug)]
+    pub struct DecodedImage {
+        pub width: u32,
+        pub height: u32,
+        pub format: Format,
+        pub pixels: Vec<u8>,
+    }
+
+    /**
+     * reads the file through the vfs and decodes it without touching GL,
+     * so it can run off the main thread
+     */
+    pub fn decode_image(
+        file_path: &str,
+    ) -> QPResult<DecodedImage> {
+        let img = QPImage::from_bytes(&vfs().read(file_path)?)?;
 
+        Ok(DecodedImage {
+            width: img.width,
+            height: img.height,
+            format: get_format(file_path),
+            pixels: img.flipv(),
+        })
+    }
+
+    pub fn from_decoded(
+        img: &DecodedImage,
+    ) -> Texture {
         let texture = Texture::new(
             img.width as i32,
             img.height as i32,
@@ -57,10 +85,9 @@ pub mod texture {
 
         texture
             .bind()
-            .add_image_data(Format::Rgba, format, &img.flipv());
-            
+            .add_image_data(Format::Rgba, img.format, &img.pixels);
 
-        Ok(texture)
+        texture
     }
 
     fn get_format(path: &str) -> Format {
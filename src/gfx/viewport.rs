@@ -1,5 +1,6 @@
 use crate::platform::opengl::functions::gl_set_viewport_dimensions;
 
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Viewport {
     x: i32,
     y: i32,
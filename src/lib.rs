@@ -5,6 +5,9 @@ extern crate sdl2;
 extern crate serde;
 // extern crate gltf;
 
+// lets the derive macros use ::quipi paths inside the engine too
+extern crate self as quipi;
+
 pub mod app;
 pub mod asset_manager;
 pub mod audio;
@@ -15,6 +18,7 @@ pub mod gfx;
 pub mod physics;
 pub mod platform;
 pub mod registry;
+pub mod schedule;
 pub mod schemas;
 pub mod world;
 
@@ -45,8 +49,20 @@ pub mod prelude {
     pub use self::qp_ecs::EntityBuilder;
     pub use self::qp_ecs::VersionedIndex;
     pub use self::registry::GlobalRegistry;
+    pub use self::schedule::ParallelController;
+    pub use self::schedule::Schedule;
+    pub use self::schedule::Stage;
+    pub use self::schedule::SystemAccess;
+    pub use self::schedule::SystemWorld;
     pub use self::schemas::prelude::Schema;
+    pub use self::world::AssetEvents;
+    pub use self::world::DebugInfo;
+    pub use self::world::Delta;
+    pub use self::world::Events;
+    pub use self::world::ResetMode;
+    pub use self::world::TextBuffer;
     pub use self::world::World;
+    pub use self::world::WorldSnapshot;
 
     #[cfg(feature = "qp_editor")]
     pub use self::editor::prelude as qp_editor;
@@ -1,15 +1,11 @@
-use std::{
-    fs,
-    io::Read,
-    ffi
-};
+use std::ffi;
 
 use super::c_str::*;
 use crate::{
     QPResult,
     prelude::{
         QPError,
-        qp_core::to_abs_path
+        qp_core::vfs
     }
 };
 
@@ -52,7 +48,7 @@ impl ShaderProgram {
     }
 
     pub fn from_file(name: &str) -> QPResult<Self> {
-        let name = &to_abs_path(&format!("assets/shaders/{}", name))?;
+        let name = &format!("shaders/{}", name);
         let vert = shader_to_cstring(&format!("{name}.vert"))?;
         let frag = shader_to_cstring(&format!("{name}.frag"))?;
 
@@ -234,13 +230,7 @@ fn str_to_cstring(shader: &str) -> QPResult<ffi::CString> {
 }
 
 fn shader_to_cstring(shader_path: &str) -> QPResult<ffi::CString> {
-    let mut file = fs::File::open(shader_path)?;
-
-    // allocate buffer of the same size as file
-    let mut buffer: Vec<u8> = Vec::with_capacity(
-        file.metadata()?.len() as usize + 1
-    );
-    file.read_to_end(&mut buffer)?;
+    let buffer = vfs().read(shader_path)?;
 
     // check for null byte
     if buffer.iter().any(|i| *i == 0) {
@@ -0,0 +1,931 @@
+mod access;
+mod system_world;
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    app::{Controller, FrameResult, Renderer},
+    prelude::{qp_ecs::SystemStorages, Delta, QPError, World},
+    QPResult,
+};
+
+pub use access::SystemAccess;
+pub use system_world::{ParallelController, SystemWorld};
+
+/**
+ * The stages of a frame, in the order they run
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stage {
+    Input,
+    PreUpdate,
+    FixedUpdate,
+    Update,
+    PostUpdate,
+    Render,
+    Late,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 7] = [
+        Stage::Input,
+        Stage::PreUpdate,
+        Stage::FixedUpdate,
+        Stage::Update,
+        Stage::PostUpdate,
+        Stage::Render,
+        Stage::Late,
+    ];
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+type RunCondition = Box<dyn Fn(&World) -> bool>;
+type StartupSystem = Box<dyn FnMut(&mut World) -> QPResult<()>>;
+
+pub enum SystemKind {
+    Controller(Box<dyn Controller>),
+    // always on the main thread, they talk to GL
+    Renderer(Box<dyn Renderer>),
+    Parallel(Box<dyn ParallelController>, SystemAccess),
+}
+
+/**
+ * A controller or renderer along with how it should be scheduled.
+ * Returned by `App::add_controller` and `App::add_renderer` so
+ * constraints can be chained on.
+ */
+pub struct SystemDescriptor {
+    name: &'static str,
+    kind: SystemKind,
+
+    label: Option<String>,
+    before: Vec<String>,
+    after: Vec<String>,
+    conditions: Vec<RunCondition>,
+}
+
+impl SystemDescriptor {
+    fn new(name: &'static str, kind: SystemKind) -> Self {
+        Self {
+            name,
+            kind,
+            label: None,
+            before: vec![],
+            after: vec![],
+            conditions: vec![],
+        }
+    }
+
+    pub fn label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.label = Some(label.into());
+
+        self
+    }
+
+    /// run before the system labeled `label` in the same stage
+    pub fn before(&mut self, label: impl Into<String>) -> &mut Self {
+        self.before.push(label.into());
+
+        self
+    }
+
+    /// run after the system labeled `label` in the same stage
+    pub fn after(&mut self, label: impl Into<String>) -> &mut Self {
+        self.after.push(label.into());
+
+        self
+    }
+
+    /// only run when every condition returns true
+    pub fn run_if(&mut self, condition: impl Fn(&World) -> bool + 'static) -> &mut Self {
+        self.conditions.push(Box::new(condition));
+
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        match &self.label {
+            Some(label) => label,
+            None => self.name,
+        }
+    }
+
+    fn should_run(&self, world: &World) -> bool {
+        self.conditions.iter().all(|condition| condition(world))
+    }
+}
+
+pub struct StageResult {
+    pub frame_result: FrameResult,
+    pub draw_calls: u32,
+}
+
+/**
+ * Two parallel systems in the same stage that touch the same component or
+ * resource, with at least one of them writing it. They never run at the same time.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessConflict {
+    pub stage: Stage,
+    pub systems: (String, String),
+    pub item: &'static str,
+}
+
+pub struct Schedule {
+    stages: Vec<Vec<SystemDescriptor>>,
+    order: Vec<Vec<usize>>,
+    // predecessors[stage][i] are all the systems that must finish before i starts
+    predecessors: Vec<Vec<Vec<usize>>>,
+    conflicts: Vec<AccessConflict>,
+    built: bool,
+
+    startup: Vec<StartupSystem>,
+
+    fixed_timestep: f32,
+    accumulator: f32,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            stages: Stage::ALL.iter().map(|_| vec![]).collect(),
+            order: Stage::ALL.iter().map(|_| vec![]).collect(),
+            predecessors: Stage::ALL.iter().map(|_| vec![]).collect(),
+            conflicts: vec![],
+            built: false,
+
+            startup: vec![],
+
+            fixed_timestep: 1.0 / 60.0,
+            accumulator: 0.0,
+        }
+    }
+}
+
+impl Schedule {
+    // stops a slow frame from queueing up fixed updates forever
+    const MAX_FIXED_STEPS: u32 = 5;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_controller<C: Controller + 'static>(
+        &mut self,
+        stage: Stage,
+        controller: C,
+    ) -> &mut SystemDescriptor {
+        self.add(
+            stage,
+            SystemDescriptor::new(
+                std::any::type_name::<C>(),
+                SystemKind::Controller(Box::new(controller)),
+            ),
+        )
+    }
+
+    pub fn add_renderer<R: Renderer + 'static>(
+        &mut self,
+        stage: Stage,
+        renderer: R,
+    ) -> &mut SystemDescriptor {
+        self.add(
+            stage,
+            SystemDescriptor::new(
+                std::any::type_name::<R>(),
+                SystemKind::Renderer(Box::new(renderer)),
+            ),
+        )
+    }
+
+    /**
+     * adds a controller that may run on a worker thread, alongside
+     * other parallel controllers whose access doesn't conflict with it
+     */
+    pub fn add_parallel<P: ParallelController + 'static>(
+        &mut self,
+        stage: Stage,
+        controller: P,
+    ) -> &mut SystemDescriptor {
+        let access = controller.access();
+
+        self.add(
+            stage,
+            SystemDescriptor::new(
+                std::any::type_name::<P>(),
+                SystemKind::Parallel(Box::new(controller), access),
+            ),
+        )
+    }
+
+    /**
+     * adds a system that runs once before the first frame and again
+     * every time the world is restarted. Runs in registration order
+     */
+    pub fn add_startup(&mut self, system: impl FnMut(&mut World) -> QPResult<()> + 'static) {
+        self.startup.push(Box::new(system));
+    }
+
+    pub fn run_startup(&mut self, world: &mut World) -> QPResult<()> {
+        for system in self.startup.iter_mut() {
+            system(world)?;
+        }
+
+        world.flush();
+
+        Ok(())
+    }
+
+    /**
+     * resets the world, runs the startup systems again, then lets every
+     * controller rebuild its own state through `Controller::reset`
+     */
+    pub fn restart(&mut self, world: &mut World) -> QPResult<()> {
+        world.reset()?;
+        self.accumulator = 0.0;
+
+        self.run_startup(world)?;
+
+        for systems in self.stages.iter_mut() {
+            for system in systems.iter_mut() {
+                if let SystemKind::Controller(controller) = &mut system.kind {
+                    controller.reset(world)?;
+                }
+            }
+        }
+
+        world.flush();
+
+        Ok(())
+    }
+
+    /// parallel systems that can't run together. Filled in by `build`
+    pub fn conflicts(&self) -> &[AccessConflict] {
+        &self.conflicts
+    }
+
+    /// seconds between fixed updates. Defaults to 1/60
+    pub fn set_fixed_timestep(&mut self, seconds: f32) {
+        self.fixed_timestep = seconds;
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.iter().map(|systems| systems.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+     * sorts the systems in every stage by their before/after constraints.
+     * Systems without constraints keep their registration order.
+     * Fails on unknown labels and on cycles
+     */
+    pub fn build(&mut self) -> QPResult<()> {
+        self.conflicts.clear();
+
+        for stage in Stage::ALL {
+            let systems = &self.stages[stage.index()];
+            let (order, predecessors) = sort_stage(stage, systems)?;
+
+            self.order[stage.index()] = order;
+            self.predecessors[stage.index()] = predecessors;
+            self.conflicts.extend(find_conflicts(stage, systems));
+        }
+
+        #[cfg(debug_assertions)]
+        for conflict in self.conflicts.iter() {
+            println!(
+                "[scheduler] {} and {} both use {} in stage {:?}, they will run one after the other",
+                conflict.systems.0, conflict.systems.1, conflict.item, conflict.stage
+            );
+        }
+
+        self.built = true;
+
+        Ok(())
+    }
+
+    pub fn run_stage(&mut self, stage: Stage, world: &mut World) -> QPResult<StageResult> {
+        if !self.built {
+            self.build()?;
+        }
+
+        let mut result = StageResult {
+            frame_result: FrameResult::None,
+            draw_calls: 0,
+        };
+
+        let systems = &mut self.stages[stage.index()];
+        let predecessors = &self.predecessors[stage.index()];
+
+        // parallel systems are collected into a batch until one conflicts with
+        // it, depends on it, has a run condition, or a main thread system comes along
+        let mut batch: Vec<usize> = vec![];
+        for index in self.order[stage.index()].iter().copied() {
+            // a run condition can read anything, so it waits for the whole batch
+            let joins_batch = match &systems[index].kind {
+                SystemKind::Parallel(_, _) if !systems[index].conditions.is_empty() => false,
+                SystemKind::Parallel(_, access) => batch.iter().all(|other| {
+                    !predecessors[index].contains(other)
+                        && match &systems[*other].kind {
+                            SystemKind::Parallel(_, other) => access.conflict(other).is_none(),
+                            _ => false,
+                        }
+                }),
+                _ => false,
+            };
+
+            // so the run condition sees what every system before it did
+            if !joins_batch {
+                run_batch(systems, &batch, world, &mut result);
+                batch.clear();
+
+                if result.frame_result != FrameResult::None {
+                    return Ok(result);
+                }
+            }
+
+            if !systems[index].should_run(world) {
+                continue;
+            }
+
+            match &mut systems[index].kind {
+                SystemKind::Parallel(..) => batch.push(index),
+                SystemKind::Controller(controller) => {
+                    handle_frame_result(controller.update(world), &mut result)
+                }
+                SystemKind::Renderer(renderer) => {
+                    if let Some(draw_calls) = renderer.draw(world) {
+                        result.draw_calls += draw_calls;
+                    }
+                }
+            }
+
+            // the rest of the stage is skipped on quit or restart
+            if result.frame_result != FrameResult::None {
+                return Ok(result);
+            }
+        }
+
+        run_batch(systems, &batch, world, &mut result);
+
+        Ok(result)
+    }
+
+    /**
+     * runs the fixed update stage as many times as the frame's delta allows.
+     * `Delta` holds the fixed timestep while it runs
+     */
+    pub fn run_fixed_update(&mut self, world: &mut World) -> QPResult<StageResult> {
+        let delta = world.resource::<Delta>().0;
+        self.accumulator += delta;
+
+        let mut result = StageResult {
+            frame_result: FrameResult::None,
+            draw_calls: 0,
+        };
+
+        let mut steps = 0;
+        while self.accumulator >= self.fixed_timestep && steps < Self::MAX_FIXED_STEPS {
+            self.accumulator -= self.fixed_timestep;
+            steps += 1;
+
+            world.resource_mut::<Delta>().0 = self.fixed_timestep;
+            let step = self.run_stage(Stage::FixedUpdate, world);
+            world.resource_mut::<Delta>().0 = delta;
+
+            let step = step?;
+            result.draw_calls += step.draw_calls;
+            if step.frame_result != FrameResult::None {
+                result.frame_result = step.frame_result;
+
+                break;
+            }
+        }
+
+        if steps == Self::MAX_FIXED_STEPS {
+            self.accumulator = 0.0;
+        }
+
+        Ok(result)
+    }
+
+    fn add(&mut self, stage: Stage, system: SystemDescriptor) -> &mut SystemDescriptor {
+        self.built = false;
+
+        let systems = &mut self.stages[stage.index()];
+        systems.push(system);
+
+        systems.last_mut().unwrap()
+    }
+}
+
+fn handle_frame_result(frame_result: FrameResult, result: &mut StageResult) {
+    match frame_result {
+        FrameResult::Quit => result.frame_result = FrameResult::Quit,
+        // quitting wins over restarting
+        FrameResult::Restart if result.frame_result == FrameResult::None => {
+            result.frame_result = FrameResult::Restart
+        }
+        _ => (),
+    }
+}
+
+/**
+ * runs a batch of non-conflicting parallel systems, each on its own thread.
+ * A batch of one runs on the main thread
+ */
+fn run_batch(
+    systems: &mut [SystemDescriptor],
+    batch: &[usize],
+    world: &mut World,
+    result: &mut StageResult,
+) {
+    if batch.is_empty() || result.frame_result != FrameResult::None {
+        return;
+    }
+
+    let entity_manager = &mut world.registry.entity_manager;
+    let tick = entity_manager.advance_tick();
+    let since = entity_manager.frame_tick();
+    let storages = entity_manager.storage_ptrs();
+    let resources = world.resources.value_ptrs();
+
+    let mut jobs = vec![];
+    for (index, system) in systems.iter_mut().enumerate() {
+        if !batch.contains(&index) {
+            continue;
+        }
+
+        let name = system.name().to_string();
+        if let SystemKind::Parallel(controller, access) = &mut system.kind {
+            let system_storages = SystemStorages::new(&storages, &name, access.components());
+            let system_world =
+                SystemWorld::new(&name, access, system_storages, &resources, since, tick);
+
+            jobs.push((controller, system_world));
+        }
+    }
+
+    let frame_results: Vec<FrameResult> = if jobs.len() == 1 {
+        jobs.into_iter()
+            .map(|(controller, mut system_world)| controller.update(&mut system_world))
+            .collect()
+    } else {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = jobs
+                .into_iter()
+                .map(|(controller, mut system_world)| {
+                    scope.spawn(move || controller.update(&mut system_world))
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| match handle.join() {
+                    Ok(frame_result) => frame_result,
+                    Err(panic) => std::panic::resume_unwind(panic),
+                })
+                .collect()
+        })
+    };
+
+    for frame_result in frame_results {
+        handle_frame_result(frame_result, result);
+    }
+}
+
+fn find_conflicts(stage: Stage, systems: &[SystemDescriptor]) -> Vec<AccessConflict> {
+    let mut conflicts = vec![];
+
+    for (i, a) in systems.iter().enumerate() {
+        let SystemKind::Parallel(_, a_access) = &a.kind else {
+            continue;
+        };
+
+        for b in systems.iter().skip(i + 1) {
+            let SystemKind::Parallel(_, b_access) = &b.kind else {
+                continue;
+            };
+
+            if let Some(item) = a_access.conflict(b_access) {
+                conflicts.push(AccessConflict {
+                    stage,
+                    systems: (a.name().to_string(), b.name().to_string()),
+                    item,
+                });
+            }
+        }
+    }
+
+    conflicts
+}
+
+type StageOrder = (Vec<usize>, Vec<Vec<usize>>);
+
+fn sort_stage(stage: Stage, systems: &[SystemDescriptor]) -> QPResult<StageOrder> {
+    let mut labels = HashMap::<&str, usize>::new();
+    for (i, system) in systems.iter().enumerate() {
+        if let Some(label) = &system.label {
+            if labels.insert(label, i).is_some() {
+                return Err(QPError::DuplicateSystemLabel(label.clone()));
+            }
+        }
+    }
+
+    let find = |label: &String| -> QPResult<usize> {
+        labels
+            .get(label.as_str())
+            .copied()
+            .ok_or_else(|| QPError::UnknownSystemLabel(label.clone(), format!("{:?}", stage)))
+    };
+
+    // edges[a] contains b when a has to run before b
+    let mut edges = vec![Vec::<usize>::new(); systems.len()];
+    let mut predecessors = vec![Vec::<usize>::new(); systems.len()];
+    let mut in_degree = vec![0; systems.len()];
+    for (i, system) in systems.iter().enumerate() {
+        for label in system.before.iter() {
+            let other = find(label)?;
+            edges[i].push(other);
+            predecessors[other].push(i);
+            in_degree[other] += 1;
+        }
+        for label in system.after.iter() {
+            let other = find(label)?;
+            edges[other].push(i);
+            predecessors[i].push(other);
+            in_degree[i] += 1;
+        }
+    }
+
+    let mut ready: BTreeSet<usize> = (0..systems.len()).filter(|i| in_degree[*i] == 0).collect();
+    let mut order = Vec::with_capacity(systems.len());
+    while let Some(i) = ready.pop_first() {
+        order.push(i);
+
+        for next in edges[i].iter() {
+            in_degree[*next] -= 1;
+            if in_degree[*next] == 0 {
+                ready.insert(*next);
+            }
+        }
+    }
+
+    if order.len() < systems.len() {
+        let cycle = (0..systems.len())
+            .filter(|i| in_degree[*i] > 0)
+            .map(|i| systems[i].name().to_string())
+            .collect::<Vec<_>>();
+
+        return Err(QPError::SystemOrderCycle(
+            format!("{:?}", stage),
+            cycle.join(", "),
+        ));
+    }
+
+    // a system waits on everything upstream of it, not only its direct constraints,
+    // so skipping a system in the middle of a chain doesn't let the ends overlap
+    for i in order.iter().copied() {
+        let mut upstream = predecessors[i].clone();
+        for p in predecessors[i].iter() {
+            upstream.extend(predecessors[*p].iter().copied());
+        }
+        upstream.sort_unstable();
+        upstream.dedup();
+
+        predecessors[i] = upstream;
+    }
+
+    Ok((order, predecessors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_ecs::{Component, EntityBuilder},
+        qp_gfx::Viewport,
+        ResetMode, TextBuffer,
+    };
+
+    struct Noop;
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Position(f32);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Velocity(f32);
+
+    struct Movement;
+
+    impl ParallelController for Movement {
+        fn access(&self) -> SystemAccess {
+            SystemAccess::new()
+                .write::<Position>()
+                .read::<Velocity>()
+                .read_resource::<Delta>()
+        }
+
+        fn update(&mut self, world: &mut SystemWorld) -> FrameResult {
+            let delta = world.resource::<Delta>().0;
+            for (_, position, velocity) in world.query::<(&mut Position, &Velocity)>() {
+                position.0 += velocity.0 * delta;
+            }
+
+            FrameResult::None
+        }
+    }
+
+    struct Accelerate;
+
+    impl ParallelController for Accelerate {
+        fn access(&self) -> SystemAccess {
+            SystemAccess::new().write::<Velocity>()
+        }
+
+        fn update(&mut self, world: &mut SystemWorld) -> FrameResult {
+            for (_, velocity) in world.query::<(&mut Velocity,)>() {
+                velocity.0 += 1.0;
+            }
+
+            FrameResult::None
+        }
+    }
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Frames(u32);
+
+    struct CountFrames;
+
+    impl ParallelController for CountFrames {
+        fn access(&self) -> SystemAccess {
+            SystemAccess::new().write_resource::<Frames>()
+        }
+
+        fn update(&mut self, world: &mut SystemWorld) -> FrameResult {
+            world.resource_mut::<Frames>().0 += 1;
+
+            FrameResult::None
+        }
+    }
+
+    struct Sneaky;
+
+    impl ParallelController for Sneaky {
+        fn access(&self) -> SystemAccess {
+            SystemAccess::new().read::<Velocity>()
+        }
+
+        fn update(&mut self, world: &mut SystemWorld) -> FrameResult {
+            world.query::<(&mut Position,)>().for_each(drop);
+
+            FrameResult::None
+        }
+    }
+
+    fn test_world() -> World {
+        let mut world = World::new(Viewport::default(), 1234).unwrap();
+        world
+            .registry
+            .entity_manager
+            .register_component::<Position>()
+            .register_component::<Velocity>();
+        world.resource_mut::<Delta>().0 = 0.5;
+        world.insert_resource(Frames::default());
+
+        world
+    }
+
+    impl Controller for Noop {
+        fn update(&mut self, _world: &mut World) -> FrameResult {
+            FrameResult::None
+        }
+    }
+
+    fn names(schedule: &Schedule, stage: Stage) -> Vec<&str> {
+        schedule.order[stage.index()]
+            .iter()
+            .map(|i| schedule.stages[stage.index()][*i].name())
+            .collect()
+    }
+
+    #[test]
+    fn schedule_orders_by_constraints() {
+        let mut schedule = Schedule::new();
+        schedule
+            .add_controller(Stage::Update, Noop)
+            .label("render_prep")
+            .after("physics");
+        schedule.add_controller(Stage::Update, Noop).label("input");
+        schedule
+            .add_controller(Stage::Update, Noop)
+            .label("physics")
+            .after("input");
+        schedule.add_controller(Stage::Update, Noop).label("audio");
+        schedule.add_controller(Stage::Late, Noop).label("cleanup");
+
+        schedule.build().unwrap();
+
+        assert_eq!(
+            names(&schedule, Stage::Update),
+            vec!["input", "physics", "render_prep", "audio"]
+        );
+        assert_eq!(names(&schedule, Stage::Late), vec!["cleanup"]);
+    }
+
+    #[test]
+    fn schedule_reports_cycles_and_unknown_labels() {
+        let mut schedule = Schedule::new();
+        schedule
+            .add_controller(Stage::Update, Noop)
+            .label("a")
+            .before("b");
+        schedule
+            .add_controller(Stage::Update, Noop)
+            .label("b")
+            .before("a");
+
+        assert!(matches!(
+            schedule.build(),
+            Err(QPError::SystemOrderCycle(_, cycle)) if cycle == "a, b"
+        ));
+
+        let mut schedule = Schedule::new();
+        schedule.add_controller(Stage::Update, Noop).label("a");
+        schedule.add_controller(Stage::PostUpdate, Noop).after("a");
+
+        assert!(matches!(
+            schedule.build(),
+            Err(QPError::UnknownSystemLabel(label, _)) if label == "a"
+        ));
+    }
+
+    #[test]
+    fn schedule_runs_parallel_systems() {
+        let mut world = test_world();
+        let entity = EntityBuilder::create(&mut world.registry.entity_manager)
+            .with(Position(0.0))
+            .with(Velocity(2.0))
+            .build();
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_parallel(Stage::Update, Movement)
+            .label("movement");
+        schedule
+            .add_parallel(Stage::Update, Accelerate)
+            .after("movement");
+        // conflicts with neither, so it runs on another thread next to accelerate
+        schedule.add_parallel(Stage::Update, CountFrames);
+        schedule.build().unwrap();
+
+        assert_eq!(schedule.conflicts().len(), 1);
+        assert_eq!(
+            schedule.conflicts()[0].item,
+            std::any::type_name::<Velocity>()
+        );
+
+        schedule.run_stage(Stage::Update, &mut world).unwrap();
+
+        let entity_manager = &world.registry.entity_manager;
+        assert_eq!(
+            entity_manager.get::<Position>(&entity),
+            Some(&Position(1.0))
+        );
+        assert_eq!(
+            entity_manager.get::<Velocity>(&entity),
+            Some(&Velocity(3.0))
+        );
+        assert_eq!(world.resource::<Frames>(), &Frames(1));
+    }
+
+    #[test]
+    fn schedule_conditions_see_earlier_parallel_systems() {
+        let mut world = test_world();
+
+        let mut schedule = Schedule::new();
+        schedule
+            .add_parallel(Stage::Update, CountFrames)
+            .label("count");
+        // conflicts with count, so it waits for it, and so does its condition
+        schedule
+            .add_parallel(Stage::Update, CountFrames)
+            .after("count")
+            .run_if(|world| world.resource::<Frames>().0 == 1);
+        schedule.run_stage(Stage::Update, &mut world).unwrap();
+
+        assert_eq!(world.resource::<Frames>(), &Frames(2));
+
+        // doesn't conflict with count or wait for it, but its condition still sees it
+        let entity = EntityBuilder::create(&mut world.registry.entity_manager)
+            .with(Velocity(2.0))
+            .build();
+        let mut schedule = Schedule::new();
+        schedule.add_parallel(Stage::Update, CountFrames);
+        schedule
+            .add_parallel(Stage::Update, Accelerate)
+            .run_if(|world| world.resource::<Frames>().0 == 3);
+        schedule.run_stage(Stage::Update, &mut world).unwrap();
+
+        assert_eq!(
+            world.registry.entity_manager.get::<Velocity>(&entity),
+            Some(&Velocity(3.0))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "without declaring it")]
+    fn schedule_rejects_undeclared_access() {
+        let mut world = test_world();
+
+        let mut schedule = Schedule::new();
+        schedule.add_parallel(Stage::Update, Sneaky);
+        schedule.run_stage(Stage::Update, &mut world).unwrap();
+    }
+
+    // stands in for an asset that owns a GL object
+    #[derive(Component, Debug, PartialEq)]
+    struct Buffer;
+
+    static LIVE_BUFFERS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    impl Buffer {
+        fn new() -> Self {
+            LIVE_BUFFERS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Buffer
+        }
+    }
+
+    impl Drop for Buffer {
+        fn drop(&mut self) {
+            LIVE_BUFFERS.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    /// restarts every frame, counting the restarts that found the startup entities
+    struct Restarter;
+
+    impl Controller for Restarter {
+        fn update(&mut self, world: &mut World) -> FrameResult {
+            let entity = world.commands.spawn();
+            world.commands.add(entity, Velocity(1.0));
+
+            FrameResult::Restart
+        }
+
+        fn reset(&mut self, world: &mut World) -> QPResult<()> {
+            if world.registry.entity_manager.query_all::<Position>().len() == 3 {
+                world.resource_mut::<Frames>().0 += 1;
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn schedule_restarts_without_leaking() {
+        let mut world = test_world();
+        world.reset_mode = ResetMode::ReloadAssets;
+        world.registry.asset_manager.register_asset::<Buffer>();
+
+        let mut schedule = Schedule::new();
+        schedule.add_startup(|world| {
+            for i in 0..3 {
+                let entity = world.registry.entity_manager.create();
+                world.registry.entity_manager.add(&entity, Position(i as f32));
+            }
+            // unloaded once nothing holds a handle
+            let buffer = world.registry.asset_manager.load_asset("buffer", Buffer::new())?;
+            world.insert_resource(buffer);
+
+            Ok(())
+        });
+        schedule.add_controller(Stage::Update, Restarter);
+        schedule.run_startup(&mut world).unwrap();
+
+        for _ in 0..10 {
+            let result = schedule.run_stage(Stage::Update, &mut world).unwrap();
+            assert_eq!(result.frame_result, FrameResult::Restart);
+
+            world.resource_mut::<TextBuffer>().push(Default::default());
+            schedule.restart(&mut world).unwrap();
+
+            let entity_manager = &world.registry.entity_manager;
+            assert_eq!(entity_manager.count(), 3);
+            assert_eq!(entity_manager.allocator_size(), 3);
+            assert!(entity_manager.query_all::<Velocity>().is_empty());
+            assert!(world.commands.is_empty());
+            assert!(world.resource::<TextBuffer>().is_empty());
+            assert_eq!(world.registry.asset_manager.len(), 1);
+            assert_eq!(LIVE_BUFFERS.load(std::sync::atomic::Ordering::SeqCst), 1);
+        }
+
+        assert_eq!(world.resource::<Frames>(), &Frames(10));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::prelude::qp_ecs::{components::CDisabled, Component, ComponentAccess};
+
+/**
+ * The components and resources a parallel system reads and writes.
+ * Systems whose access doesn't conflict may run at the same time.
+ */
+#[derive(Debug, Clone)]
+pub struct SystemAccess {
+    components: Vec<ComponentAccess>,
+    resources: Vec<ComponentAccess>,
+}
+
+impl Default for SystemAccess {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemAccess {
+    /// every system reads CDisabled, its queries skip disabled entities
+    pub fn new() -> Self {
+        Self {
+            components: vec![ComponentAccess::of::<CDisabled>(false)],
+            resources: vec![],
+        }
+    }
+
+    pub fn read<C: Component + Send + Sync + 'static>(mut self) -> Self {
+        self.components.push(ComponentAccess::of::<C>(false));
+
+        self
+    }
+
+    pub fn write<C: Component + Send + Sync + 'static>(mut self) -> Self {
+        self.components.push(ComponentAccess::of::<C>(true));
+
+        self
+    }
+
+    pub fn read_resource<R: Send + Sync + 'static>(mut self) -> Self {
+        self.resources.push(ComponentAccess::of::<R>(false));
+
+        self
+    }
+
+    pub fn write_resource<R: Send + Sync + 'static>(mut self) -> Self {
+        self.resources.push(ComponentAccess::of::<R>(true));
+
+        self
+    }
+
+    pub fn components(&self) -> &[ComponentAccess] {
+        &self.components
+    }
+
+    pub fn resources(&self) -> &[ComponentAccess] {
+        &self.resources
+    }
+
+    pub fn allows_resource(&self, access: &ComponentAccess) -> bool {
+        allows(&self.resources, access)
+    }
+
+    /// the name of the first component or resource both systems touch while one of them writes it
+    pub fn conflict(&self, other: &SystemAccess) -> Option<&'static str> {
+        conflict(&self.components, &other.components)
+            .or_else(|| conflict(&self.resources, &other.resources))
+    }
+}
+
+fn allows(declared: &[ComponentAccess], access: &ComponentAccess) -> bool {
+    declared
+        .iter()
+        .any(|a| a.type_id == access.type_id && (a.mutable || !access.mutable))
+}
+
+fn conflict(a: &[ComponentAccess], b: &[ComponentAccess]) -> Option<&'static str> {
+    a.iter()
+        .find(|a| {
+            b.iter()
+                .any(|b| a.type_id == b.type_id && (a.mutable || b.mutable))
+        })
+        .map(|a| a.name)
+}
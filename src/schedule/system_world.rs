@@ -0,0 +1,138 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+use crate::{
+    app::FrameResult,
+    prelude::qp_ecs::{
+        Component, ComponentAccess, Query, QueryFilter, QueryIter, SparseSet, StorageSource,
+        SystemStorages, VersionedIndex,
+    },
+};
+
+use super::access::SystemAccess;
+
+/**
+ * A controller that declares its access up front so the scheduler can
+ * run it on a worker thread alongside other systems it doesn't conflict with.
+ *
+ * Structural changes (spawning, despawning, adding and removing components)
+ * aren't available from here; use a regular controller and `world.commands` for those.
+ */
+pub trait ParallelController: Send {
+    fn access(&self) -> SystemAccess;
+
+    fn update(&mut self, world: &mut SystemWorld) -> FrameResult;
+}
+
+/**
+ * The part of the world a parallel system declared it would use.
+ * Reaching for anything else panics.
+ */
+pub struct SystemWorld {
+    name: String,
+    access: SystemAccess,
+
+    storages: SystemStorages,
+    resources: HashMap<TypeId, *mut dyn Any>,
+
+    since: u64,
+    tick: u64,
+}
+
+// SAFETY: the scheduler only runs systems together when their declared access
+// doesn't conflict, declared types are Send + Sync, and every lookup is checked
+// against the declaration
+unsafe impl Send for SystemWorld {}
+
+impl SystemWorld {
+    pub(super) fn new(
+        name: &str,
+        access: &SystemAccess,
+        storages: SystemStorages,
+        resources: &HashMap<TypeId, *mut dyn Any>,
+        since: u64,
+        tick: u64,
+    ) -> Self {
+        let resources = access
+            .resources()
+            .iter()
+            .filter_map(|a| resources.get(&a.type_id).map(|ptr| (a.type_id, *ptr)))
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            access: access.clone(),
+            storages,
+            resources,
+            since,
+            tick,
+        }
+    }
+
+    pub fn query<Q: Query>(&mut self) -> QueryIter<'_, Q> {
+        QueryIter::new(&mut self.storages, self.since, self.tick)
+    }
+
+    pub fn query_filtered<Q: Query, F: QueryFilter>(&mut self) -> QueryIter<'_, Q, F> {
+        QueryIter::new(&mut self.storages, self.since, self.tick)
+    }
+
+    pub fn get<C: Component + 'static>(&mut self, entity: &VersionedIndex) -> Option<&C> {
+        if !self.storages.allocator().validate(entity) {
+            return None;
+        }
+
+        let storage = self.storage::<C>(false)?;
+
+        unsafe { (*storage).get(entity) }
+    }
+
+    pub fn get_mut<C: Component + 'static>(&mut self, entity: &VersionedIndex) -> Option<&mut C> {
+        if !self.storages.allocator().validate(entity) {
+            return None;
+        }
+
+        let storage = self.storage::<C>(true)?;
+
+        unsafe { (*storage).get_mut_at_tick(entity, self.tick) }
+    }
+
+    pub fn resource<R: 'static>(&self) -> &R {
+        let ptr = self.resource_ptr::<R>(false);
+
+        unsafe { &*ptr }
+    }
+
+    pub fn resource_mut<R: 'static>(&mut self) -> &mut R {
+        let ptr = self.resource_ptr::<R>(true);
+
+        unsafe { &mut *ptr }
+    }
+
+    fn storage<C: Component + 'static>(&mut self, mutable: bool) -> Option<*mut SparseSet<C>> {
+        self.storages
+            .check_access(&[ComponentAccess::of::<C>(mutable)]);
+
+        self.storages.storage_ptr::<C>()
+    }
+
+    fn resource_ptr<R: 'static>(&self, mutable: bool) -> *mut R {
+        let access = ComponentAccess::of::<R>(mutable);
+        if !self.access.allows_resource(&access) {
+            panic!(
+                "[scheduler] system {} accessed resource {} ({}) without declaring it",
+                self.name,
+                access.name,
+                if mutable { "write" } else { "read" }
+            );
+        }
+
+        match self.resources.get(&access.type_id) {
+            // the value under TypeId::of::<R>() is always an R
+            Some(ptr) => *ptr as *mut R,
+            None => panic!("[world] resource {} does not exist", access.name),
+        }
+    }
+}
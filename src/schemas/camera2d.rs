@@ -2,7 +2,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::prelude::qp_assets::camera::OrthographicCameraParams;
 use crate::prelude::Schema;
-use crate::prelude::{qp_assets::RCamera2D, qp_ecs::components::CTransform2D, GlobalRegistry};
+use crate::prelude::{
+    qp_assets::{Handle, RCamera2D, UntypedHandle},
+    qp_ecs::components::CTransform2D,
+    GlobalRegistry,
+};
 use crate::QPResult;
 
 pub const DEFAULT_CAMERA: &str = "default_camera";
@@ -35,15 +39,12 @@ impl Default for SchemaCamera2D {
 }
 
 impl Schema for SchemaCamera2D {
-    fn load_resource(&self, registry: &mut GlobalRegistry) -> QPResult<u64> {
-        Ok(registry.asset_manager.load_asset(
-            &self.name,
-            RCamera2D::new(self.params(), 1.0, self.transform),
-        )?)
+    fn load_resource(&self, registry: &mut GlobalRegistry) -> QPResult<UntypedHandle> {
+        Ok(self.load(registry)?.into())
     }
 
     fn from_resource(id: u64, registry: &GlobalRegistry) -> Option<Self> {
-        if let Some(camera) = registry.asset_manager.get::<RCamera2D>(id) {
+        if let Some(camera) = registry.asset_manager.get_by_id::<RCamera2D>(id) {
             let schema = Self {
                 name: registry.strings().get_string(id)?,
                 transform: camera.transform,
@@ -63,6 +64,13 @@ impl Schema for SchemaCamera2D {
 }
 
 impl SchemaCamera2D {
+    pub fn load(&self, registry: &mut GlobalRegistry) -> QPResult<Handle<RCamera2D>> {
+        registry.asset_manager.load_asset(
+            &self.name,
+            RCamera2D::new(self.params(), 1.0, self.transform),
+        )
+    }
+
     fn params(&self) -> OrthographicCameraParams {
         OrthographicCameraParams {
             left: self.left,
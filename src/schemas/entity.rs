@@ -0,0 +1,215 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::{qp_ecs::Value, GlobalRegistry, QPError, Schema, VersionedIndex};
+use crate::QPResult;
+
+/**
+ * An entity and every component on it, keyed by the registered
+ * component name
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaEntity {
+    /// the entity this was saved from. Entity references inside components point at these
+    pub id: VersionedIndex,
+    pub components: BTreeMap<String, Value>,
+}
+
+impl Schema for SchemaEntity {
+    /// builds a lone entity, use `build_entities` to keep references between entities
+    fn build_entity(&self, registry: &mut GlobalRegistry) -> QPResult<VersionedIndex> {
+        let entities = build_entities(std::slice::from_ref(self), registry)?;
+
+        Ok(entities[0])
+    }
+
+    /// None if the entity is gone or has a component that couldn't be loaded back, see `save`
+    fn from_entity(entity: VersionedIndex, registry: &GlobalRegistry) -> Option<Self> {
+        Self::save(entity, registry).ok()
+    }
+}
+
+impl SchemaEntity {
+    /// fails on a component that can't be built from its saved value, instead of leaving it out
+    pub fn save(entity: VersionedIndex, registry: &GlobalRegistry) -> QPResult<Self> {
+        let entity_manager = &registry.entity_manager;
+        if !entity_manager.is_valid(&entity) {
+            return Err(QPError::ProblemCreatingEntity);
+        }
+
+        let mut components = BTreeMap::new();
+        for component in entity_manager.reflect_components(&entity) {
+            let name = component.type_name();
+            let registration = entity_manager
+                .types()
+                .get(name)
+                .ok_or_else(|| QPError::UnknownComponent(name.to_string()))?;
+            if !registration.can_build() {
+                return Err(QPError::ComponentNotConstructible(name.to_string()));
+            }
+
+            components.insert(name.to_string(), component.to_value());
+        }
+
+        Ok(Self {
+            id: entity,
+            components,
+        })
+    }
+}
+
+/**
+ * Creates every entity first so the references between them can be
+ * remapped to the new entities. A component that points at an entity
+ * outside of `schemas` is skipped
+ */
+pub fn build_entities(
+    schemas: &[SchemaEntity],
+    registry: &mut GlobalRegistry,
+) -> QPResult<Vec<VersionedIndex>> {
+    let entity_manager = &mut registry.entity_manager;
+    let entities: Vec<VersionedIndex> = schemas.iter().map(|_| entity_manager.create()).collect();
+
+    add_components(schemas, &entities, registry)?;
+
+    Ok(entities)
+}
+
+/**
+ * Replaces every entity in the registry with the saved ones, keeping
+ * their ids so entities held outside of the registry stay valid
+ */
+pub fn restore_entities(schemas: &[SchemaEntity], registry: &mut GlobalRegistry) -> QPResult<()> {
+    let entities: Vec<VersionedIndex> = schemas.iter().map(|schema| schema.id).collect();
+    registry.entity_manager.restore_entities(&entities)?;
+
+    add_components(schemas, &entities, registry)
+}
+
+fn add_components(
+    schemas: &[SchemaEntity],
+    entities: &[VersionedIndex],
+    registry: &mut GlobalRegistry,
+) -> QPResult<()> {
+    let _handles = registry.asset_manager.bind_handles();
+    let entity_manager = &mut registry.entity_manager;
+
+    let remap: HashMap<(usize, u64), VersionedIndex> = schemas
+        .iter()
+        .zip(entities.iter())
+        .map(|(schema, entity)| ((schema.id.index(), schema.id.version()), *entity))
+        .collect();
+
+    for (schema, entity) in schemas.iter().zip(entities.iter()) {
+        for (name, value) in schema.components.iter() {
+            let mut value = value.clone();
+            let resolved = value.map_entities(&mut |saved| {
+                remap.get(&(saved.index(), saved.version())).copied()
+            });
+
+            if !resolved {
+                #[cfg(debug_assertions)]
+                println!(
+                    "[scene] skipping {} on entity {}, it refers to an entity that wasn't saved",
+                    name, schema.id
+                );
+
+                continue;
+            }
+
+            entity_manager.build_by_name(entity, name, value)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::qp_ecs::{
+        components::{register_components, CChildren, CParent, CTag, CTransform2D},
+        Component,
+    };
+
+    #[derive(Component, Debug, PartialEq, Default)]
+    struct Target {
+        entity: VersionedIndex,
+        weight: u8,
+    }
+
+    fn registry() -> GlobalRegistry {
+        let mut registry = GlobalRegistry::init().unwrap();
+        register_components(&mut registry);
+        registry.entity_manager.register_default_component::<Target>();
+
+        registry
+    }
+
+    #[test]
+    fn scene_entities_round_trip() {
+        // shift the indices so the saved entities don't line up with the loaded ones
+        let mut old = registry_with_gaps();
+        let ship = old.entity_manager.create();
+        let turret = old.entity_manager.create();
+        old.entity_manager.add(&ship, CTag { tag: "ship".into() });
+        old.entity_manager.add(
+            &ship,
+            CTransform2D {
+                translate: glm::vec2(3.0, 4.0),
+                ..CTransform2D::default()
+            },
+        );
+        old.entity_manager.set_parent(&turret, &ship);
+        old.entity_manager.add(
+            &turret,
+            Target {
+                entity: ship,
+                weight: 7,
+            },
+        );
+
+        let schemas: Vec<SchemaEntity> = [ship, turret]
+            .iter()
+            .filter_map(|entity| SchemaEntity::from_entity(*entity, &old))
+            .collect();
+        let yaml = serde_yaml::to_string(&schemas).unwrap();
+        let loaded: Vec<SchemaEntity> = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(serde_yaml::to_string(&loaded).unwrap(), yaml);
+
+        let mut registry = registry();
+        let entities = build_entities(&loaded, &mut registry).unwrap();
+        let (ship, turret) = (entities[0], entities[1]);
+        let entity_manager = &registry.entity_manager;
+
+        assert_eq!(entity_manager.get::<CTag>(&ship).unwrap().tag, "ship");
+        assert_eq!(
+            entity_manager.get::<CTransform2D>(&ship).unwrap().translate,
+            glm::vec2(3.0, 4.0)
+        );
+        assert_eq!(entity_manager.get::<CParent>(&turret), Some(&CParent(ship)));
+        assert_eq!(entity_manager.get::<CChildren>(&ship).unwrap().list, [turret]);
+        assert_eq!(
+            entity_manager.get::<Target>(&turret),
+            Some(&Target {
+                entity: ship,
+                weight: 7
+            })
+        );
+
+        // references to entities that weren't saved are dropped
+        let lone = build_entities(&loaded[1..], &mut registry).unwrap()[0];
+        assert!(registry.entity_manager.get::<Target>(&lone).is_none());
+        assert!(registry.entity_manager.get::<CParent>(&lone).is_none());
+    }
+
+    fn registry_with_gaps() -> GlobalRegistry {
+        let mut registry = registry();
+        for _ in 0..3 {
+            registry.entity_manager.create();
+        }
+
+        registry
+    }
+}
@@ -1,4 +1,6 @@
 pub mod camera2d;
+pub mod entity;
+pub mod prefab;
 pub mod scene;
 pub mod scene2d;
 pub mod shader;
@@ -6,11 +8,23 @@ pub mod sprite;
 pub mod texture;
 
 pub mod prelude {
-    use crate::{prelude::VersionedIndex, registry::GlobalRegistry, QPResult};
+    use crate::{
+        prelude::{qp_assets::UntypedHandle, VersionedIndex},
+        registry::GlobalRegistry,
+        QPResult,
+    };
 
     use super::*;
 
     pub use camera2d::SchemaCamera2D;
+    pub use entity::build_entities;
+    pub use entity::restore_entities;
+    pub use entity::SchemaEntity;
+    pub use prefab::instantiate;
+    pub use prefab::instantiate_batch;
+    pub use prefab::load_prefab;
+    pub use prefab::SchemaPrefab;
+    pub use prefab::SchemaPrefabInstance;
     pub use scene2d::SchemaScene2D;
     pub use shader::SchemaShader;
     pub use sprite::SchemaSprite;
@@ -27,7 +41,8 @@ pub mod prelude {
             unimplemented!()
         }
 
-        fn load_resource(&self, _registry: &mut GlobalRegistry) -> QPResult<u64> {
+        /// the asset stays loaded while the returned handle lives
+        fn load_resource(&self, _registry: &mut GlobalRegistry) -> QPResult<UntypedHandle> {
             unimplemented!()
         }
 
@@ -0,0 +1,300 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::{
+    qp_assets::{PrefabEntity, RPrefab, UntypedHandle},
+    qp_core::vfs,
+    qp_ecs::Value,
+    GlobalRegistry, QPError, Schema, VersionedIndex,
+};
+use crate::QPResult;
+
+/**
+ * PREFAB FILE, prefabs/<name>.yaml
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaPrefab {
+    pub name: String,
+    #[serde(flatten)]
+    pub root: PrefabEntity,
+}
+
+impl Schema for SchemaPrefab {
+    fn load_resource(&self, registry: &mut GlobalRegistry) -> QPResult<UntypedHandle> {
+        Ok(registry
+            .asset_manager
+            .load_asset(&self.name, RPrefab::new(self.root.clone()))?
+            .into())
+    }
+
+    fn from_resource(id: u64, registry: &GlobalRegistry) -> Option<Self> {
+        let prefab = registry.asset_manager.get_by_id::<RPrefab>(id)?;
+
+        Some(Self {
+            name: registry.strings().get_string(id)?,
+            root: prefab.root.clone(),
+        })
+    }
+}
+
+pub fn load_prefab(name: &str) -> QPResult<SchemaPrefab> {
+    let Ok(file) = vfs().read(&format!("prefabs/{}.yaml", name)) else {
+        return Err(QPError::PrefabNotFound(name.to_string()));
+    };
+
+    serde_yaml::from_slice(&file).map_err(|e| QPError::Generic(e.to_string()))
+}
+
+/**
+ * A prefab placed in a scene
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaPrefabInstance {
+    pub prefab: String,
+    #[serde(default)]
+    pub overrides: BTreeMap<String, Value>,
+}
+
+impl Schema for SchemaPrefabInstance {
+    fn build_entity(&self, registry: &mut GlobalRegistry) -> QPResult<VersionedIndex> {
+        let instance = PrefabEntity {
+            overrides: self.overrides.clone(),
+            ..PrefabEntity::instance_of(&self.prefab)
+        };
+
+        build_prefab_entity(&instance, registry, &mut vec![])
+    }
+}
+
+/**
+ * Spawns the prefab and its children, then applies the overrides to the
+ * root, eg `("CTransform2D.translate", Value::Vec2(position))`. Prefabs
+ * that aren't loaded yet are read from prefabs/
+ */
+pub fn instantiate(
+    registry: &mut GlobalRegistry,
+    prefab: &str,
+    overrides: &[(&str, Value)],
+) -> QPResult<VersionedIndex> {
+    let instance = PrefabEntity {
+        overrides: overrides
+            .iter()
+            .map(|(path, value)| (path.to_string(), value.clone()))
+            .collect(),
+        ..PrefabEntity::instance_of(prefab)
+    };
+
+    build_prefab_entity(&instance, registry, &mut vec![])
+}
+
+/// spawns `count` instances of the prefab, reading it only once
+pub fn instantiate_batch(
+    registry: &mut GlobalRegistry,
+    prefab: &str,
+    count: usize,
+    overrides: &[(&str, Value)],
+) -> QPResult<Vec<VersionedIndex>> {
+    let mut root = prefab_root(prefab, registry)?;
+    for (path, value) in overrides.iter() {
+        root.overrides.insert(path.to_string(), value.clone());
+    }
+
+    let mut building = vec![prefab.to_string()];
+    (0..count)
+        .map(|_| build_prefab_entity(&root, registry, &mut building))
+        .collect()
+}
+
+fn build_prefab_entity(
+    node: &PrefabEntity,
+    registry: &mut GlobalRegistry,
+    // the prefabs being built, to catch one that contains itself
+    building: &mut Vec<String>,
+) -> QPResult<VersionedIndex> {
+    let _handles = registry.asset_manager.bind_handles();
+    let entity = match &node.prefab {
+        Some(name) => {
+            if building.contains(name) {
+                return Err(QPError::PrefabCycle(name.clone()));
+            }
+
+            let root = prefab_root(name, registry)?;
+
+            building.push(name.clone());
+            let entity = build_prefab_entity(&root, registry, building)?;
+            building.pop();
+
+            entity
+        }
+        None => registry.entity_manager.create(),
+    };
+
+    // don't leave a half built tree behind
+    if let Err(e) = build_prefab_parts(node, entity, registry, building) {
+        registry.entity_manager.set_to_delete_recursive(entity);
+
+        return Err(e);
+    }
+
+    Ok(entity)
+}
+
+// the components, overrides and children of an entity that was just created
+fn build_prefab_parts(
+    node: &PrefabEntity,
+    entity: VersionedIndex,
+    registry: &mut GlobalRegistry,
+    building: &mut Vec<String>,
+) -> QPResult<()> {
+    let entity_manager = &mut registry.entity_manager;
+    for (name, value) in node.components.iter() {
+        entity_manager.build_by_name(&entity, name, value.clone())?;
+    }
+
+    for (path, value) in node.overrides.iter() {
+        let (name, path) = path.split_once('.').unwrap_or((path, ""));
+        let component = match entity_manager.reflect_component_mut(&entity, name) {
+            Some(component) => component,
+            None => entity_manager.add_by_name(&entity, name)?,
+        };
+
+        component.set_path(path, value.clone())?;
+    }
+
+    for child in node.children.iter() {
+        let child = build_prefab_entity(child, registry, building)?;
+        registry.entity_manager.set_parent(&child, &entity);
+    }
+
+    Ok(())
+}
+
+// loaded under the name it's instantiated by, which is also its file name
+fn prefab_root(name: &str, registry: &mut GlobalRegistry) -> QPResult<PrefabEntity> {
+    let prefab = match registry.asset_manager.handle::<RPrefab>(name) {
+        Some(prefab) => prefab,
+        None => {
+            let schema = load_prefab(name)?;
+            registry
+                .asset_manager
+                .load_asset(name, RPrefab::new(schema.root))?
+        }
+    };
+    registry.asset_manager.keep_prefab(name, prefab.clone());
+
+    registry
+        .asset_manager
+        .get(&prefab)
+        .map(|prefab| prefab.root.clone())
+        .ok_or_else(|| QPError::PrefabNotFound(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::qp_ecs::components::{
+        register_components, CQuad, CTag, CTransform2D, CVelocity2D,
+    };
+
+    fn registry() -> GlobalRegistry {
+        let mut registry = GlobalRegistry::init().unwrap();
+        register_components(&mut registry);
+
+        registry
+    }
+
+    #[test]
+    fn prefab_instances_and_overrides() {
+        let mut registry = registry();
+
+        let thruster = PrefabEntity::new()
+            .with(CTag {
+                tag: "thruster".into(),
+            })
+            .with(CTransform2D::default());
+        let ship = PrefabEntity::new()
+            .with(CTag { tag: "ship".into() })
+            .with(CQuad::default())
+            .with(CTransform2D::default())
+            .with_child(
+                PrefabEntity::instance_of("thruster")
+                    .with_override("CTransform2D.translate.y", Value::Float(-26.0)),
+            );
+
+        let yaml = "name: ship\n".to_string() + &serde_yaml::to_string(&ship).unwrap();
+        let schema: SchemaPrefab = serde_yaml::from_str(&yaml).unwrap();
+        let ship = schema.load_resource(&mut registry).unwrap();
+        let thruster = registry
+            .asset_manager
+            .load_asset("thruster", RPrefab::new(thruster))
+            .unwrap();
+
+        let entity = instantiate(
+            &mut registry,
+            "ship",
+            &[
+                ("CTransform2D.translate", Value::Vec2(glm::vec2(5.0, 6.0))),
+                ("CVelocity2D.x", Value::Float(2.0)),
+            ],
+        )
+        .unwrap();
+
+        let entity_manager = &registry.entity_manager;
+        assert_eq!(entity_manager.get::<CTag>(&entity).unwrap().tag, "ship");
+        assert_eq!(
+            entity_manager.get::<CTransform2D>(&entity).unwrap().translate,
+            glm::vec2(5.0, 6.0)
+        );
+        assert_eq!(entity_manager.get::<CVelocity2D>(&entity).unwrap().x, 2.0);
+
+        let children = entity_manager.children(&entity);
+        assert_eq!(children.len(), 1);
+        assert_eq!(entity_manager.get::<CTag>(&children[0]).unwrap().tag, "thruster");
+        assert_eq!(
+            entity_manager.get::<CTransform2D>(&children[0]).unwrap().translate,
+            glm::vec2(0.0, -26.0)
+        );
+
+        // instantiated prefabs stay loaded without a handle of their own
+        drop((ship, thruster));
+        registry.asset_manager.flush();
+        assert!(registry.asset_manager.handle::<RPrefab>("ship").is_some());
+        assert!(registry.asset_manager.handle::<RPrefab>("thruster").is_some());
+
+        registry.asset_manager.release_prefabs();
+        registry.asset_manager.flush();
+        assert!(registry.asset_manager.handle::<RPrefab>("ship").is_none());
+
+        // a prefab that fails partway leaves nothing behind
+        registry.entity_manager.flush();
+        let count = registry.entity_manager.count();
+        let broken = PrefabEntity::new()
+            .with(CTag {
+                tag: "broken".into(),
+            })
+            .with_child(PrefabEntity::new().with(CTransform2D::default()))
+            .with_child(PrefabEntity::new().with_override("Missing.x", Value::Float(1.0)));
+        registry
+            .asset_manager
+            .load_asset("broken", RPrefab::new(broken))
+            .unwrap();
+        assert!(matches!(
+            instantiate(&mut registry, "broken", &[]),
+            Err(QPError::UnknownComponent(_))
+        ));
+        registry.entity_manager.flush();
+        assert_eq!(registry.entity_manager.count(), count);
+
+        // a prefab can't contain itself
+        let looping = PrefabEntity::new().with_child(PrefabEntity::instance_of("looping"));
+        registry
+            .asset_manager
+            .load_asset("looping", RPrefab::new(looping))
+            .unwrap();
+        assert!(matches!(
+            instantiate(&mut registry, "looping", &[]),
+            Err(QPError::PrefabCycle(_))
+        ));
+    }
+}
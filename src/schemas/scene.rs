@@ -1,34 +1,147 @@
 use crate::prelude::{
-    qp_core::to_abs_path, qp_schemas::SchemaScene2D, GlobalRegistry, QPError, Schema,
-    VersionedIndex,
+    qp_core::vfs, qp_ecs::components::CScene, qp_schemas::SchemaScene2D, GlobalRegistry, QPError,
+    Schema, VersionedIndex,
 };
 use crate::QPResult;
-use std::{
-    fs::{self, File},
-    io::BufReader,
-};
+use std::fs;
 
 pub fn save_scene_2d(name: &str, scene: VersionedIndex, registry: &GlobalRegistry) -> QPResult<()> {
     if let Some(scene) = SchemaScene2D::from_entity(scene, registry) {
         let str = serde_yaml::to_string(&scene).map_err(|e| QPError::Generic(e.to_string()))?;
-        let path = to_abs_path(&format!("assets/scenes/{}.yaml", name))?;
+        let name = format!("scenes/{}.yaml", name);
+        let Some(path) = vfs().disk_path(&name) else {
+            return Err(QPError::AssetFileNotFound(name));
+        };
 
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
         fs::write(path, str)?;
     } else {
-        #[cfg(debug_assertions)]
-        println!("there was a problem saving the scene");
+        // rather than writing a scene that's missing entities
+        return Err(QPError::Generic(format!("there was a problem saving scene {}", name)));
     }
 
     Ok(())
 }
 
 pub fn load_scene_2d(name: &str, default: SchemaScene2D) -> QPResult<SchemaScene2D> {
-    let path = to_abs_path(&format!("assets/scenes/{}.yaml", name))?;
-    if let Ok(file) = File::open(path) {
-        let reader = BufReader::new(file);
-
-        return Ok(serde_yaml::from_reader(reader).map_err(|e| QPError::Generic(e.to_string()))?);
+    if let Ok(file) = vfs().read(&format!("scenes/{}.yaml", name)) {
+        return serde_yaml::from_slice(&file).map_err(|e| QPError::Generic(e.to_string()));
     }
 
     Ok(default)
 }
+
+/**
+ * rebuilds the entities scenes/<name>.yaml created. Entities spawned at
+ * runtime and assets the scene already loaded are kept. A file that doesn't
+ * parse leaves the world as it was
+ */
+pub fn reload_scene_2d(name: &str, registry: &mut GlobalRegistry) -> QPResult<VersionedIndex> {
+    let file = vfs().read(&format!("scenes/{}.yaml", name))?;
+    let schema: SchemaScene2D =
+        serde_yaml::from_slice(&file).map_err(|e| QPError::Generic(e.to_string()))?;
+
+    rebuild_scene_2d(&schema, registry)
+}
+
+/// despawns whatever an earlier load of the same scene created, then builds it again
+fn rebuild_scene_2d(
+    schema: &SchemaScene2D,
+    registry: &mut GlobalRegistry,
+) -> QPResult<VersionedIndex> {
+    let mut scenes = vec![];
+    for entity in registry.entity_manager.get_valid_entities() {
+        if let Some(scene) = registry.entity_manager.get::<CScene>(&entity) {
+            if registry.strings().get_string(scene.id).as_deref() == Some(schema.name.as_str()) {
+                scenes.push((entity, scene.entities.clone()));
+            }
+        }
+    }
+
+    for (scene, entities) in scenes {
+        for entity in entities {
+            registry.entity_manager.delete_recursive_now(entity);
+        }
+        registry.entity_manager.delete_recursive_now(scene);
+    }
+
+    schema.build_entity(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::qp_ecs::{
+        components::{register_components, CTag},
+        Value,
+    };
+    use crate::prelude::qp_schemas::SchemaEntity;
+    use std::collections::BTreeMap;
+
+    fn tagged(tag: &str) -> SchemaEntity {
+        SchemaEntity {
+            id: VersionedIndex::default(),
+            components: BTreeMap::from([(
+                "CTag".to_string(),
+                Value::Struct(vec![("tag".to_string(), Value::String(tag.into()))]),
+            )]),
+        }
+    }
+
+    fn scene(name: &str, entities: Vec<SchemaEntity>) -> SchemaScene2D {
+        SchemaScene2D {
+            name: name.into(),
+            cameras: vec![],
+            shaders: vec![],
+            textures: vec![],
+            sprites: vec![],
+            entities,
+            prefabs: vec![],
+        }
+    }
+
+    fn tags(registry: &mut GlobalRegistry) -> Vec<String> {
+        let mut tags: Vec<String> = registry
+            .entity_manager
+            .query_iter::<(&CTag,)>()
+            .map(|(_, tag)| tag.tag.clone())
+            .collect();
+        tags.sort();
+
+        tags
+    }
+
+    #[test]
+    fn scene_reload_keeps_runtime_entities() {
+        let mut registry = GlobalRegistry::init().unwrap();
+        register_components(&mut registry);
+
+        rebuild_scene_2d(&scene("level", vec![tagged("wall")]), &mut registry).unwrap();
+        rebuild_scene_2d(&scene("hud", vec![tagged("score")]), &mut registry).unwrap();
+
+        let player = registry.entity_manager.create();
+        registry.entity_manager.add(
+            &player,
+            CTag {
+                tag: "player".into(),
+            },
+        );
+
+        let scene = rebuild_scene_2d(
+            &scene("level", vec![tagged("door"), tagged("floor")]),
+            &mut registry,
+        )
+        .unwrap();
+
+        assert_eq!(tags(&mut registry), ["door", "floor", "player", "score"]);
+        assert_eq!(
+            registry.entity_manager.query_iter::<(&CScene,)>().count(),
+            2
+        );
+        let scene = registry.entity_manager.get::<CScene>(&scene).unwrap();
+        assert_eq!(scene.entities.len(), 2);
+        assert!(registry.entity_manager.is_valid(&player));
+    }
+}
@@ -2,14 +2,17 @@ use serde::{Deserialize, Serialize};
 
 use crate::prelude::{
     qp_ecs::{
-        components::{CScene, CSprite, CTransform2D},
+        components::{CScene, CTransform2D},
         VersionedIndex,
     },
     GlobalRegistry, Schema,
 };
 use crate::QPResult;
 
-use super::prelude::{SchemaCamera2D, SchemaShader, SchemaSprite, SchemaTexture};
+use super::prelude::{
+    build_entities, SchemaCamera2D, SchemaEntity, SchemaPrefabInstance, SchemaShader,
+    SchemaSprite, SchemaTexture,
+};
 
 pub const DEFAULT_SCENE: &str = "default_scene";
 
@@ -23,7 +26,13 @@ pub struct SchemaScene2D {
     pub shaders: Vec<SchemaShader>,
     pub textures: Vec<SchemaTexture>,
 
+    #[serde(default)]
     pub sprites: Vec<SchemaSprite>,
+    /// every other entity, with all of its components
+    #[serde(default)]
+    pub entities: Vec<SchemaEntity>,
+    #[serde(default)]
+    pub prefabs: Vec<SchemaPrefabInstance>,
 }
 
 impl Schema for SchemaScene2D {
@@ -31,24 +40,29 @@ impl Schema for SchemaScene2D {
         // 1. build cameras
         let mut cameras = vec![];
         for camera in self.cameras.iter() {
-            cameras.push(camera.load_resource(registry)?);
+            cameras.push(camera.load(registry)?);
         }
 
         // 2. build shaders
         let mut shaders = vec![];
         for shader in self.shaders.iter() {
-            shaders.push(shader.load_resource(registry)?);
+            shaders.push(shader.load(registry)?);
         }
 
         // 2. build textures
         let mut textures = vec![];
         for texture in self.textures.iter() {
-            textures.push(texture.load_resource(registry)?);
+            textures.push(texture.load(registry)?);
         }
 
-        // 3. build entities
+        // 3. build entities, keeping track of them for when the scene is reloaded
+        let mut entities = vec![];
         for rect in self.sprites.iter() {
-            rect.build_entity(registry)?;
+            entities.push(rect.build_entity(registry)?);
+        }
+        entities.extend(build_entities(&self.entities, registry)?);
+        for prefab in self.prefabs.iter() {
+            entities.push(prefab.build_entity(registry)?);
         }
 
         let id = registry.strings_mut().intern(self.name.clone());
@@ -61,6 +75,7 @@ impl Schema for SchemaScene2D {
                 cameras,
                 shaders,
                 textures,
+                entities,
             },
         );
 
@@ -76,35 +91,47 @@ impl Schema for SchemaScene2D {
                 shaders: vec![],
                 textures: vec![],
                 sprites: vec![],
+                entities: vec![],
+                prefabs: vec![],
             };
 
             // 2. parse the cameras
-            for id in scene.cameras.iter() {
+            for camera in scene.cameras.iter() {
                 schema
                     .cameras
-                    .push(SchemaCamera2D::from_resource(*id, registry)?);
+                    .push(SchemaCamera2D::from_resource(camera.id(), registry)?);
             }
 
             // 2. parse the shaders
-            for id in scene.shaders.iter() {
+            for shader in scene.shaders.iter() {
                 schema
                     .shaders
-                    .push(SchemaShader::from_resource(*id, registry)?);
+                    .push(SchemaShader::from_resource(shader.id(), registry)?);
             }
 
             // 3. parse textures
-            for id in scene.textures.iter() {
+            for texture in scene.textures.iter() {
                 schema
                     .textures
-                    .push(SchemaTexture::from_resource(*id, registry)?);
+                    .push(SchemaTexture::from_resource(texture.id(), registry)?);
             }
 
-            // 4. parse the entities
-            let entities = registry.entity_manager.query_all::<CSprite>();
-            for entity in entities {
-                schema
-                    .sprites
-                    .push(SchemaSprite::from_entity(entity, registry)?);
+            // 4. parse the entities, except for scenes which are rebuilt from their schema
+            for entity in registry.entity_manager.get_valid_entities() {
+                if registry.entity_manager.get::<CScene>(&entity).is_some() {
+                    continue;
+                }
+
+                match SchemaEntity::save(entity, registry) {
+                    Ok(saved) if saved.components.is_empty() => (),
+                    Ok(saved) => schema.entities.push(saved),
+                    Err(_e) => {
+                        #[cfg(debug_assertions)]
+                        println!("[scene] couldn't save entity {}: {}", entity, _e);
+
+                        return None;
+                    }
+                }
             }
 
             return Some(schema);
@@ -133,6 +160,8 @@ impl Default for SchemaScene2D {
             shaders: vec![shader],
             textures: vec![],
             sprites: vec![sprite],
+            entities: vec![],
+            prefabs: vec![],
         }
     }
 }
@@ -2,7 +2,12 @@ use serde::{Deserialize, Serialize};
 
 use crate::prelude::qp_gfx::ShaderUniforms;
 use crate::prelude::Schema;
-use crate::prelude::{qp_assets::RShader, qp_gfx::get_shader, GlobalRegistry};
+use crate::prelude::{
+    qp_assets::{Handle, RShader, UntypedHandle},
+    qp_core::vfs,
+    qp_gfx::get_shader,
+    GlobalRegistry,
+};
 use crate::QPResult;
 
 pub const DEFAULT_SHADER: &str = "sprite";
@@ -26,19 +31,13 @@ impl Default for SchemaShader {
 }
 
 impl Schema for SchemaShader {
-    fn load_resource(&self, registry: &mut GlobalRegistry) -> QPResult<u64> {
-        let shader = get_shader(&self.name);
-        let id = registry.asset_manager.load_asset(
-            &self.name,
-            RShader::from_str(shader.vert, shader.frag, self.uniforms.to_vec())?,
-        )?;
-
-        Ok(id)
+    fn load_resource(&self, registry: &mut GlobalRegistry) -> QPResult<UntypedHandle> {
+        Ok(self.load(registry)?.into())
     }
 
     fn from_resource(id: u64, registry: &GlobalRegistry) -> Option<Self> {
         if let (Some(shader), Some(name)) = (
-            registry.asset_manager.get::<RShader>(id),
+            registry.asset_manager.get_by_id::<RShader>(id),
             registry.strings().get_string(id),
         ) {
             let schema = SchemaShader {
@@ -54,3 +53,24 @@ impl Schema for SchemaShader {
         None
     }
 }
+
+impl SchemaShader {
+    /// shader files in shaders/ take the place of the built in shader with the same name
+    pub fn load(&self, registry: &mut GlobalRegistry) -> QPResult<Handle<RShader>> {
+        let paths = RShader::source_paths(&self.name);
+        if paths.iter().all(|path| vfs().exists(path)) {
+            return RShader::load(
+                &mut registry.asset_manager,
+                &self.name,
+                self.uniforms.to_vec(),
+            );
+        }
+
+        let shader = get_shader(&self.name);
+
+        registry.asset_manager.load_asset(
+            &self.name,
+            RShader::from_str(shader.vert, shader.frag, self.uniforms.to_vec())?,
+        )
+    }
+}
@@ -5,10 +5,10 @@ use crate::QPResult;
 use crate::{
     errors::QPError,
     prelude::{
-        qp_assets::RTexture,
+        qp_assets::{Handle, RTexture},
         qp_ecs::{
-            components::{CQuad, CSprite, CTag, CTransform2D, CVelocity2D},
-            VersionedIndex,
+            components::{CQuad, CSprite, CTag, CTransform2D, CVelocity2D, SpriteBundle},
+            Reflect, VersionedIndex,
         },
         GlobalRegistry,
     },
@@ -31,15 +31,15 @@ impl Schema for SchemaSprite {
     fn build_entity(&self, registry: &mut GlobalRegistry) -> QPResult<VersionedIndex> {
         let texture_atlas = match &self.texture {
             Some(id_as_str) => {
-                let Some(id) = registry.asset_manager.get_asset_id(&id_as_str) else {
+                let Some(handle) = registry.asset_manager.handle::<RTexture>(id_as_str) else {
                     return Err(QPError::SpriteTextureDoesntExist);
                 };
 
-                let texture = registry.asset_manager.get::<RTexture>(id).unwrap();
+                let texture_dims = registry.asset_manager.get(&handle).unwrap().texture_dims;
 
                 Some(TextureAtlas {
-                    texture: id,
-                    texture_dims: texture.texture_dims,
+                    texture: handle,
+                    texture_dims,
                     active_texture: glm::vec2(0.0, 0.0),
                 })
             }
@@ -49,19 +49,16 @@ impl Schema for SchemaSprite {
         let entity = registry.entity_manager.create();
         registry.entity_manager.add(
             &entity,
-            CTag {
-                tag: self.tag.clone(),
+            SpriteBundle {
+                tag: CTag {
+                    tag: self.tag.clone(),
+                },
+                transform: self.transform,
+                quad: self.quad.clone(),
+                sprite: CSprite::new(&self.quad, Some(self.color), texture_atlas),
+                velocity: self.velocity,
             },
         );
-        if let Some(velocity) = self.velocity {
-            registry.entity_manager.add(&entity, velocity);
-        }
-        registry.entity_manager.add(&entity, self.quad.clone());
-        registry.entity_manager.add(&entity, self.transform);
-        registry.entity_manager.add(
-            &entity,
-            CSprite::new(&self.quad, Some(self.color), texture_atlas),
-        );
 
         Ok(entity)
     }
@@ -81,7 +78,7 @@ impl Schema for SchemaSprite {
                 transform: transform.clone(),
                 quad: quad.clone(),
                 texture: match &sprite.texture_atlas {
-                    Some(atlas) => registry.strings().get_string(atlas.texture),
+                    Some(atlas) => registry.strings().get_string(atlas.texture.id()),
                     None => None,
                 },
                 color: sprite.color,
@@ -112,9 +109,9 @@ impl Default for SchemaSprite {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Reflect, Serialize, Deserialize, Clone, PartialEq, Default)]
 pub struct TextureAtlas {
-    pub texture: u64,
+    pub texture: Handle<RTexture>,
     pub texture_dims: glm::Vec2,
     pub active_texture: glm::Vec2,
 }
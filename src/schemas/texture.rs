@@ -1,8 +1,9 @@
 use crate::{
     platform::opengl::textures::{ParameterName, ParameterValue},
     prelude::{
-        qp_assets::RTexture, qp_core::to_abs_path, qp_gfx::texture::from_image, GlobalRegistry,
-        Schema,
+        qp_assets::{Handle, RTexture, UntypedHandle},
+        qp_gfx::texture::{decode_image, from_decoded, DecodedImage},
+        GlobalRegistry, Schema,
     },
     QPResult,
 };
@@ -15,30 +16,13 @@ pub struct SchemaTexture {
 }
 
 impl Schema for SchemaTexture {
-    fn load_resource(&self, registry: &mut GlobalRegistry) -> QPResult<u64> {
-        let path = format!("assets/textures/{}", self.name);
-
-        let texture = from_image(&to_abs_path(&path)?)?;
-        texture
-            .set_parameter(ParameterName::WrapS, ParameterValue::ClampToEdge)
-            .set_parameter(ParameterName::WrapT, ParameterValue::ClampToEdge)
-            .set_parameter(ParameterName::MinFilter, ParameterValue::Linear)
-            .set_parameter(ParameterName::MagFilter, ParameterValue::Nearest);
-
-        let id = registry.asset_manager.load_asset(
-            &self.name,
-            RTexture {
-                texture,
-                texture_dims: self.texture_dims,
-            },
-        )?;
-
-        Ok(id)
+    fn load_resource(&self, registry: &mut GlobalRegistry) -> QPResult<UntypedHandle> {
+        Ok(self.load(registry)?.into())
     }
 
     fn from_resource(id: u64, registry: &GlobalRegistry) -> Option<Self> {
         if let (Some(texture), Some(name)) = (
-            registry.asset_manager.get::<RTexture>(id),
+            registry.asset_manager.get_by_id::<RTexture>(id),
             registry.strings().get_string(id),
         ) {
             let schema = SchemaTexture {
@@ -54,3 +38,47 @@ impl Schema for SchemaTexture {
         None
     }
 }
+
+impl SchemaTexture {
+    /// reloads when the image changes and hot reloading is on
+    pub fn load(&self, registry: &mut GlobalRegistry) -> QPResult<Handle<RTexture>> {
+        let path = format!("textures/{}", self.name);
+        let texture_dims = self.texture_dims;
+
+        registry
+            .asset_manager
+            .load_watched(&self.name, vec![path.clone().into()], move || {
+                read_texture(&path, texture_dims)
+            })
+    }
+
+    /// decodes the image on a loader thread, the texture is uploaded by a later `AssetManager::finish_loads`
+    pub fn load_async(&self, registry: &mut GlobalRegistry) -> QPResult<Handle<RTexture>> {
+        let path = format!("textures/{}", self.name);
+        let texture_dims = self.texture_dims;
+
+        registry.asset_manager.load_async(
+            &self.name,
+            move || decode_image(&path),
+            move |image| Ok(upload_texture(&image, texture_dims)),
+        )
+    }
+}
+
+fn read_texture(path: &str, texture_dims: glm::Vec2) -> QPResult<RTexture> {
+    Ok(upload_texture(&decode_image(path)?, texture_dims))
+}
+
+fn upload_texture(image: &DecodedImage, texture_dims: glm::Vec2) -> RTexture {
+    let texture = from_decoded(image);
+    texture
+        .set_parameter(ParameterName::WrapS, ParameterValue::ClampToEdge)
+        .set_parameter(ParameterName::WrapT, ParameterValue::ClampToEdge)
+        .set_parameter(ParameterName::MinFilter, ParameterValue::Linear)
+        .set_parameter(ParameterName::MagFilter, ParameterValue::Nearest);
+
+    RTexture {
+        texture,
+        texture_dims,
+    }
+}
@@ -1,29 +1,41 @@
+mod snapshot;
+
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+};
+
 use sdl2::event::Event;
 
 use crate::{
-    core::prelude::{random::Random, Timer},
+    core::prelude::{random::Random, AnyMap, Interval, Timer},
     platform::sdl2::QPWindow,
     prelude::{
-        qp_ecs::components::register_components,
+        qp_assets::AssetEvent,
+        qp_ecs::{components::register_components, Commands},
         qp_gfx::{QPText, Viewport},
+        qp_schemas::reload_scene_2d,
     },
     registry::GlobalRegistry,
     QPResult,
 };
 
+use snapshot::SnapshotResource;
+pub use snapshot::WorldSnapshot;
+
 pub struct World {
     pub registry: GlobalRegistry,
-    pub debug_info: DebugInfo,
+    pub commands: Commands,
+    pub resources: AnyMap,
     pub debug_mode: bool,
+    /// what a restart does with the loaded assets
+    pub reset_mode: ResetMode,
 
-    pub events: Vec<Event>,
-    pub text_buffer: Vec<QPText>,
-
-    pub viewport: Viewport,
-
-    pub delta: f32,
     timer: Timer,
-    pub rand: Random,
+    // how often the watched asset files are checked
+    reload_interval: Interval,
+    snapshot_resources: HashMap<TypeId, SnapshotResource>,
 }
 
 impl World {
@@ -32,45 +44,133 @@ impl World {
         register_components(&mut registry);
 
         let mut timer = Timer::new();
-        let delta = timer.delta();
+
+        let mut resources = AnyMap::new();
+        resources.insert(Delta(timer.delta()));
+        resources.insert(Random::from_seed(seed));
+        resources.insert(DebugInfo::default());
+        resources.insert(Events::default());
+        resources.insert(AssetEvents::default());
+        resources.insert(TextBuffer::default());
+        resources.insert(viewport);
 
         Ok(Self {
             registry,
+            commands: Commands::new(),
+            resources,
+            debug_mode: false,
+            reset_mode: ResetMode::default(),
             timer,
-            delta,
-            rand: Random::from_seed(seed),
+            reload_interval: Interval::new(0.5),
+            snapshot_resources: HashMap::new(),
+        })
+    }
 
-            debug_info: DebugInfo::default(),
-            debug_mode: false,
+    pub fn insert_resource<R: 'static>(&mut self, resource: R) {
+        self.resources.insert(resource);
+    }
 
-            events: vec![],
-            text_buffer: vec![],
+    pub fn remove_resource<R: 'static>(&mut self) -> Option<R> {
+        self.resources.remove::<R>()
+    }
 
-            viewport,
-        })
+    /**
+     * panics if the resource was never inserted.
+     * Use `world.resources.get` when it might not be there
+     */
+    pub fn resource<R: 'static>(&self) -> &R {
+        match self.resources.get::<R>() {
+            Some(resource) => resource,
+            None => panic!("[world] resource {} does not exist", std::any::type_name::<R>()),
+        }
+    }
+
+    pub fn resource_mut<R: 'static>(&mut self) -> &mut R {
+        match self.resources.get_mut::<R>() {
+            Some(resource) => resource,
+            None => panic!("[world] resource {} does not exist", std::any::type_name::<R>()),
+        }
     }
 
-    pub fn reset(&self) {
-        todo!()
+    /**
+     * despawns every entity and clears the queued commands and per-frame
+     * resources. Assets are unloaded as well with `ResetMode::ReloadAssets`,
+     * which drops their GL objects. Use `FrameResult::Restart` from a
+     * controller to also run the startup systems again
+     */
+    pub fn reset(&mut self) -> QPResult<()> {
+        self.commands = Commands::new();
+
+        self.registry.entity_manager.reset()?;
+        if self.reset_mode == ResetMode::ReloadAssets {
+            self.registry.asset_manager.reset()?;
+        }
+        self.registry.flush();
+
+        self.resource_mut::<Events>().0.clear();
+        self.resource_mut::<AssetEvents>().0.clear();
+        self.resource_mut::<TextBuffer>().clear();
+        self.resource_mut::<Delta>().0 = 0.0;
+        *self.resource_mut::<DebugInfo>() = DebugInfo::default();
+
+        Ok(())
     }
 
     pub fn new_frame(&mut self, winapi: &mut QPWindow) -> QPResult<()> {
-        self.events = winapi.get_event_queue()?;
-        self.delta = self.timer.delta();
+        let delta = self.timer.delta();
+
+        self.resource_mut::<Events>().0 = winapi.get_event_queue()?;
+        self.resource_mut::<Delta>().0 = delta;
 
-        self.debug_info.fps = (1.0 / self.delta) as u32;
-        self.debug_info.frame_ms = (self.delta * 1000.0) as u32;
+        let debug_info = self.resource_mut::<DebugInfo>();
+        debug_info.fps = (1.0 / delta) as u32;
+        debug_info.frame_ms = (delta * 1000.0) as u32;
 
         Ok(())
     }
 
+    /**
+     * uploads the background loads that finished, reloads the watched
+     * assets that changed and rebuilds scenes whose file changed. Watching
+     * only happens once hot reloading was turned on with
+     * `AssetManager::set_hot_reload`
+     */
+    pub fn update_assets(&mut self) {
+        let mut events = self.registry.asset_manager.finish_loads();
+
+        if self.registry.asset_manager.hot_reload() && self.reload_interval.check() {
+            events.extend(self.registry.asset_manager.poll_changes());
+        }
+
+        for event in events.iter() {
+            if let AssetEvent::SceneChanged { name } = event {
+                if let Err(_e) = reload_scene_2d(name, &mut self.registry) {
+                    #[cfg(debug_assertions)]
+                    println!("[world] failed to reload scene {}: {}", name, _e);
+                }
+            }
+        }
+
+        self.resource_mut::<AssetEvents>().0 = events;
+    }
+
     pub fn flush(&mut self) {
+        self.commands.apply(&mut self.registry);
         self.registry.flush();
 
-        self.text_buffer.clear();
+        self.resource_mut::<TextBuffer>().clear();
     }
 }
 
+/// what `World::reset` does with the loaded assets
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ResetMode {
+    #[default]
+    KeepAssets,
+    /// unloads every asset, the startup systems are expected to load them again
+    ReloadAssets,
+}
+
 #[derive(Debug, Default)]
 pub struct DebugInfo {
     pub fps: u32,
@@ -81,3 +181,58 @@ pub struct DebugInfo {
     pub render_ms: u32,
     pub draw_calls: u32,
 }
+
+/// seconds since the last frame
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Delta(pub f32);
+
+/// the window events polled at the start of the frame
+#[derive(Debug, Default, Clone)]
+pub struct Events(pub Vec<Event>);
+
+/// the assets that finished loading or were reloaded this frame
+#[derive(Debug, Default, Clone)]
+pub struct AssetEvents(pub Vec<AssetEvent>);
+
+/// text queued for the text renderer. Cleared every frame
+#[derive(Debug, Default)]
+pub struct TextBuffer(pub Vec<QPText>);
+
+impl Deref for Delta {
+    type Target = f32;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Deref for Events {
+    type Target = Vec<Event>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Deref for AssetEvents {
+    type Target = Vec<AssetEvent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Deref for TextBuffer {
+    type Target = Vec<QPText>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for TextBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
@@ -0,0 +1,245 @@
+use std::{any::TypeId, collections::BTreeMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use super::World;
+use crate::{
+    core::prelude::{
+        random::{Random, RandomState},
+        AnyMap,
+    },
+    prelude::{
+        qp_ecs::{Commands, Reflect, Value},
+        qp_schemas::{restore_entities, SchemaEntity},
+        QPError,
+    },
+    QPResult,
+};
+
+/**
+ * The state of a world at one point in time: every entity with all of
+ * its components, the snapshot resources, the random generator and the
+ * timer. Assets aren't included
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub entities: Vec<SchemaEntity>,
+    /// keyed by the resource's reflected type name
+    #[serde(default)]
+    pub resources: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub random: Option<RandomState>,
+    /// seconds on the world's timer
+    #[serde(default)]
+    pub elapsed: f32,
+}
+
+impl WorldSnapshot {
+    pub fn to_bytes(&self) -> QPResult<Vec<u8>> {
+        serde_yaml::to_string(self)
+            .map(String::into_bytes)
+            .map_err(|e| QPError::Generic(e.to_string()))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> QPResult<Self> {
+        serde_yaml::from_slice(bytes).map_err(|e| QPError::Generic(e.to_string()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> QPResult<()> {
+        fs::write(path, self.to_bytes()?)?;
+
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> QPResult<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+/// reaches a resource in the world's `AnyMap` through reflection
+#[derive(Clone, Copy)]
+pub(super) struct SnapshotResource {
+    get: fn(&AnyMap) -> Option<&dyn Reflect>,
+    get_mut: fn(&mut AnyMap) -> Option<&mut dyn Reflect>,
+}
+
+impl SnapshotResource {
+    fn of<R: Reflect + 'static>() -> Self {
+        Self {
+            get: |resources| resources.get::<R>().map(|r| r as &dyn Reflect),
+            get_mut: |resources| resources.get_mut::<R>().map(|r| r as &mut dyn Reflect),
+        }
+    }
+}
+
+impl World {
+    /// saves the resource in snapshots whenever it's inserted
+    pub fn register_snapshot_resource<R: Reflect + 'static>(&mut self) {
+        self.snapshot_resources
+            .insert(TypeId::of::<R>(), SnapshotResource::of::<R>());
+    }
+
+    /// fails if a component couldn't be restored, see `SchemaEntity::save`
+    pub fn snapshot(&self) -> QPResult<WorldSnapshot> {
+        let entities = self
+            .registry
+            .entity_manager
+            .get_valid_entities()
+            .into_iter()
+            .map(|entity| SchemaEntity::save(entity, &self.registry))
+            .collect::<QPResult<_>>()?;
+
+        let resources = self
+            .snapshot_resources
+            .values()
+            .filter_map(|resource| (resource.get)(&self.resources))
+            .map(|resource| (resource.type_name().to_string(), resource.to_value()))
+            .collect();
+
+        Ok(WorldSnapshot {
+            entities,
+            resources,
+            random: self.resources.get::<Random>().map(Random::state),
+            elapsed: self.timer.elapsed(),
+        })
+    }
+
+    /**
+     * puts the world back the way it was when the snapshot was taken.
+     * Entities keep their ids, so ones held by controllers stay valid.
+     * Queued commands are dropped
+     */
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) -> QPResult<()> {
+        self.commands = Commands::new();
+        let _handles = self.registry.asset_manager.bind_handles();
+
+        restore_entities(&snapshot.entities, &mut self.registry)?;
+
+        for resource in self.snapshot_resources.values() {
+            let Some(resource) = (resource.get_mut)(&mut self.resources) else {
+                continue;
+            };
+
+            if let Some(value) = snapshot.resources.get(resource.type_name()) {
+                resource.set_value(value.clone())?;
+            }
+        }
+
+        if let Some(state) = &snapshot.random {
+            self.insert_resource(Random::from_state(state));
+        }
+
+        self.timer.set_elapsed(snapshot.elapsed);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        qp_ecs::{
+            components::{CParent, CTag, CTransform2D},
+            Component, Reflect,
+        },
+        qp_gfx::Viewport,
+        VersionedIndex,
+    };
+
+    #[derive(Reflect, Debug, Default, PartialEq)]
+    struct Score {
+        points: u32,
+    }
+
+    // no default, it's built from its saved fields
+    #[derive(Component, Debug, PartialEq)]
+    struct Hull {
+        armor: Option<u32>,
+        docked_at: VersionedIndex,
+        #[reflect(ignore)]
+        damage: Vec<f32>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Channel(u32);
+
+    // nothing to build the ignored field from
+    #[derive(Component, Debug, PartialEq)]
+    struct Engine {
+        #[reflect(ignore)]
+        channel: Channel,
+    }
+
+    #[test]
+    fn world_snapshot_and_restore() {
+        let mut world = World::new(Viewport::default(), 42).unwrap();
+        world.register_snapshot_resource::<Score>();
+        world.insert_resource(Score { points: 10 });
+
+        let entity_manager = &mut world.registry.entity_manager;
+        let ship = entity_manager.create();
+        let turret = entity_manager.create();
+        entity_manager.add(&ship, CTag { tag: "ship".into() });
+        entity_manager.add(&ship, CTransform2D::default());
+        entity_manager.set_parent(&turret, &ship);
+        entity_manager.add(
+            &turret,
+            Hull {
+                armor: Some(3),
+                docked_at: ship,
+                damage: vec![0.5],
+            },
+        );
+
+        let bytes = world.snapshot().unwrap().to_bytes().unwrap();
+        let snapshot = WorldSnapshot::from_bytes(&bytes).unwrap();
+        let rolls: Vec<f32> = (0..3).map(|_| world.resource_mut::<Random>().random()).collect();
+
+        // play on
+        world.resource_mut::<Score>().points = 99;
+        let entity_manager = &mut world.registry.entity_manager;
+        entity_manager.get_mut::<CTransform2D>(&ship).unwrap().translate.x = 5.0;
+        entity_manager.set_to_delete(turret);
+        world.registry.flush();
+        let stray = world.registry.entity_manager.create();
+
+        world.restore(&snapshot).unwrap();
+
+        let entity_manager = &world.registry.entity_manager;
+        assert_eq!(entity_manager.count(), 2);
+        assert!(!entity_manager.is_valid(&stray));
+        assert_eq!(entity_manager.get::<CTag>(&ship).unwrap().tag, "ship");
+        assert_eq!(entity_manager.get::<CTransform2D>(&ship).unwrap().translate.x, 0.0);
+        assert_eq!(entity_manager.get::<CParent>(&turret), Some(&CParent(ship)));
+        assert_eq!(
+            entity_manager.get::<Hull>(&turret),
+            Some(&Hull {
+                armor: Some(3),
+                docked_at: ship,
+                damage: vec![],
+            })
+        );
+        assert_eq!(world.resource::<Score>(), &Score { points: 10 });
+
+        let replayed: Vec<f32> = (0..3).map(|_| world.resource_mut::<Random>().random()).collect();
+        assert_eq!(replayed, rolls);
+
+        // new entities don't collide with the restored ones
+        let spawned = world.registry.entity_manager.create();
+        assert!(spawned != ship && spawned != turret);
+        assert_eq!(world.registry.entity_manager.count(), 3);
+
+        let path = std::env::temp_dir().join("quipi_world_snapshot.yaml");
+        snapshot.save(&path).unwrap();
+        assert_eq!(WorldSnapshot::load(&path).unwrap().to_bytes().unwrap(), bytes);
+        std::fs::remove_file(path).unwrap();
+
+        // a component that couldn't be restored fails the snapshot instead of being left out
+        world.registry.entity_manager.add(&ship, Engine { channel: Channel(1) });
+        assert!(matches!(
+            world.snapshot(),
+            Err(QPError::ComponentNotConstructible(_))
+        ));
+    }
+}